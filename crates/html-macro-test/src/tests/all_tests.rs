@@ -7,7 +7,10 @@
 
 use html_macro::html;
 use std::collections::HashMap;
-use virtual_node::{AttributeValue, IterableNodes, VElement, VText, View, VirtualNode};
+use virtual_node::{
+    assert_vnode_eq, AttributeValue, Classes, IterableNodes, NodeRef, PortalTarget, PropertyValue,
+    Styles, VElement, VText, View, VirtualNode,
+};
 
 #[must_use]
 pub(crate) struct HtmlMacroTest {
@@ -18,7 +21,7 @@ pub(crate) struct HtmlMacroTest {
 impl HtmlMacroTest {
     /// Ensure that the generated and the expected virtual node are equal.
     pub fn test(self) {
-        assert_eq!(self.generated, self.expected);
+        assert_vnode_eq!(self.generated, self.expected);
     }
 }
 
@@ -208,6 +211,171 @@ fn hyphenated_attribute() {
     );
 }
 
+/// Verify that `prop:name=value` sets a DOM property instead of an attribute.
+#[test]
+fn properties() {
+    let scroll_top = 100;
+
+    let mut expected = VElement::new("div");
+    expected
+        .properties
+        .insert("scrollTop".to_string(), PropertyValue::Number(100.));
+    expected
+        .properties
+        .insert("title".to_string(), PropertyValue::String("Hi".to_string()));
+    expected.classes.insert("list");
+
+    HtmlMacroTest {
+        generated: html! { <div prop:scrollTop=scroll_top prop:title="Hi" class="list"></div> },
+        expected: expected.into(),
+    }
+    .test();
+}
+
+/// Verify that the class attribute accepts a string, an array or a `Vec`, and becomes the
+/// element's classes instead of an attribute.
+#[test]
+fn classes() {
+    let active = true;
+
+    let expected = || {
+        let mut div = VElement::new("div");
+        div.classes = Classes::from(["btn", "primary", "active"]);
+        VirtualNode::from(div)
+    };
+
+    HtmlMacroTest {
+        generated: html! { <div class="btn primary active"></div> },
+        expected: expected(),
+    }
+    .test();
+
+    HtmlMacroTest {
+        generated: html! { <div class=["btn primary", if active { "active" } else { "" }]></div> },
+        expected: expected(),
+    }
+    .test();
+
+    HtmlMacroTest {
+        generated: html! { <div class=vec!["active".to_string(), "btn primary".to_string()]></div> },
+        expected: expected(),
+    }
+    .test();
+}
+
+/// Verify that the style attribute accepts a string or property/value pairs, and becomes the
+/// element's styles instead of an attribute.
+#[test]
+fn styles() {
+    let width = format!("{}px", 10);
+
+    let expected = || {
+        let mut div = VElement::new("div");
+        div.styles = Styles::from([("color", "red"), ("width", "10px")]);
+        VirtualNode::from(div)
+    };
+
+    HtmlMacroTest {
+        generated: html! { <div style="color: red; width: 10px;"></div> },
+        expected: expected(),
+    }
+    .test();
+
+    HtmlMacroTest {
+        generated: html! { <div style=[("color", "red"), ("width", width.as_str())]></div> },
+        expected: expected(),
+    }
+    .test();
+}
+
+/// Verify that namespaced attribute keys such as `xlink:href` are kept intact.
+#[test]
+fn namespaced_attributes() {
+    let icon = "#icon-save";
+
+    let mut expected = VElement::new("use");
+    expected.attrs.insert(
+        "xlink:href".to_string(),
+        AttributeValue::String(icon.to_string()),
+    );
+    expected.attrs.insert(
+        "xml:lang".to_string(),
+        AttributeValue::String("en".to_string()),
+    );
+
+    HtmlMacroTest {
+        generated: html! { <use xlink:href=icon xml:lang="en" /> },
+        expected: expected.into(),
+    }
+    .test();
+}
+
+/// Verify that custom element names, customized built-in elements and attribute keys with many
+/// hyphens are supported.
+#[test]
+fn custom_elements() {
+    let mut button = VElement::new("button");
+    button.attrs.insert(
+        "is".to_string(),
+        AttributeValue::String("fancy-button".to_string()),
+    );
+
+    let mut expected = VElement::new("my-widget");
+    expected.attrs.insert(
+        "data-user-id".to_string(),
+        AttributeValue::String("5".to_string()),
+    );
+    expected.children = vec![button.into(), VirtualNode::element("x-2d-canvas")];
+
+    HtmlMacroTest {
+        generated: html! {
+            <my-widget data-user-id="5">
+              <button is="fancy-button"></button>
+              <x-2d-canvas></x-2d-canvas>
+            </my-widget>
+        },
+        expected: expected.into(),
+    }
+    .test();
+}
+
+/// Verify that a declarative shadow root can be placed into a custom element.
+#[test]
+fn declarative_shadow_root() {
+    let mut template = VElement::new("template");
+    template.attrs.insert(
+        "shadowrootmode".to_string(),
+        AttributeValue::String("open".to_string()),
+    );
+    template.children = vec![VirtualNode::element("slot")];
+
+    let mut expected = VElement::new("my-widget");
+    expected.children = vec![template.into(), VirtualNode::text("Light DOM")];
+
+    HtmlMacroTest {
+        generated: html! {
+            <my-widget><template shadowrootmode="open"><slot></slot></template>Light DOM</my-widget>
+        },
+        expected: expected.into(),
+    }
+    .test();
+}
+
+/// Verify that a closure with a typed argument is not mistaken for a prefixed attribute key.
+#[test]
+fn property_after_typed_closure() {
+    let node: VirtualNode = html! {
+        <input onclick=|_event: virtual_node::event::MouseEvent| {} prop:indeterminate=true />
+    };
+    let element = node.as_velement_ref().unwrap();
+
+    assert_eq!(
+        element.properties.get("indeterminate"),
+        Some(&PropertyValue::Bool(true))
+    );
+    assert!(element.attrs.is_empty());
+}
+
 /// For unquoted text apostrophes should be parsed correctly
 #[test]
 fn apostrophe() {
@@ -342,7 +510,7 @@ fn custom_component_props() {
 
     let mut expected = VElement::new("div");
     let mut child = VElement::new("span");
-    child.children = vec![VirtualNode::text("Counter="), VirtualNode::text("1")];
+    child.children = vec![VirtualNode::text("Counter = "), VirtualNode::text("1")];
     expected.children = vec![child.into()];
 
     HtmlMacroTest {
@@ -354,6 +522,30 @@ fn custom_component_props() {
     .test();
 }
 
+/// Verify that a component can be given more than one prop.
+#[test]
+fn custom_component_multiple_props() {
+    struct Labeled {
+        label: &'static str,
+        count: u8,
+    }
+
+    impl View for Labeled {
+        fn render(&self) -> VirtualNode {
+            let text = format!("{} = {}", self.label, self.count);
+            html! { <span>{text}</span> }
+        }
+    }
+
+    HtmlMacroTest {
+        generated: html! {
+          <div><Labeled label="Total" count={2} /></div>
+        },
+        expected: html! { <div><span>{"Total = 2"}</span></div> },
+    }
+    .test();
+}
+
 #[test]
 fn custom_component_children() {
     struct Child;
@@ -432,3 +624,79 @@ fn numbers() {
     }
     .test()
 }
+
+/// Verify that `<>` and `</>` create a fragment.
+#[test]
+fn fragment_root() {
+    HtmlMacroTest {
+        generated: html! { <> <li></li> <li>Two</li> </> },
+        expected: VirtualNode::fragment(vec![VirtualNode::element("li"), html! { <li>Two</li> }]),
+    }
+    .test()
+}
+
+/// Verify that a fragment's children are flattened into the fragment's parent.
+#[test]
+fn nested_fragment_is_flattened() {
+    HtmlMacroTest {
+        generated: html! { <ul> <> <li></li> <li></li> </> <li></li> </ul> },
+        expected: html! { <ul> <li></li> <li></li> <li></li> </ul> },
+    }
+    .test()
+}
+
+/// Verify that a fragment within a block, such as one returned by a component, is flattened into
+/// the block's parent.
+#[test]
+fn braced_fragment_is_flattened() {
+    struct Term;
+
+    impl View for Term {
+        fn render(&self) -> VirtualNode {
+            html! { <> <dt></dt> <dd></dd> </> }
+        }
+    }
+
+    let terms = vec![Term, Term];
+
+    HtmlMacroTest {
+        generated: html! { <dl> <Term /> {terms} </dl> },
+        expected: html! { <dl> <dt></dt> <dd></dd> <dt></dt> <dd></dd> <dt></dt> <dd></dd> </dl> },
+    }
+    .test()
+}
+
+/// Verify that the `ref` attribute sets the element's `NodeRef` instead of an attribute, and that
+/// the same `NodeRef` can be passed to more than one render.
+#[test]
+fn node_ref() {
+    let node_ref = NodeRef::new();
+
+    for _ in 0..2 {
+        let node = html! { <input ref=node_ref /> };
+        let element = node.as_velement_ref().unwrap();
+
+        assert_eq!(element.special_attributes.node_ref(), Some(&node_ref));
+        assert!(element.attrs.is_empty());
+    }
+}
+
+/// Verify that the `portal` attribute sets the element's portal target instead of an attribute.
+#[test]
+fn portal() {
+    let modals = String::from("#modals");
+
+    let node = html! { <div portal="body"></div> };
+    let element = node.as_velement_ref().unwrap();
+    assert_eq!(
+        element.special_attributes.portal(),
+        Some(&PortalTarget::Selector("body".to_string()))
+    );
+    assert!(element.attrs.is_empty());
+
+    let node = html! { <div portal=modals></div> };
+    assert_eq!(
+        node.as_velement_ref().unwrap().special_attributes.portal(),
+        Some(&PortalTarget::Selector("#modals".to_string()))
+    );
+}
@@ -31,6 +31,22 @@ fn store_unsupported_events_on_non_wasm32_targets_if_no_args() {
     assert!(matches!(handler, EventHandler::NoArgs(_)));
 }
 
+/// Verify that we can listen to the custom events that custom elements dispatch, such as
+/// `sl-after-show`.
+#[test]
+fn custom_event_listener() {
+    let node: VirtualNode = html! {
+        <sl-dialog onsl-after-show = || {}></sl-dialog>
+    };
+    let handler = node
+        .as_velement_ref()
+        .unwrap()
+        .events
+        .get(&"onsl-after-show".into())
+        .unwrap();
+    assert!(matches!(handler, EventHandler::NoArgs(_)));
+}
+
 /// We don't store unsupported events in non wasm32 targets.. but we still want the variables that
 /// these events capture to be considered used.
 /// We test this using `[#deny(unused)]` on the variable.
@@ -44,20 +60,114 @@ fn closure_moved_variables_used() {
     };
 }
 
-/// Verify that onclick events are stored.
+/// Verify that onclick events are stored, on every target.
 #[test]
 fn stores_onclick_events() {
-    let node: VirtualNode = html! {
+    let typed: VirtualNode = html! {
         <button onclick = |_: virtual_node::event::MouseEvent| {}> </button>
     };
+    // The macro adds the type to the closure's argument.
+    let untyped: VirtualNode = html! {
+        <button onclick = |_event| {}> </button>
+    };
 
-    let event = node
-        .as_velement_ref()
-        .unwrap()
-        .events
-        .get(&"onclick".into())
+    for node in [typed, untyped] {
+        let event = node
+            .as_velement_ref()
+            .unwrap()
+            .events
+            .get(&"onclick".into())
+            .unwrap();
+        assert!(matches!(event, EventHandler::MouseEvent(_)));
+    }
+}
+
+/// Verify that keyboard, input, focus, pointer, drag, wheel and submit events are stored using
+/// their typed event handler, on every target.
+#[test]
+fn stores_typed_events() {
+    let node: VirtualNode = html! {
+        <form
+          onkeydown = |_event| {}
+          oninput = |_: percy_dom::event::InputEvent| {}
+          onfocusin = |_event| {}
+          onpointerdown = |_event| {}
+          ondrop = |_event| {}
+          onwheel = |_event| {}
+          onsubmit = |_event| {}
+        >
+        </form>
+    };
+    let events = &node.as_velement_ref().unwrap().events;
+
+    assert!(matches!(
+        events.get(&"onkeydown".into()).unwrap(),
+        EventHandler::KeyboardEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"oninput".into()).unwrap(),
+        EventHandler::InputEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"onfocusin".into()).unwrap(),
+        EventHandler::FocusEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"onpointerdown".into()).unwrap(),
+        EventHandler::PointerEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"ondrop".into()).unwrap(),
+        EventHandler::DragEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"onwheel".into()).unwrap(),
+        EventHandler::WheelEvent(_)
+    ));
+    assert!(matches!(
+        events.get(&"onsubmit".into()).unwrap(),
+        EventHandler::SubmitEvent(_)
+    ));
+}
+
+/// Verify that event modifiers, such as the `.prevent` in `onsubmit.prevent`, are stored in the
+/// event's name.
+#[test]
+fn stores_event_modifiers() {
+    let node: VirtualNode = html! {
+        <form onsubmit.prevent = || {} onclick.capture.once = |_event| {}>
+        </form>
+    };
+    let events = &node.as_velement_ref().unwrap().events;
+
+    let (submit_name, submit) = events
+        .iter()
+        .find(|(name, _)| name.with_on_prefix() == "onsubmit")
         .unwrap();
-    assert!(matches!(event, EventHandler::MouseEvent(_)));
+    assert!(submit_name.options().prevent_default);
+    assert!(matches!(submit, EventHandler::NoArgs(_)));
+
+    let click = events.get(&"onclick.capture.once".into()).unwrap();
+    assert!(matches!(click, EventHandler::MouseEvent(_)));
+}
+
+/// Verify that window and document listeners, such as `window:onresize`, are stored with their
+/// listener target.
+#[test]
+fn stores_window_and_document_events() {
+    let node: VirtualNode = html! {
+        <div window:onresize = || {} document:onkeydown.prevent = |_event| {}>
+        </div>
+    };
+    let events = &node.as_velement_ref().unwrap().events;
+
+    let resize = events.get(&"window:onresize".into()).unwrap();
+    assert!(matches!(resize, EventHandler::NoArgs(_)));
+
+    let keydown = events.get(&"document:onkeydown.prevent".into()).unwrap();
+    assert!(matches!(keydown, EventHandler::KeyboardEvent(_)));
+
+    assert!(events.get(&"onresize".into()).is_none());
 }
 
 /// Verify that we can set the on create element function.
@@ -75,6 +185,31 @@ fn on_create_element() {
     );
 }
 
+/// We don't store on create or on remove element functions that take an argument in non wasm32
+/// targets.. but we still want the variables that they capture to be considered used.
+#[test]
+#[deny(unused_variables)]
+fn element_fn_with_arg_uses_captured_variables() {
+    let created = "created";
+    let removed = "removed";
+
+    let node: VirtualNode = html! {
+        <div
+          key="my-key"
+          on_create_element=move |_: u8| { let _len = created.len(); }
+          on_remove_element=move |_: u8| { let _len = removed.len(); }
+        >
+        </div>
+    };
+    assert_eq!(
+        node.as_velement_ref()
+            .unwrap()
+            .special_attributes
+            .on_create_element_key(),
+        Some(&"my-key".into())
+    );
+}
+
 /// Verify that we can set the on remove element function.
 #[test]
 fn on_remove_element() {
@@ -89,7 +89,7 @@ fn text_multiple_text_no_space_between() {
 
     assert_eq!(
         &html! { <div>{ hello }{ world }</div> }.to_string(),
-        "<div>HelloWorld</div>"
+        "<div>Hello<!--percy:ptns-->World</div>"
     )
 }
 
@@ -136,7 +136,7 @@ fn text_tokens_in_between_vars_without_space() {
 
     assert_eq!(
         &html! { <div>{ hello }NoSpace{ world }</div> }.to_string(),
-        "<div>HelloNoSpaceWorld</div>"
+        "<div>Hello<!--percy:ptns-->NoSpace<!--percy:ptns-->World</div>"
     )
 }
 
@@ -0,0 +1,14 @@
+//! # To Run
+//!
+//! cargo test -p html-macro-test --lib ui -- trybuild=unknown_event_modifier.rs
+
+extern crate percy_dom;
+use percy_dom::prelude::*;
+
+// Used an event modifier that does not exist.
+fn main() {
+    html! {
+        <form onsubmit.prevnt = ||{} >
+        </form>
+    };
+}
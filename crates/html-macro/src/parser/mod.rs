@@ -1,4 +1,4 @@
-use crate::tag::TagKind;
+use crate::tag::{TagKind, TagName};
 use crate::Tag;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
@@ -35,7 +35,7 @@ pub struct HtmlParser {
     /// we push it's node index onto the stack.
     ///
     /// Text nodes cannot be parent nodes.
-    parent_stack: Vec<(usize, Ident)>,
+    parent_stack: Vec<(usize, TagName)>,
     /// Key -> index of the parent node within the HTML tree
     /// Value -> vector of child node indices
     parent_to_children: HashMap<usize, Vec<usize>>,
@@ -133,6 +133,8 @@ impl HtmlParser {
                         let push_children = quote! {
                             if let Some(ref mut element_node) = #parent_name.as_velement_mut() {
                                 element_node.children.extend(#children.into_iter());
+                            } else if let Some(fragment) = #parent_name.as_fragment_mut() {
+                                fragment.extend(#children.into_iter());
                             } else {
                                 #unreachable;
                             }
@@ -145,7 +147,7 @@ impl HtmlParser {
         }
 
         for (_, tag) in &self.parent_stack {
-            let is_text_node = tag == "__text__";
+            let is_text_node = *tag == "__text__";
             if is_text_node {
                 continue;
             }
@@ -281,3 +283,7 @@ fn is_self_closing(tag: &str) -> bool {
 fn is_valid_tag(tag: &str) -> bool {
     html_validation::is_valid_tag(tag)
 }
+
+fn is_custom_element_name(tag: &str) -> bool {
+    html_validation::is_custom_element_name(tag)
+}
@@ -1,10 +1,10 @@
 use crate::parser::open_tag::event::insert_closure_tokens;
-use crate::parser::{is_self_closing, is_valid_tag, HtmlParser};
-use crate::tag::Attr;
+use crate::parser::{is_custom_element_name, is_self_closing, is_valid_tag, HtmlParser};
+use crate::tag::{Attr, TagName, FRAGMENT_TAG};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
-use syn::Expr;
 use syn::__private::TokenStream2;
+use syn::Expr;
 
 mod event;
 
@@ -12,7 +12,7 @@ impl HtmlParser {
     /// Parse an incoming Tag::Open
     pub(crate) fn parse_open_tag(
         &mut self,
-        name: &Ident,
+        name: &TagName,
         closing_span: &Span,
         attrs: &Vec<Attr>,
         is_self_closing_tag: bool,
@@ -30,9 +30,13 @@ impl HtmlParser {
         // TODO: Not sure what the span is supposed to be so I just picked something..
         let var_name_node = Ident::new(format!("node_{}", idx).as_str(), name.span());
         let html_tag = format!("{}", name);
-        let is_html_tag = is_valid_tag(&html_tag);
+        let is_html_tag = is_valid_tag(&html_tag) || is_custom_element_name(&html_tag);
 
-        if is_html_tag {
+        if html_tag == FRAGMENT_TAG {
+            tokens.push(quote! {
+                let mut #var_name_node = VirtualNode::fragment(vec![]);
+            });
+        } else if is_html_tag {
             create_valid_node(&html_tag, attrs, &var_name_node, tokens);
         } else if !html_tag.chars().next().unwrap().is_uppercase() {
             let compile_err = invalid_tag_compile_error(name, &html_tag, &var_name_node);
@@ -97,6 +101,65 @@ fn create_valid_node(
         let key = attr.key_string();
         let value = attr.value();
 
+        // The "indeterminate" in
+        //   html! { <input prop:indeterminate=true /> }
+        if let Some(property) = key.strip_prefix("prop:") {
+            let insert_property = quote! {
+                #var_name_node.as_velement_mut().expect("Not an element")
+                    .properties.insert(#property.to_string(), #value.into());
+            };
+            tokens.push(insert_property);
+            continue;
+        }
+
+        // The "btn primary" in
+        //   html! { <div class="btn primary"></div> }
+        if key == "class" {
+            // NOTE: The `Classes`'s `From` implementations' documentation mentions that
+            //  contributors can search for `#value.into()` to find this.
+            let set_classes = quote! {
+                #var_name_node.as_velement_mut().expect("Not an element")
+                    .classes = #value.into();
+            };
+            tokens.push(set_classes);
+            continue;
+        }
+
+        // The "input_ref" in
+        //   html! { <input ref=input_ref /> }
+        if key == "ref" {
+            let set_node_ref = quote! {
+                #var_name_node.as_velement_mut().expect("Not an element")
+                    .special_attributes.set_node_ref((#value).clone());
+            };
+            tokens.push(set_node_ref);
+            continue;
+        }
+
+        // The "#modals" in
+        //   html! { <div portal="#modals"></div> }
+        if key == "portal" {
+            let set_portal = quote! {
+                #var_name_node.as_velement_mut().expect("Not an element")
+                    .special_attributes.set_portal(#value);
+            };
+            tokens.push(set_portal);
+            continue;
+        }
+
+        // The "color: red" in
+        //   html! { <div style="color: red"></div> }
+        if key == "style" {
+            // NOTE: The `Styles`'s `From` implementations' documentation mentions that
+            //  contributors can search for `#value.into()` to find this.
+            let set_styles = quote! {
+                #var_name_node.as_velement_mut().expect("Not an element")
+                    .styles = #value.into();
+            };
+            tokens.push(set_styles);
+            continue;
+        }
+
         match value {
             Expr::Closure(closure) => {
                 let add_closure = insert_closure_tokens(var_name_node, attr, &closure, key_attr);
@@ -119,7 +182,7 @@ fn create_valid_node(
     }
 }
 
-fn invalid_tag_compile_error(name: &Ident, html_tag: &str, var_name_node: &Ident) -> TokenStream {
+fn invalid_tag_compile_error(name: &TagName, html_tag: &str, var_name_node: &Ident) -> TokenStream {
     let error = format!(
         r#"{} is not a valid HTML tag.
                 
@@ -127,6 +190,8 @@ If you are trying to use a valid HTML tag, perhaps there's a typo?
                 
 If you are trying to use a custom component, please capitalize the component name.
                 
+If you are trying to use a custom element, its name must contain a hyphen, such as my-widget.
+                
 custom components: https://chinedufn.github.io/percy/html-macro/custom-components/index.html"#,
         html_tag,
     );
@@ -145,7 +210,7 @@ custom components: https://chinedufn.github.io/percy/html-macro/custom-component
 
 fn component_node(
     idx: usize,
-    name: &Ident,
+    name: &TagName,
     html_tag: &str,
     attrs: &Vec<Attr>,
     var_name_node: &Ident,
@@ -160,7 +225,7 @@ fn component_node(
             let value = attr.value();
 
             quote! {
-                #key: #value,
+                #key: #value
             }
         })
         .collect();
@@ -1,5 +1,5 @@
 use crate::tag::Attr;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::__private::TokenStream2;
 use syn::{Expr, ExprClosure, Pat, PatType, Type};
@@ -22,6 +22,24 @@ pub(super) fn insert_closure_tokens(
 
     let attr_key_span = &event_attribute.key_span();
 
+    // The event's name without its modifiers or target, such as the "onkeydown" in
+    // "document:onkeydown.prevent".
+    // The modifiers and target stay in the name that we store, since that's where `EventName`
+    // reads them from.
+    let mut modifiers = event_name.split('.');
+    let base_event_name = modifiers.next().unwrap();
+    let modifiers: Vec<&str> = modifiers.collect();
+    let base_event_name = base_event_name
+        .strip_prefix("window:")
+        .or_else(|| base_event_name.strip_prefix("document:"))
+        .unwrap_or(base_event_name);
+
+    if let Some(error) = modifiers_error(base_event_name, &modifiers) {
+        return quote_spanned! { *attr_key_span => {
+            compile_error!(#error);
+        }};
+    }
+
     // TODO: Refactor duplicate code between these blocks.
     if event_name == "on_create_element" {
         let mut maybe_missing_key_error = None;
@@ -47,20 +65,41 @@ Documentation:
             quote! { #FAKE_KEY }
         };
 
-        let closure =
+        let typed_closure =
             maybe_set_arg_type(closure, quote! { __html_macro_helpers__::web_sys::Element });
+        let maybe_use_closure = maybe_use_closure_on_non_wasm32(closure);
+
+        let set_callback = if arg_count == 0 {
+            quote! {
+                let event_callback = #closure;
 
-        let setter = if arg_count == 0 {
-            quote! { set_on_create_element_no_args }
+                #var_name_node.as_velement_mut().unwrap()
+                  .special_attributes.set_on_create_element_no_args(#key_attr_value.to_string(), event_callback);
+            }
         } else {
-            quote! { set_on_create_element }
+            quote! {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let event_callback = #typed_closure;
+
+                    #var_name_node.as_velement_mut().unwrap()
+                      .special_attributes.set_on_create_element(#key_attr_value.to_string(), event_callback);
+                }
+
+                // The closure's argument is a `web_sys::Element`, which is not available on non
+                // wasm32 targets, so we only store the key.
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    #maybe_use_closure
+
+                    #var_name_node.as_velement_mut().unwrap()
+                      .special_attributes.set_on_create_element_no_args(#key_attr_value.to_string(), || {});
+                }
+            }
         };
 
         quote! {
-            let event_callback = #closure;
-
-            #var_name_node.as_velement_mut().unwrap()
-              .special_attributes.#setter(#key_attr_value.to_string(), event_callback);
+            #set_callback
 
             #maybe_missing_key_error
         }
@@ -88,20 +127,41 @@ Documentation:
             quote! { #FAKE_KEY }
         };
 
-        let closure =
+        let typed_closure =
             maybe_set_arg_type(closure, quote! { __html_macro_helpers__::web_sys::Element });
+        let maybe_use_closure = maybe_use_closure_on_non_wasm32(closure);
+
+        let set_callback = if arg_count == 0 {
+            quote! {
+                let event_callback = #closure;
 
-        let setter = if arg_count == 0 {
-            quote! { set_on_remove_element_no_args }
+                #var_name_node.as_velement_mut().unwrap()
+                  .special_attributes.set_on_remove_element_no_args(#key_attr_value.to_string(), event_callback);
+            }
         } else {
-            quote! { set_on_remove_element }
+            quote! {
+                #[cfg(target_arch = "wasm32")]
+                {
+                    let event_callback = #typed_closure;
+
+                    #var_name_node.as_velement_mut().unwrap()
+                      .special_attributes.set_on_remove_element(#key_attr_value.to_string(), event_callback);
+                }
+
+                // The closure's argument is a `web_sys::Element`, which is not available on non
+                // wasm32 targets, so we only store the key.
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    #maybe_use_closure
+
+                    #var_name_node.as_velement_mut().unwrap()
+                      .special_attributes.set_on_remove_element_no_args(#key_attr_value.to_string(), || {});
+                }
+            }
         };
 
         quote! {
-            let event_callback = #closure;
-
-            #var_name_node.as_velement_mut().unwrap()
-              .special_attributes.#setter(#key_attr_value.to_string(), event_callback);
+            #set_callback
 
             #maybe_missing_key_error
         }
@@ -115,44 +175,42 @@ Documentation:
                 )
             );
         }
-    } else if event_name == "onclick" {
-        let tokens = quote! {
-            #var_name_node.as_velement_mut().unwrap().events.insert_mouse_event(
+    } else if let Some((event_type, insert_event)) = typed_event(base_event_name) {
+        let closure = maybe_set_arg_type(
+            closure,
+            quote! { __html_macro_helpers__::event::#event_type },
+        );
+
+        // The closure can take our event type or the `web_sys` event that it wraps, such as
+        // `|event: web_sys::KeyboardEvent| {}`.
+        quote! {
+            let event_callback = #closure;
+            #var_name_node.as_velement_mut().unwrap().events.#insert_event(
                 #event_name.into(),
-                std::rc::Rc::new(
-                    std::cell::RefCell::new( event_callback )
-                )
-            );
-        };
-
-        let tokens = if arg_count == 0 {
-            quote! {
-                let event_callback = #closure;
-                #tokens
-            }
-        } else {
-            let closure = maybe_set_arg_type(
-                closure,
-                quote! { __html_macro_helpers__::event::MouseEvent },
+                event_callback
             );
-
-            quote! {
-                let event_callback = #closure;
-                #tokens
-            }
-        };
-
-        tokens
+        }
     } else {
         let arg_type_placeholders: Vec<TokenStream2> =
             (0..arg_count).into_iter().map(|_| quote! { _ }).collect();
 
+        // Custom elements dispatch custom events, such as the "sl-after-show" in
+        // `<sl-dialog onsl-after-show=|event| {}></sl-dialog>`, which are `CustomEvent`s.
+        let typed_closure = if base_event_name.contains('-') {
+            maybe_set_arg_type(
+                closure,
+                quote! { __html_macro_helpers__::web_sys::CustomEvent },
+            )
+        } else {
+            closure.clone()
+        };
+
         quote! {
           #[cfg(target_arch = "wasm32")]
           {
 
               let closure = Closure::wrap(
-                  Box::new(#closure) as Box<dyn FnMut(#(#arg_type_placeholders)*)>
+                  Box::new(#typed_closure) as Box<dyn FnMut(#(#arg_type_placeholders)*)>
               );
               let closure_rc = std::rc::Rc::new(closure);
 
@@ -169,6 +227,93 @@ Documentation:
     }
 }
 
+/// On non wasm32 targets we don't store closures that take an element, but we still want the
+/// variables that they capture to be considered used.
+///
+/// A closure whose argument has no type annotation, such as `|elem| {}`, can't be type checked
+/// without the `web_sys::Element` that we would annotate it with, so it is left out.
+fn maybe_use_closure_on_non_wasm32(closure: &ExprClosure) -> Option<TokenStream2> {
+    let arg_is_typed = closure.inputs.iter().all(|arg| matches!(arg, Pat::Type(_)));
+
+    if !arg_is_typed {
+        return None;
+    }
+
+    Some(quote! {
+        // Ensures that the variables that the closure captures are considered used.
+        let _ = #closure;
+    })
+}
+
+/// An error message if the event's modifiers, such as the "prevent" in "onsubmit.prevent", are
+/// invalid.
+fn modifiers_error(base_event_name: &str, modifiers: &[&str]) -> Option<String> {
+    if modifiers.is_empty() {
+        return None;
+    }
+
+    if base_event_name == "on_create_element" || base_event_name == "on_remove_element" {
+        return Some(format!("`{}` does not support modifiers.", base_event_name));
+    }
+
+    for modifier in modifiers {
+        if !["prevent", "passive", "capture", "once"].contains(modifier) {
+            return Some(format!(
+                "Unknown event modifier `.{}`. The supported modifiers are `.prevent`, \
+`.passive`, `.capture` and `.once`.",
+                modifier
+            ));
+        }
+    }
+
+    if modifiers.contains(&"prevent") && modifiers.contains(&"passive") {
+        return Some(
+            "The `.prevent` and `.passive` modifiers cannot be used together, since a passive \
+listener cannot prevent the event's default action."
+                .to_string(),
+        );
+    }
+
+    None
+}
+
+/// The event type and the `Events` method that inserts it for the events that we have a dedicated
+/// type for, such as `(KeyboardEvent, __insert_keyboard_event)` for `onkeydown`.
+///
+/// Other events, such as custom events, have their closure stored as an
+/// `EventHandler::UnsupportedSignature`.
+fn typed_event(event_name: &str) -> Option<(Ident, Ident)> {
+    let (event_type, insert_event) = match event_name {
+        "onclick" | "ondblclick" | "onauxclick" | "oncontextmenu" | "onmousedown" | "onmouseup"
+        | "onmousemove" | "onmouseover" | "onmouseout" | "onmouseenter" | "onmouseleave" => {
+            ("MouseEvent", "__insert_mouse_event")
+        }
+        "onkeydown" | "onkeyup" | "onkeypress" => ("KeyboardEvent", "__insert_keyboard_event"),
+        "oninput" | "onbeforeinput" => ("InputEvent", "__insert_input_event"),
+        "onfocus" | "onblur" | "onfocusin" | "onfocusout" => ("FocusEvent", "__insert_focus_event"),
+        "onpointerdown"
+        | "onpointerup"
+        | "onpointermove"
+        | "onpointerover"
+        | "onpointerout"
+        | "onpointerenter"
+        | "onpointerleave"
+        | "onpointercancel"
+        | "ongotpointercapture"
+        | "onlostpointercapture" => ("PointerEvent", "__insert_pointer_event"),
+        "ondrag" | "ondragstart" | "ondragend" | "ondragenter" | "ondragleave" | "ondragover"
+        | "ondrop" => ("DragEvent", "__insert_drag_event"),
+        "onwheel" => ("WheelEvent", "__insert_wheel_event"),
+        "onsubmit" => ("SubmitEvent", "__insert_submit_event"),
+        _ => return None,
+    };
+
+    Some((
+        Ident::new(event_type, Span::call_site()),
+        Ident::new(insert_event, Span::call_site()),
+    ))
+}
+
 /// Clone the incoming closure tokens.
 ///
 /// - If the closure does not have any arguments, return the closure.
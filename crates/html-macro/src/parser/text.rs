@@ -1,5 +1,5 @@
 use crate::parser::HtmlParser;
-use crate::tag::{Tag, TagKind};
+use crate::tag::{Tag, TagKind, TagName};
 use proc_macro2::{Ident, Span};
 use quote::quote;
 
@@ -47,7 +47,7 @@ impl HtmlParser {
             // things work but in reality a text node isn't a parent ever.
             // Just need to make the code DRY / refactor so that we can make things make
             // sense vs. just bolting things together.
-            parent_stack.push((0, Ident::new("__text__", Span::call_site())));
+            parent_stack.push((0, TagName::new("__text__", Span::call_site())));
         }
 
         let var_name = Ident::new(format!("node_{}", idx).as_str(), Span::call_site());
@@ -1,9 +1,14 @@
 use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{quote_spanned, ToTokens};
+use std::fmt;
+use syn::ext::IdentExt;
 use syn::parse::{Parse, ParseStream, Result};
 use syn::spanned::Spanned;
 use syn::token::Brace;
-use syn::{braced, Block, Expr, Ident, Token};
+use syn::{braced, Block, Expr, Ident, LitInt, Token};
+
+/// The name that we give to the `<>` and `</>` tags of a fragment, since they don't have one.
+pub const FRAGMENT_TAG: &str = "__fragment__";
 
 /// The different kinds of tokens that we parse.
 ///
@@ -12,16 +17,18 @@ use syn::{braced, Block, Expr, Ident, Token};
 pub enum Tag {
     /// <div id="app" class=*CSS>
     /// <br />
+    /// <>
     Open {
-        name: Ident,
+        name: TagName,
         attrs: Vec<Attr>,
         open_bracket_span: Span,
         closing_bracket_span: Span,
         is_self_closing: bool,
     },
     /// </div>
+    /// </>
     Close {
-        name: Ident,
+        name: TagName,
         first_angle_bracket_span: Span,
     },
     /// html! { <div> Hello World </div> }
@@ -65,6 +72,42 @@ pub enum TagKind {
     Braced,
 }
 
+/// The name of a tag, such as the "div" in `<div>` or the "my-widget" in `<my-widget>`.
+///
+/// Custom element names contain hyphens, so unlike component names they can not be stored in an
+/// `Ident`.
+#[derive(Debug, Clone)]
+pub struct TagName {
+    name: String,
+    span: Span,
+}
+impl TagName {
+    /// Create a tag name.
+    pub fn new<N: Into<String>>(name: N, span: Span) -> Self {
+        TagName {
+            name: name.into(),
+            span,
+        }
+    }
+
+    /// Get the span for the tag name.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+
+impl fmt::Display for TagName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl PartialEq<&str> for TagName {
+    fn eq(&self, other: &&str) -> bool {
+        self.name == *other
+    }
+}
+
 /// id="my-id"
 /// class="some classes"
 /// etc...
@@ -126,7 +169,7 @@ impl Parse for Tag {
 
 /// `<div id="app" class=*CSS>`
 fn parse_open_tag(input: &mut ParseStream, open_bracket_span: Span) -> Result<Tag> {
-    let name: Ident = input.parse()?;
+    let name = parse_tag_name(input, open_bracket_span)?;
 
     let attrs = parse_attributes(input)?;
 
@@ -160,6 +203,7 @@ fn parse_attributes(input: &mut ParseStream) -> Result<Vec<Attr>> {
         || input.peek(Token![async])
         || input.peek(Token![for])
         || input.peek(Token![loop])
+        || input.peek(Token![ref])
         || input.peek(Token![type])
     {
         let (key, key_span) = parse_attribute_key(input)?;
@@ -179,6 +223,7 @@ fn parse_attributes(input: &mut ParseStream) -> Result<Vec<Attr>> {
                 || input.peek(Token![async])
                 || input.peek(Token![for])
                 || input.peek(Token![loop])
+                || input.peek(Token![ref])
                 || input.peek(Token![type]);
 
             // The `=` in:
@@ -189,8 +234,10 @@ fn parse_attributes(input: &mut ParseStream) -> Result<Vec<Attr>> {
             let next_next_token_is_equals_or_hyphen =
                 input.peek2(Token![=]) || input.peek2(Token![-]);
 
-            let peek_start_of_next_attr =
-                next_token_is_attrib_key && next_next_token_is_equals_or_hyphen;
+            let peek_start_of_next_attr = (next_token_is_attrib_key
+                && next_next_token_is_equals_or_hyphen)
+                || peek_prefixed_attribute_key(input)
+                || peek_event_key_with_modifiers(input);
 
             let peek_end_of_tag = input.peek(Token![>]);
 
@@ -213,47 +260,111 @@ fn parse_attributes(input: &mut ParseStream) -> Result<Vec<Attr>> {
     Ok(attrs)
 }
 
+/// Whether or not the input starts with a prefixed attribute key followed by an `=`, such as
+/// `prop:indeterminate=`.
+///
+/// We check for the `=` so that a closure's typed argument, such as the `event: MouseEvent` in
+/// `onclick=|event: MouseEvent| {}`, is not mistaken for the start of the next attribute.
+fn peek_prefixed_attribute_key(input: ParseStream) -> bool {
+    if !input.peek(Ident) || !input.peek2(Token![:]) || input.peek2(Token![::]) {
+        return false;
+    }
+
+    let fork = input.fork();
+    let mut fork: ParseStream = &fork;
+
+    parse_attribute_key(&mut fork).is_ok() && fork.peek(Token![=])
+}
+
+/// Whether or not the input starts with an event listener's key that has modifiers followed by an
+/// `=`, such as `onsubmit.prevent=`.
+///
+/// We check that the key starts with "on" so that a field access in an attribute's value, such as
+/// the `state.count` in `value=state.count`, is not mistaken for the start of the next attribute.
+fn peek_event_key_with_modifiers(input: ParseStream) -> bool {
+    if !input.peek(Ident) || !input.peek2(Token![.]) {
+        return false;
+    }
+
+    let fork = input.fork();
+    let mut fork: ParseStream = &fork;
+
+    match parse_attribute_key(&mut fork) {
+        Ok((key, _)) => key.to_string().starts_with("on") && fork.peek(Token![=]),
+        Err(_) => false,
+    }
+}
+
 /// Parse an attribute key such as the "http-equiv" in
 /// `<meta http-equiv="refresh" />`
+///
+/// A key can have a prefix, such as the "prop" in `<input prop:indeterminate=true />`.
 fn parse_attribute_key(input: &mut ParseStream) -> Result<(TokenStream, Span)> {
+    if input.peek(Ident) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+        let prefix: Ident = input.parse()?;
+        let colon: Token![:] = input.parse()?;
+        let (key, key_span) = parse_attribute_key(input)?;
+
+        let combined_span = prefix
+            .span()
+            .join(key_span)
+            .unwrap_or_else(|| prefix.span());
+
+        return Ok((
+            quote_spanned! {combined_span=> #prefix #colon #key },
+            combined_span,
+        ));
+    }
+
     let first_key_segment = parse_attribute_key_segment(input)?;
 
-    let maybe_hyphen: Option<Token![-]> = input.parse()?;
+    let mut key = first_key_segment.to_token_stream();
+    let mut key_span = first_key_segment.span();
 
-    let attribute_key;
-    if let Some(hyphen) = maybe_hyphen {
+    // Keys can have any number of hyphens, such as the "data-user-id" in
+    // `<div data-user-id="5"></div>` or the "onsl-after-show" custom event listener in
+    // `<sl-dialog onsl-after-show=|| {}></sl-dialog>`.
+    while input.peek(Token![-]) {
+        let hyphen: Token![-] = input.parse()?;
         let next_segment = parse_attribute_key_segment(input)?;
 
-        let combined_span = first_key_segment
-            .span()
+        // Spans can only be joined on nightly, so on stable we fall back to the first segment's
+        // span.
+        key_span = key_span
             .join(hyphen.span())
-            .unwrap()
-            .join(next_segment.span())
-            .unwrap();
+            .and_then(|span| span.join(next_segment.span()))
+            .unwrap_or(key_span);
 
-        attribute_key = (
-            quote_spanned! {combined_span=> #first_key_segment - #next_segment },
-            combined_span,
-        );
-    } else {
-        attribute_key = (
-            first_key_segment.to_token_stream(),
-            first_key_segment.span(),
-        );
+        key = quote_spanned! {key_span=> #key - #next_segment };
     }
 
-    Ok(attribute_key)
+    // Event listeners can have modifiers, such as the "prevent" in
+    // `<form onsubmit.prevent=|| {}></form>`.
+    while input.peek(Token![.]) {
+        let dot: Token![.] = input.parse()?;
+        let modifier: Ident = input.parse()?;
+
+        key_span = key_span
+            .join(dot.span())
+            .and_then(|span| span.join(modifier.span()))
+            .unwrap_or(key_span);
+
+        key = quote_spanned! {key_span=> #key . #modifier };
+    }
+
+    Ok((key, key_span))
 }
 
 /// Parse a segment within an attribute key, such as the "http" or "equiv" in
 /// `<meta http-equiv="refresh" />`
 fn parse_attribute_key_segment(input: &mut ParseStream) -> Result<Ident> {
     // <link rel="stylesheet" type="text/css"
-    //   .. as, async, for, loop, type need to be handled specially since they are keywords
+    //   .. as, async, for, loop, ref, type need to be handled specially since they are keywords
     let maybe_as_key: Option<Token![as]> = input.parse()?;
     let maybe_async_key: Option<Token![async]> = input.parse()?;
     let maybe_for_key: Option<Token![for]> = input.parse()?;
     let maybe_loop_key: Option<Token![loop]> = input.parse()?;
+    let maybe_ref_key: Option<Token![ref]> = input.parse()?;
     let maybe_type_key: Option<Token![type]> = input.parse()?;
 
     let key = if let Some(as_key) = maybe_as_key {
@@ -264,6 +375,8 @@ fn parse_attribute_key_segment(input: &mut ParseStream) -> Result<Ident> {
         Ident::new("for", for_key.span())
     } else if let Some(loop_key) = maybe_loop_key {
         Ident::new("loop", loop_key.span())
+    } else if let Some(ref_key) = maybe_ref_key {
+        Ident::new("ref", ref_key.span())
     } else if let Some(type_key) = maybe_type_key {
         Ident::new("type", type_key.span())
     } else {
@@ -274,7 +387,7 @@ fn parse_attribute_key_segment(input: &mut ParseStream) -> Result<Ident> {
 
 /// </div>
 fn parse_close_tag(input: &mut ParseStream, first_angle_bracket_span: Span) -> Result<Tag> {
-    let name: Ident = input.parse()?;
+    let name = parse_tag_name(input, first_angle_bracket_span)?;
 
     input.parse::<Token![>]>()?;
 
@@ -284,11 +397,53 @@ fn parse_close_tag(input: &mut ParseStream, first_angle_bracket_span: Span) -> R
     })
 }
 
+/// Parse the "div" in `<div>` or `</div>`.
+///
+/// Fragment tags, `<>` and `</>`, do not have a name so we name them [`FRAGMENT_TAG`].
+///
+/// Tag names can be Rust keywords, such as the SVG `<use>` tag, and can contain hyphens, such as
+/// the custom element `<my-widget>`.
+fn parse_tag_name(input: &mut ParseStream, first_angle_bracket_span: Span) -> Result<TagName> {
+    if input.peek(Token![>]) {
+        return Ok(TagName::new(FRAGMENT_TAG, first_angle_bracket_span));
+    }
+
+    let first_segment = Ident::parse_any(input)?;
+    let mut name = first_segment.to_string();
+    let mut span = first_segment.span();
+
+    while input.peek(Token![-]) {
+        let hyphen: Token![-] = input.parse()?;
+
+        // The "2d" in `<x-2d-canvas>` is a number followed by an ident, which Rust tokenizes as
+        // an integer literal with a suffix.
+        let (segment, segment_span) = if input.peek(LitInt) {
+            let segment: LitInt = input.parse()?;
+            (segment.to_string(), segment.span())
+        } else {
+            let segment = Ident::parse_any(input)?;
+            (segment.to_string(), segment.span())
+        };
+
+        name += "-";
+        name += &segment;
+
+        // Spans can only be joined on nightly, so on stable we fall back to the first segment's
+        // span.
+        span = span
+            .join(hyphen.span())
+            .and_then(|span| span.join(segment_span))
+            .unwrap_or(span);
+    }
+
+    Ok(TagName::new(name, span))
+}
+
 fn parse_block(input: &mut ParseStream) -> Result<Tag> {
     let content;
     let brace_token = braced!(content in input);
 
-    let brace_span = brace_token.span.open();
+    let brace_span = brace_token.span.join();
 
     let block_expr = content.call(Block::parse_within)?;
 
@@ -0,0 +1,38 @@
+/// Names that contain a hyphen but are reserved by SVG and MathML, so they can not be used as
+/// custom element names.
+///
+/// https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name
+const RESERVED_NAMES: [&str; 8] = [
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-src",
+    "font-face-uri",
+    "font-face-format",
+    "font-face-name",
+    "missing-glyph",
+];
+
+/// Whether or not this is a valid custom element name, such as `my-widget`.
+///
+/// A custom element name starts with a lowercase ASCII letter, contains a hyphen and does not
+/// contain any uppercase ASCII letters.
+///
+/// ```
+/// use html_validation::is_custom_element_name;
+///
+/// assert_eq!(is_custom_element_name("my-widget"), true);
+/// assert_eq!(is_custom_element_name("x-2d-canvas"), true);
+///
+/// assert_eq!(is_custom_element_name("widget"), false);
+/// assert_eq!(is_custom_element_name("My-Widget"), false);
+/// assert_eq!(is_custom_element_name("font-face"), false);
+/// ```
+pub fn is_custom_element_name(tag: &str) -> bool {
+    matches!(tag.chars().next(), Some('a'..='z'))
+        && tag.contains('-')
+        && !tag
+            .chars()
+            .any(|c| c.is_ascii_uppercase() || c.is_whitespace())
+        && !RESERVED_NAMES.contains(&tag)
+}
@@ -33,10 +33,14 @@
 
 #![deny(missing_docs)]
 
+pub use custom_element::is_custom_element_name;
+pub use mathml_namespace::is_mathml_namespace;
 pub use self_closing::is_self_closing;
 pub use svg_namespace::is_svg_namespace;
 pub use valid_tags::is_valid_tag;
 
+mod custom_element;
+mod mathml_namespace;
 mod self_closing;
 mod svg_namespace;
 mod valid_tags;
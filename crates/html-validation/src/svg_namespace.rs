@@ -6,7 +6,8 @@ lazy_static! {
     //  https://developer.mozilla.org/en-US/docs/Web/SVG/Element
     //  a hashmap of `(tag, is_self_closing)`
     static ref SVG_NAMESPACED_TAGS: HashMap<&'static str, bool> = [
-        // TODO: can cause conflict with html `a`
+        // Also an HTML tag, so it is only SVG when inside of an `<svg>`.
+        // See `virtual_node::namespace::element_namespace`.
         //("a", true),
         ("animate", true),
         ("animateMotion", false),
@@ -70,19 +71,22 @@ lazy_static! {
         ("polyline", true),
         ("radialGradient", false),
         ("rect", true),
-        // TODO: can cause conflict with html `script` tag
+        // Also an HTML tag, so it is only SVG when inside of an `<svg>`.
+        // See `virtual_node::namespace::element_namespace`.
         //("script", false),
         ("set", true),
         ("solidcolor", true),
         ("stop", true),
-        // TODO: can cause conflict with html `style` tag
+        // Also an HTML tag, so it is only SVG when inside of an `<svg>`.
+        // See `virtual_node::namespace::element_namespace`.
         //("style", false),
         ("svg", false),
         ("switch", false),
         ("symbol", false),
         ("text", false),
         ("textPath", false),
-        // TODO: can cause conflict with html `title` tag
+        // Also an HTML tag, so it is only SVG when inside of an `<svg>`.
+        // See `virtual_node::namespace::element_namespace`.
         //("title", false),
         ("tspan", false),
         // TODO: undocumented
@@ -1,6 +1,7 @@
 use lazy_static::lazy_static;
 use std::collections::hash_set::HashSet;
 
+use super::mathml_namespace::is_mathml_namespace;
 use super::svg_namespace::is_svg_namespace;
 
 lazy_static! {
@@ -94,6 +95,7 @@ lazy_static! {
         "script",
         "section",
         "select",
+        "slot",
         "small",
         "source",
         "span",
@@ -105,6 +107,7 @@ lazy_static! {
         "table",
         "tbody",
         "td",
+        "template",
         "textarea",
         "tfoot",
         "th",
@@ -134,5 +137,5 @@ lazy_static! {
 /// assert_eq!(is_valid_tag("random"), false);
 /// ```
 pub fn is_valid_tag(tag: &str) -> bool {
-    VALID_TAGS.contains(tag) || is_svg_namespace(tag)
+    VALID_TAGS.contains(tag) || is_svg_namespace(tag) || is_mathml_namespace(tag)
 }
@@ -0,0 +1,83 @@
+//! Benchmarks for diffing large virtual DOM trees.
+//!
+//! Run with:
+//!
+//! cargo bench -p percy-dom --bench diff
+
+#[cfg(not(target_arch = "wasm32"))]
+criterion::criterion_main!(benches::benches);
+
+// Criterion is only a dev-dependency on non wasm32 targets, so there is nothing to run on wasm32.
+#[cfg(target_arch = "wasm32")]
+fn main() {}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod benches {
+    use criterion::{criterion_group, Criterion};
+    use percy_dom::{diff, VElement, VirtualNode};
+    use std::hint::black_box;
+
+    const LIST_LEN: usize = 100_000;
+
+    fn diff_benchmarks(c: &mut Criterion) {
+        let mut group = c.benchmark_group("diff");
+        group.sample_size(20);
+
+        group.bench_function("unchanged list", |b| {
+            let old = list(LIST_LEN, |idx| idx.to_string());
+            let new = list(LIST_LEN, |idx| idx.to_string());
+
+            b.iter(|| diff(black_box(&old), black_box(&new)));
+        });
+
+        group.bench_function("change every text node", |b| {
+            let old = list(LIST_LEN, |idx| idx.to_string());
+            let new = list(LIST_LEN, |idx| format!("Changed {}", idx));
+
+            b.iter(|| diff(black_box(&old), black_box(&new)));
+        });
+
+        group.bench_function("remove list", |b| {
+            let old = list(LIST_LEN, |idx| idx.to_string());
+            let new = list(0, |idx| idx.to_string());
+
+            b.iter(|| diff(black_box(&old), black_box(&new)));
+        });
+
+        group.bench_function("reverse keyed list", |b| {
+            let keys: Vec<usize> = (0..LIST_LEN).collect();
+            let old = keyed_list(keys.iter().copied());
+            let new = keyed_list(keys.iter().rev().copied());
+
+            b.iter(|| diff(black_box(&old), black_box(&new)));
+        });
+
+        group.finish();
+    }
+
+    /// A `<ul>` with `len` `<li>`s that each contain a text node.
+    fn list(len: usize, text: impl Fn(usize) -> String) -> VirtualNode {
+        let mut list = VElement::new("ul");
+        for idx in 0..len {
+            let mut item = VElement::new("li");
+            item.children.push(VirtualNode::text(text(idx)));
+            list.children.push(item.into());
+        }
+
+        list.into()
+    }
+
+    /// A `<ul>` with an `<li>` for every key.
+    fn keyed_list(keys: impl Iterator<Item = usize>) -> VirtualNode {
+        let mut list = VElement::new("ul");
+        for key in keys {
+            let mut item = VElement::new("li");
+            item.attrs.insert("key".to_string(), key.to_string().into());
+            list.children.push(item.into());
+        }
+
+        list.into()
+    }
+
+    criterion_group!(benches, diff_benchmarks);
+}
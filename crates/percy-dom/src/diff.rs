@@ -2,7 +2,7 @@ use crate::diff::longest_increasing_subsequence::{
     get_longest_increasing_subsequence, KeyAndChildIdx,
 };
 use crate::event::{EventHandler, EventName};
-use crate::{AttributeValue, Patch, PatchSpecialAttribute};
+use crate::{AttributeValue, Patch, PatchSpecialAttribute, PropertyValue};
 use crate::{VElement, VirtualNode};
 use std::collections::{HashMap, VecDeque};
 use std::mem;
@@ -11,18 +11,12 @@ mod longest_increasing_subsequence;
 
 /// Given two VirtualNode's generate Patch's that would turn the old virtual node's
 /// real DOM node equivalent into the new VirtualNode's real DOM node equivalent.
+///
+/// If the old virtual node is a fragment, the new virtual node's DOM nodes are diffed against
+/// the fragment's nodes. See [`VirtualNode::dom_nodes`].
 pub fn diff<'a>(old: &'a VirtualNode, new: &'a VirtualNode) -> Vec<Patch<'a>> {
-    let old_node_idx = 0;
-    let mut diff_queue: VecDeque<DiffJob> = VecDeque::new();
-
-    diff_queue.push_back(DiffJob {
-        old_node_idx,
-        old,
-        new,
-    });
-
     let mut ctx = DiffContext::new(old, new);
-    diff_recursive(&mut ctx);
+    process_jobs(&mut ctx);
 
     ctx.take_patches()
 }
@@ -30,6 +24,7 @@ pub fn diff<'a>(old: &'a VirtualNode, new: &'a VirtualNode) -> Vec<Patch<'a>> {
 enum Job<'a> {
     Diff(DiffJob<'a>),
     ProcessDeleted(DeleteJob<'a>),
+    Skip(SkipJob<'a>),
 }
 
 #[derive(Copy, Clone)]
@@ -45,6 +40,12 @@ struct DeleteJob<'a> {
     old: &'a VirtualNode,
 }
 
+/// A node within an unchanged memo. We don't push any patches for it, but we still need to count
+/// its descendants in order to know the breadth-first indices of the nodes that come after them.
+struct SkipJob<'a> {
+    old: &'a VirtualNode,
+}
+
 use self::diff_ctx::DiffContext;
 mod diff_ctx {
     use super::*;
@@ -84,6 +85,10 @@ mod diff_ctx {
             self.job_queue.push_back(Job::ProcessDeleted(delete_job))
         }
 
+        pub fn push_skip_job(&mut self, skip_job: SkipJob<'a>) {
+            self.job_queue.push_back(Job::Skip(skip_job))
+        }
+
         /// We always push patches if the node has not been deleted.
         /// If the node or one of its ancestors has been deleted we only push patches that are
         /// applicable to deleted nodes.
@@ -105,27 +110,63 @@ mod diff_ctx {
     }
 }
 
-fn diff_recursive(ctx: &mut DiffContext) {
-    let job = ctx.next_job();
-    match job {
-        Some(Job::Diff(diff_job)) => process_diff_job(ctx, diff_job),
-        Some(Job::ProcessDeleted(delete_job)) => process_delete_job(ctx, delete_job),
-        None => return,
-    };
-
-    diff_recursive(ctx);
+/// Process jobs until the queue is empty.
+///
+/// Processing a job only pushes more jobs onto the queue instead of processing them, so our stack
+/// usage does not grow with the number of nodes that we diff.
+fn process_jobs(ctx: &mut DiffContext) {
+    while let Some(job) = ctx.next_job() {
+        match job {
+            Job::Diff(diff_job) => process_diff_job(ctx, diff_job),
+            Job::ProcessDeleted(delete_job) => process_delete_job(ctx, delete_job),
+            Job::Skip(skip_job) => process_skip_job(ctx, skip_job),
+        };
+    }
 }
 
 fn process_diff_job<'a>(ctx: &mut DiffContext<'a>, diff_job: DiffJob<'a>) {
-    let old = diff_job.old;
-    let new = diff_job.new;
     let old_node_idx = diff_job.old_node_idx;
 
+    // A memo with the same hash as the old memo would render the same view, so we keep the old
+    // memo's DOM node and skip diffing it without rendering the new memo's view.
+    if let (VirtualNode::Memo(old_memo), VirtualNode::Memo(new_memo)) = (diff_job.old, diff_job.new)
+    {
+        if new_memo.reuse_view_of(old_memo) {
+            process_skip_job(
+                ctx,
+                SkipJob {
+                    old: old_memo.view(),
+                },
+            );
+            return;
+        }
+    }
+
+    let old = diff_job.old.resolve_memos();
+    let new = diff_job.new.resolve_memos();
+    let diff_job = DiffJob {
+        old_node_idx,
+        old,
+        new,
+    };
+
+    // A fragment does not have a DOM node of its own. An old root fragment's node index refers to
+    // the DOM node that the fragment's nodes were placed into, so we diff the nodes that get
+    // placed into it.
+    if old.as_fragment_ref().is_some() {
+        generate_patches_for_children(old_node_idx, old.dom_nodes(), new.dom_nodes(), ctx);
+        return;
+    }
+
     let node_variants_different = mem::discriminant(old) != mem::discriminant(new);
     let mut element_tags_different = false;
 
     if let (VirtualNode::Element(old_element), VirtualNode::Element(new_element)) = (old, new) {
-        element_tags_different = old_element.tag != new_element.tag;
+        // A portal's DOM children are in a container within its target, so we move them by
+        // replacing the element.
+        element_tags_different = old_element.tag != new_element.tag
+            || old_element.custom_element_is() != new_element.custom_element_is()
+            || old_element.special_attributes.portal() != new_element.special_attributes.portal();
     }
 
     let should_fully_replace_node = node_variants_different || element_tags_different;
@@ -142,6 +183,12 @@ fn process_diff_job<'a>(ctx: &mut DiffContext<'a>, diff_job: DiffJob<'a>) {
             }
         }
 
+        (VirtualNode::Comment(old_comment), VirtualNode::Comment(new_comment)) => {
+            if old_comment != new_comment {
+                ctx.push_patch(Patch::ChangeComment(old_node_idx, new_comment));
+            }
+        }
+
         (VirtualNode::Element(old_element), VirtualNode::Element(new_element)) => {
             let mut attributes_to_add: HashMap<&str, &AttributeValue> = HashMap::new();
             let mut attributes_to_remove: Vec<&str> = vec![];
@@ -179,6 +226,35 @@ fn process_diff_job<'a>(ctx: &mut DiffContext<'a>, diff_job: DiffJob<'a>) {
                 ctx.push_patch(Patch::RemoveAttributes(old_node_idx, attributes_to_remove));
             }
 
+            let (properties_to_set, properties_to_remove) =
+                find_properties_to_set_and_remove(old_element, new_element);
+            if !properties_to_set.is_empty() {
+                ctx.push_patch(Patch::SetProperties(old_node_idx, properties_to_set));
+            }
+            if !properties_to_remove.is_empty() {
+                ctx.push_patch(Patch::RemoveProperties(old_node_idx, properties_to_remove));
+            }
+
+            let (classes_to_add, classes_to_remove) =
+                find_classes_to_add_and_remove(old_element, new_element);
+            if !classes_to_remove.is_empty() {
+                ctx.push_patch(Patch::RemoveClasses(old_node_idx, classes_to_remove));
+            }
+            if !classes_to_add.is_empty() {
+                ctx.push_patch(Patch::AddClasses(old_node_idx, classes_to_add));
+            }
+
+            // Styles are removed first so that removing a shorthand such as `margin` does not
+            // clear a longhand such as `margin-top` that is being set.
+            let (styles_to_set, styles_to_remove) =
+                find_styles_to_set_and_remove(old_element, new_element);
+            if !styles_to_remove.is_empty() {
+                ctx.push_patch(Patch::RemoveStyles(old_node_idx, styles_to_remove));
+            }
+            if !styles_to_set.is_empty() {
+                ctx.push_patch(Patch::SetStyles(old_node_idx, styles_to_set));
+            }
+
             if events_to_remove.len() > 0 {
                 ctx.push_patch(Patch::RemoveEvents(old_node_idx, events_to_remove));
             }
@@ -188,19 +264,37 @@ fn process_diff_job<'a>(ctx: &mut DiffContext<'a>, diff_job: DiffJob<'a>) {
 
             maybe_push_inner_html_patch(new, old_element, new_element, old_node_idx, ctx);
 
-            maybe_push_on_create_element_patch(new, old_element, new_element, old_node_idx, ctx);
+            // The old element's on remove function is called before the new element's on create
+            // function, since they might both be managing the same resource, such as a
+            // component that is rendered into the element.
             maybe_push_on_remove_element_patch(old, old_element, new_element, old_node_idx, ctx);
+            maybe_push_on_create_element_patch(new, old_element, new_element, old_node_idx, ctx);
+            maybe_push_render_component_patch(new, old_element, new_element, old_node_idx, ctx);
+            maybe_push_node_ref_patches(old, new, old_element, new_element, old_node_idx, ctx);
 
-            generate_patches_for_children(old_node_idx, old_element, new_element, ctx);
+            generate_patches_for_children(
+                old_node_idx,
+                old_element.dom_children(),
+                new_element.dom_children(),
+                ctx,
+            );
         }
-        (VirtualNode::Text(_), VirtualNode::Element(_))
-        | (VirtualNode::Element(_), VirtualNode::Text(_)) => {
+        (VirtualNode::Text(_), _)
+        | (VirtualNode::Element(_), _)
+        | (VirtualNode::Comment(_), _)
+        | (VirtualNode::Fragment(_), _) => {
             unreachable!("Unequal variant discriminants should already have been handled");
         }
+        (VirtualNode::Memo(_), _) => unreachable!("Memos were resolved to their views."),
     };
 }
 
 fn process_delete_job<'a>(ctx: &mut DiffContext<'a>, delete_job: DeleteJob<'a>) {
+    let delete_job = DeleteJob {
+        old: delete_job.old.resolve_memos(),
+        ..delete_job
+    };
+
     if let VirtualNode::Element(element_node) = delete_job.old {
         if element_node.events.len() > 0 {
             ctx.push_patch(Patch::RemoveAllVirtualEventsWithNodeIdx(
@@ -218,10 +312,33 @@ fn process_delete_job<'a>(ctx: &mut DiffContext<'a>, delete_job: DeleteJob<'a>)
             ));
         }
 
+        if element_node.special_attributes.node_ref().is_some() {
+            ctx.push_patch(Patch::SpecialAttribute(
+                PatchSpecialAttribute::ClearNodeRef(delete_job.old_node_idx, delete_job.old),
+            ));
+        }
+
+        if element_node.special_attributes.portal().is_some() {
+            ctx.push_patch(Patch::SpecialAttribute(
+                PatchSpecialAttribute::RemovePortal(delete_job.old_node_idx),
+            ));
+        }
+
         maybe_push_delete_jobs_for_children(ctx, delete_job.old);
     }
 }
 
+fn process_skip_job<'a>(ctx: &mut DiffContext<'a>, skip_job: SkipJob<'a>) {
+    if let VirtualNode::Element(element_node) = skip_job.old.resolve_memos() {
+        let children = element_node.dom_children();
+        ctx.increment_old_node_idx(children.len());
+
+        for child in children {
+            ctx.push_skip_job(SkipJob { old: child });
+        }
+    }
+}
+
 /// Push patches for replacing a node.
 fn replace_node<'a>(diff_job: DiffJob<'a>, ctx: &mut DiffContext<'a>) {
     if let Some(elem) = diff_job.old.as_velement_ref() {
@@ -240,6 +357,22 @@ fn replace_node<'a>(diff_job: DiffJob<'a>, ctx: &mut DiffContext<'a>) {
         }
         _ => {}
     };
+    match diff_job.old.as_velement_ref() {
+        Some(elem) if elem.special_attributes.node_ref().is_some() => {
+            ctx.push_patch(Patch::SpecialAttribute(
+                PatchSpecialAttribute::ClearNodeRef(diff_job.old_node_idx, diff_job.old),
+            ));
+        }
+        _ => {}
+    };
+    match diff_job.old.as_velement_ref() {
+        Some(elem) if elem.special_attributes.portal().is_some() => {
+            ctx.push_patch(Patch::SpecialAttribute(
+                PatchSpecialAttribute::RemovePortal(diff_job.old_node_idx),
+            ));
+        }
+        _ => {}
+    };
     ctx.push_patch(Patch::Replace {
         old_idx: diff_job.old_node_idx,
         new_node: diff_job.new,
@@ -250,14 +383,16 @@ fn replace_node<'a>(diff_job: DiffJob<'a>, ctx: &mut DiffContext<'a>) {
 
 fn maybe_push_delete_jobs_for_children<'a>(ctx: &mut DiffContext<'a>, node: &'a VirtualNode) {
     if let VirtualNode::Element(old_element_node) = node {
+        let children = old_element_node.dom_children();
+
         let node_idx_of_first_child = ctx.next_old_node_idx();
-        ctx.increment_old_node_idx(old_element_node.children.len());
+        ctx.increment_old_node_idx(children.len());
 
-        for (idx, child) in old_element_node.children.iter().enumerate() {
+        for (idx, child) in children.into_iter().enumerate() {
             let cur_node_idx = node_idx_of_first_child + idx as u32;
             ctx.push_delete_job(DeleteJob {
                 old_node_idx: cur_node_idx,
-                old: &child,
+                old: child,
             });
         }
     }
@@ -322,6 +457,72 @@ fn find_attributes_to_remove<'a>(
     }
 }
 
+/// Find the properties that are new or have changed, and the properties that the new element no
+/// longer has.
+fn find_properties_to_set_and_remove<'a>(
+    old_element: &'a VElement,
+    new_element: &'a VElement,
+) -> (HashMap<&'a str, &'a PropertyValue>, Vec<&'a str>) {
+    let to_set = new_element
+        .properties
+        .iter()
+        .filter(|(name, value)| old_element.properties.get(*name) != Some(*value))
+        .map(|(name, value)| (name.as_str(), value))
+        .collect();
+
+    let to_remove = old_element
+        .properties
+        .keys()
+        .filter(|name| !new_element.properties.contains_key(*name))
+        .map(|name| name.as_str())
+        .collect();
+
+    (to_set, to_remove)
+}
+
+/// Find the classes that the new element has that the old one does not, and the classes that the
+/// new element no longer has.
+fn find_classes_to_add_and_remove<'a>(
+    old_element: &'a VElement,
+    new_element: &'a VElement,
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let to_add = new_element
+        .classes
+        .iter()
+        .filter(|class| !old_element.classes.contains(class))
+        .collect();
+
+    let to_remove = old_element
+        .classes
+        .iter()
+        .filter(|class| !new_element.classes.contains(class))
+        .collect();
+
+    (to_add, to_remove)
+}
+
+/// Find the styles that are new or have changed, in the order that the new element has them, and
+/// the styles that the new element no longer has.
+fn find_styles_to_set_and_remove<'a>(
+    old_element: &'a VElement,
+    new_element: &'a VElement,
+) -> (Vec<(&'a str, &'a str)>, Vec<&'a str>) {
+    let to_set = new_element
+        .styles
+        .iter()
+        .filter(|(name, value)| old_element.styles.get(name) != Some(*value))
+        .collect();
+
+    let to_remove = old_element
+        .styles
+        .iter()
+        .filter(|(name, _)| new_element.styles.get(name).is_none())
+        .map(|(name, _)| name)
+        .collect();
+
+    (to_set, to_remove)
+}
+
 /// Add attributes from the new element that are not already on the old one or that have changed.
 fn find_events_to_add<'a>(
     events_to_add: &mut HashMap<&'a EventName, &'a EventHandler>,
@@ -413,6 +614,24 @@ fn maybe_push_on_create_element_patch<'a>(
     };
 }
 
+// A component whose host element is kept gets re-rendered with its new props. If the key changed
+// a different component takes over the element, which its on create element function handles.
+fn maybe_push_render_component_patch<'a>(
+    new: &'a VirtualNode,
+    old_element: &VElement,
+    new_element: &VElement,
+    old_node_idx: u32,
+    ctx: &mut DiffContext<'a>,
+) {
+    let new_key = new_element.special_attributes.on_render_component_key();
+
+    if new_key.is_some() && old_element.special_attributes.on_render_component_key() == new_key {
+        ctx.push_patch(Patch::SpecialAttribute(
+            PatchSpecialAttribute::RenderComponent(old_node_idx, new),
+        ));
+    }
+}
+
 fn maybe_push_on_remove_element_patch<'a>(
     old: &'a VirtualNode,
     old_element: &VElement,
@@ -437,17 +656,48 @@ fn maybe_push_on_remove_element_patch<'a>(
     }
 }
 
+// A `NodeRef` that stays on the same element already points at it. Otherwise the old `NodeRef` is
+// cleared and the new one is pointed at the element. Clearing only happens if the old `NodeRef`
+// still points at this element, so it's fine if a `NodeRef` moved to a different element.
+fn maybe_push_node_ref_patches<'a>(
+    old: &'a VirtualNode,
+    new: &'a VirtualNode,
+    old_element: &VElement,
+    new_element: &VElement,
+    old_node_idx: u32,
+    ctx: &mut DiffContext<'a>,
+) {
+    let old_node_ref = old_element.special_attributes.node_ref();
+    let new_node_ref = new_element.special_attributes.node_ref();
+
+    if old_node_ref == new_node_ref {
+        return;
+    }
+
+    if old_node_ref.is_some() {
+        ctx.push_patch(Patch::SpecialAttribute(
+            PatchSpecialAttribute::ClearNodeRef(old_node_idx, old),
+        ));
+    }
+    if new_node_ref.is_some() {
+        ctx.push_patch(Patch::SpecialAttribute(PatchSpecialAttribute::SetNodeRef(
+            old_node_idx,
+            new,
+        )));
+    }
+}
+
+/// Diff the nodes that get placed into a parent DOM node, such as an element's children with
+/// any fragments flattened.
 fn generate_patches_for_children<'a, 'b>(
     parent_old_node_idx: u32,
-    old_element: &'a VElement,
-    new_element: &'a VElement,
+    old_children: Vec<&'a VirtualNode>,
+    new_children: Vec<&'a VirtualNode>,
     ctx: &mut DiffContext<'a>,
 ) {
     // TODO: Refactor into smaller functions
     //       Optimize
 
-    let old_child_count = old_element.children.len();
-
     let mut key_to_old_child_idx: HashMap<ElementKey, usize> = HashMap::new();
     let mut key_to_new_child_idx: HashMap<ElementKey, usize> = HashMap::new();
 
@@ -461,9 +711,9 @@ fn generate_patches_for_children<'a, 'b>(
     let mut old_child_indices_of_preserved_keys = vec![];
 
     let node_idx_of_first_child = ctx.next_old_node_idx();
-    ctx.increment_old_node_idx(old_element.children.len());
+    ctx.increment_old_node_idx(old_children.len());
 
-    for (idx, new_child) in new_element.children.iter().enumerate() {
+    for (idx, new_child) in new_children.iter().copied().enumerate() {
         let implicit_key = new_tracked_indices.get_and_increment(new_child);
         let new_key = node_key(new_child, implicit_key);
 
@@ -473,7 +723,7 @@ fn generate_patches_for_children<'a, 'b>(
         }
     }
 
-    for (idx, old_child) in old_element.children.iter().enumerate() {
+    for (idx, old_child) in old_children.iter().copied().enumerate() {
         let implicit_key = old_tracked_indices.get_and_increment(old_child);
         let old_key = node_key(old_child, implicit_key);
 
@@ -487,7 +737,7 @@ fn generate_patches_for_children<'a, 'b>(
         }
     }
 
-    for new_child_idx in 0..new_element.children.len() {
+    for new_child_idx in 0..new_children.len() {
         let key = new_node_keys.get(&new_child_idx);
         let Some(key) = key else {
             continue;
@@ -529,8 +779,12 @@ fn generate_patches_for_children<'a, 'b>(
     // (Child idx, DiffJob)
     let mut jobs: Vec<(usize, DiffJob)> = vec![];
 
+    // The old child index of the last node that is staying where it is. The nodes that stay
+    // where they are must keep their old order, otherwise they would need to be moved.
+    let mut last_unmoved_old_child_idx: Option<usize> = None;
+
     let mut new_tracked_indices = TrackedImplicitlyKeyableIndices::default();
-    for (new_child_idx, new_child_node) in new_element.children.iter().enumerate() {
+    for (new_child_idx, new_child_node) in new_children.iter().copied().enumerate() {
         let implicit_key = new_tracked_indices.get_and_increment(new_child_node);
         let key = node_key(new_child_node, implicit_key);
         let old_child_idx = key.as_ref().and_then(|key| longest_increasing.get(key));
@@ -567,10 +821,11 @@ fn generate_patches_for_children<'a, 'b>(
                 maybe_push_move_before(ctx, old_idx, &mut move_before);
 
                 let old_idx = node_idx_of_first_child + *old_child_idx as u32;
+                last_unmoved_old_child_idx = Some(*old_child_idx);
 
                 let job = DiffJob {
                     old_node_idx: old_idx,
-                    old: &old_element.children[*old_child_idx],
+                    old: old_children[*old_child_idx],
                     new: new_child_node,
                 };
                 jobs.push((*old_child_idx, job));
@@ -585,7 +840,7 @@ fn generate_patches_for_children<'a, 'b>(
 
                     let job = DiffJob {
                         old_node_idx: old_idx,
-                        old: &old_element.children[*old_child_idx],
+                        old: old_children[*old_child_idx],
                         new: new_child_node,
                     };
                     jobs.push((*old_child_idx, job));
@@ -594,14 +849,23 @@ fn generate_patches_for_children<'a, 'b>(
                     {
                         let old_idx = node_idx_of_first_child + old_child_idx as u32;
 
-                        if old_child_idx != new_child_idx {
+                        // Nodes that are waiting to be placed get placed before the next unmoved
+                        // keyed node, so if there are any then this node also needs to be placed
+                        // in order to come after them.
+                        let is_unmoved = old_child_idx == new_child_idx
+                            && insert_before_or_move.is_empty()
+                            && last_unmoved_old_child_idx.is_none_or(|last| last < old_child_idx);
+
+                        if is_unmoved {
+                            last_unmoved_old_child_idx = Some(old_child_idx);
+                        } else {
                             insert_before_or_move
                                 .push(InsertBeforeOrMoveBefore::MoveBefore(old_idx));
                         }
 
                         let job = DiffJob {
                             old_node_idx: old_idx,
-                            old: &old_element.children[old_child_idx],
+                            old: old_children[old_child_idx],
                             new: new_child_node,
                         };
                         jobs.push((old_child_idx, job));
@@ -615,12 +879,12 @@ fn generate_patches_for_children<'a, 'b>(
     }
 
     jobs.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut jobs = jobs.into_iter().peekable();
 
     let mut to_remove = vec![];
-    for child_idx in 0..old_child_count {
-        if jobs.get(0).map(|j| j.0) == Some(child_idx) {
-            let job = jobs.remove(0);
-            ctx.push_diff_job(job.1);
+    for (child_idx, old_child) in old_children.iter().copied().enumerate() {
+        if let Some((_, job)) = jobs.next_if(|(job_child_idx, _)| *job_child_idx == child_idx) {
+            ctx.push_diff_job(job);
         } else {
             let node_idx = node_idx_of_first_child + child_idx as u32;
 
@@ -628,7 +892,7 @@ fn generate_patches_for_children<'a, 'b>(
 
             ctx.push_delete_job(DeleteJob {
                 old_node_idx: node_idx,
-                old: &old_element.children[child_idx],
+                old: old_child,
             });
         }
     }
@@ -796,14 +1060,38 @@ mod diff_test_case;
 mod tests {
     use super::*;
     use crate::event::EventName;
-    use crate::{html, EventAttribFn, PatchSpecialAttribute, VText, VirtualNode};
+    use crate::{html, NodeRef, PatchSpecialAttribute, VComment, VText, VirtualNode};
+    use std::cell::RefCell;
     use std::collections::HashMap;
     use std::rc::Rc;
     use virtual_node::IterableNodes;
-    use wasm_bindgen::JsValue;
 
     use super::diff_test_case::*;
 
+    /// Verify that changing a customized built-in element's `is` attribute replaces the element,
+    /// since the browser only reads `is` when creating the element.
+    #[test]
+    fn replace_node_if_is_attribute_changed() {
+        DiffTestCase {
+            old: html! { <button is="fancy-button"></button> },
+            new: html! { <button is="plain-button"></button> },
+            expected: vec![Patch::Replace {
+                old_idx: 0,
+                new_node: &html! { <button is="plain-button"></button> },
+            }],
+        }
+        .test();
+        DiffTestCase {
+            old: html! { <button></button> },
+            new: html! { <button is="fancy-button"></button> },
+            expected: vec![Patch::Replace {
+                old_idx: 0,
+                new_node: &html! { <button is="fancy-button"></button> },
+            }],
+        }
+        .test();
+    }
+
     /// Verify that we can generate patches that replace a virtual node with another one.
     #[test]
     fn replace_node() {
@@ -1208,6 +1496,77 @@ mod tests {
         .test();
     }
 
+    /// Verify that we set properties that were added or changed and remove properties that the new
+    /// node does not have.
+    #[test]
+    fn set_and_remove_properties() {
+        let mut properties = HashMap::new();
+        let indeterminate = true.into();
+        properties.insert("indeterminate", &indeterminate);
+
+        DiffTestCase {
+            old: html! { <input prop:indeterminate=false prop:title="unchanged" /> },
+            new: html! { <input prop:indeterminate=true prop:title="unchanged" /> },
+            expected: vec![Patch::SetProperties(0, properties)],
+        }
+        .test();
+
+        DiffTestCase {
+            old: html! { <input prop:indeterminate=true /> },
+            new: html! { <input indeterminate=true /> },
+            expected: vec![
+                Patch::AddAttributes(
+                    0,
+                    vec![("indeterminate", &true.into())].into_iter().collect(),
+                ),
+                Patch::RemoveProperties(0, vec!["indeterminate"]),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we only add and remove the classes that changed.
+    #[test]
+    fn add_and_remove_classes() {
+        DiffTestCase {
+            old: html! { <div class="btn primary large"></div> },
+            new: html! { <div class=["large", "btn", "active"]></div> },
+            expected: vec![
+                Patch::RemoveClasses(0, vec!["primary"]),
+                Patch::AddClasses(0, vec!["active"]),
+            ],
+        }
+        .test();
+
+        DiffTestCase {
+            old: html! { <div class="a b"></div> },
+            new: html! { <div class="b a"></div> },
+            expected: vec![],
+        }
+        .test();
+    }
+
+    /// Verify that we only set styles that changed and remove styles that are gone.
+    #[test]
+    fn set_and_remove_styles() {
+        DiffTestCase {
+            old: html! { <div style="color: red; width: 10px; margin: 0"></div> },
+            new: html! { <div style="color: blue; width: 10px; padding: 0"></div> },
+            expected: vec![
+                Patch::RemoveStyles(0, vec!["margin"]),
+                Patch::SetStyles(0, vec![("color", "blue"), ("padding", "0")]),
+            ],
+        }
+        .test();
+
+        DiffTestCase {
+            old: html! { <div style="color: red"></div> },
+            new: html! { <div style=[("color", "red")]></div> },
+            expected: vec![],
+        }
+        .test();
+    }
+
     /// Verify that we can change a text node's text.
     #[test]
     fn replace_text_node() {
@@ -1219,6 +1578,68 @@ mod tests {
         .test();
     }
 
+    /// Verify that we can change a comment node's text.
+    #[test]
+    fn change_comment_text() {
+        DiffTestCase {
+            old: VirtualNode::comment("Old"),
+            new: VirtualNode::comment("New"),
+            expected: vec![Patch::ChangeComment(0, &VComment::new("New"))],
+        }
+        .test();
+    }
+
+    /// Verify that we replace a comment node that becomes a different kind of node, and a text
+    /// node that becomes a comment.
+    #[test]
+    fn replace_comment_node() {
+        DiffTestCase {
+            old: html! { <div> {VirtualNode::comment("placeholder")} </div> },
+            new: html! { <div> <em></em> </div> },
+            expected: vec![Patch::Replace {
+                old_idx: 1,
+                new_node: &html! { <em></em> },
+            }],
+        }
+        .test();
+        DiffTestCase {
+            old: html! { <div> {"placeholder"} </div> },
+            new: html! { <div> {VirtualNode::comment("placeholder")} </div> },
+            expected: vec![Patch::Replace {
+                old_idx: 1,
+                new_node: &VirtualNode::comment("placeholder"),
+            }],
+        }
+        .test();
+    }
+
+    /// Verify that comment nodes in between keyed elements are indexed like any other node, so
+    /// that the keyed elements can be moved around them.
+    #[test]
+    fn move_keyed_elements_around_comment() {
+        DiffTestCase {
+            old: html! {
+              <div>
+                <b key="a"></b>
+                {VirtualNode::comment("separator")}
+                <i key="b"></i>
+              </div>
+            },
+            new: html! {
+              <div>
+                <i key="b"></i>
+                {VirtualNode::comment("separator")}
+                <b key="a"></b>
+              </div>
+            },
+            expected: vec![Patch::MoveNodesBefore {
+                anchor_old_node_idx: 1,
+                to_move: vec![3, 2],
+            }],
+        }
+        .test();
+    }
+
     /// If an input or textarea has a value attribute we always push a patch for setting the value
     /// attribute so that we can replace anything that might have been typed into the field.
     #[test]
@@ -1931,6 +2352,70 @@ mod tests {
         .test();
     }
 
+    /// Verify that an unkeyed node that keeps its index is still moved if keyed nodes that come
+    /// before it are waiting to be moved, since those get placed before the next unmoved node.
+    #[test]
+    fn move_unkeyed_node_after_moved_keyed_nodes() {
+        DiffTestCase {
+            old: html! {
+              <div>
+                <b key="a"></b>
+                <i key="b"></i>
+                <em key="c"></em>
+                {"text"}
+              </div>
+            },
+            new: html! {
+              <div>
+                <i key="b"></i>
+                <em key="c"></em>
+                <b key="a"></b>
+                {"text"}
+              </div>
+            },
+            expected: vec![Patch::MoveToEndOfSiblings {
+                parent_old_node_idx: 0,
+                siblings_to_move: vec![1, 4],
+            }],
+        }
+        .test();
+    }
+
+    /// Verify that an unkeyed node that keeps its index is still moved if an unmoved keyed node
+    /// that comes before it used to come after it.
+    #[test]
+    fn move_unkeyed_node_after_unmoved_keyed_node_from_later_index() {
+        DiffTestCase {
+            old: html! {
+              <div>
+                <b key="a"></b>
+                {"text"}
+                <i key="b"></i>
+                <em key="c"></em>
+              </div>
+            },
+            new: html! {
+              <div>
+                <i key="b"></i>
+                {"text"}
+                <em key="c"></em>
+                <b key="a"></b>
+              </div>
+            },
+            expected: vec![
+                Patch::MoveNodesBefore {
+                    anchor_old_node_idx: 4,
+                    to_move: vec![2],
+                },
+                Patch::MoveToEndOfSiblings {
+                    parent_old_node_idx: 0,
+                    siblings_to_move: vec![1],
+                },
+            ],
+        }
+        .test();
+    }
+
     /// Verify that we can prepend an element to a keyed list.
     #[test]
     fn prepend_new_element_to_keyed_list() {
@@ -2390,7 +2875,7 @@ mod tests {
                     anchor_old_node_idx: 1,
                     to_move: vec![2],
                 },
-                Patch::RemoveAttributes(2, vec!["class"]),
+                Patch::RemoveClasses(2, vec!["hello"]),
             ],
         }
         .test();
@@ -2863,6 +3348,404 @@ mod tests {
         .test();
     }
 
+    /// Verify that we diff a root fragment's nodes as the children of the node that they were
+    /// placed into, which has index 0.
+    #[test]
+    fn root_fragment() {
+        DiffTestCase {
+            old: html! { <> <b></b> <em></em> </> },
+            new: html! { <> <b></b> <i></i> <span></span> </> },
+            expected: vec![
+                Patch::AppendChildren {
+                    parent_old_node_idx: 0,
+                    new_nodes: vec![&html! { <span></span> }],
+                },
+                Patch::Replace {
+                    old_idx: 2,
+                    new_node: &html! { <i></i> },
+                },
+            ],
+        }
+        .test();
+
+        DiffTestCase {
+            old: html! { <> <b></b> <em></em> </> },
+            new: html! { <b id="one"></b> },
+            expected: vec![
+                Patch::RemoveChildren {
+                    parent_old_node_idx: 0,
+                    to_remove: vec![2],
+                },
+                Patch::AddAttributes(
+                    1,
+                    vec![("id", &AttributeValue::String("one".to_string()))]
+                        .into_iter()
+                        .collect(),
+                ),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we replace a root node that becomes a fragment.
+    #[test]
+    fn root_becomes_fragment() {
+        DiffTestCase {
+            old: html! { <div></div> },
+            new: html! { <> <div></div> <div></div> </> },
+            expected: vec![Patch::Replace {
+                old_idx: 0,
+                new_node: &html! { <> <div></div> <div></div> </> },
+            }],
+        }
+        .test();
+    }
+
+    /// Verify that a fragment within an element's children does not get a node index of its own,
+    /// since its children get placed into the element.
+    #[test]
+    fn fragment_children_are_indexed_as_their_parents_children() {
+        let mut old = VElement::new("div");
+        old.children = vec![
+            VirtualNode::Fragment(vec![html! { <b></b> }, html! { <em></em> }]),
+            html! { <span><a></a></span> },
+        ];
+
+        let mut new = VElement::new("div");
+        new.children = vec![
+            VirtualNode::Fragment(vec![html! { <b></b> }, html! { <i></i> }]),
+            html! { <span><strong></strong></span> },
+        ];
+
+        DiffTestCase {
+            old: old.into(),
+            new: new.into(),
+            expected: vec![
+                Patch::Replace {
+                    old_idx: 2,
+                    new_node: &html! { <i></i> },
+                },
+                Patch::Replace {
+                    old_idx: 4,
+                    new_node: &html! { <strong></strong> },
+                },
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we do not push a node ref patch when an element keeps the same `NodeRef`.
+    #[test]
+    fn same_node_ref() {
+        let node_ref = NodeRef::new();
+
+        DiffTestCase {
+            old: html! { <div ref=node_ref></div> },
+            new: html! { <div ref=node_ref></div> },
+            expected: vec![],
+        }
+        .test();
+    }
+
+    /// Verify that we clear the old `NodeRef` and set the new one when an existing element's
+    /// `NodeRef` changes.
+    #[test]
+    fn changed_node_ref() {
+        let old_ref = NodeRef::new();
+        let new_ref = NodeRef::new();
+
+        DiffTestCase {
+            old: html! { <div ref=old_ref></div> },
+            new: html! { <div ref=new_ref></div> },
+            expected: vec![
+                Patch::SpecialAttribute(PatchSpecialAttribute::ClearNodeRef(
+                    0,
+                    &html! { <div ref=old_ref></div> },
+                )),
+                Patch::SpecialAttribute(PatchSpecialAttribute::SetNodeRef(
+                    0,
+                    &html! { <div ref=new_ref></div> },
+                )),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we clear the `NodeRef`s of replaced elements and their descendants.
+    #[test]
+    fn clear_node_ref_of_replaced_elements() {
+        let parent_ref = NodeRef::new();
+        let child_ref = NodeRef::new();
+
+        DiffTestCase {
+            old: html! {
+              <div ref=parent_ref>
+                <em ref=child_ref></em>
+              </div>
+            },
+            new: html! { <span></span> },
+            expected: vec![
+                Patch::SpecialAttribute(PatchSpecialAttribute::ClearNodeRef(
+                    0,
+                    &html! {
+                      <div ref=parent_ref>
+                        <em ref=child_ref></em>
+                      </div>
+                    },
+                )),
+                Patch::Replace {
+                    old_idx: 0,
+                    new_node: &html! { <span></span> },
+                },
+                Patch::SpecialAttribute(PatchSpecialAttribute::ClearNodeRef(
+                    1,
+                    &html! { <em ref=child_ref></em> },
+                )),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we clear the `NodeRef`s of removed elements.
+    #[test]
+    fn clear_node_ref_of_removed_elements() {
+        let node_ref = NodeRef::new();
+
+        DiffTestCase {
+            old: html! {
+              <div>
+                <em ref=node_ref></em>
+              </div>
+            },
+            new: html! { <div></div> },
+            expected: vec![
+                Patch::RemoveChildren {
+                    parent_old_node_idx: 0,
+                    to_remove: vec![1],
+                },
+                Patch::SpecialAttribute(PatchSpecialAttribute::ClearNodeRef(
+                    1,
+                    &html! { <em ref=node_ref></em> },
+                )),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we patch a portal's children the same way that we patch an element's children.
+    #[test]
+    fn diff_portal_children() {
+        DiffTestCase {
+            old: html! { <div portal="body"><em>Old</em></div> },
+            new: html! { <div portal="body"><em>New</em></div> },
+            expected: vec![Patch::ChangeText(2, &VText::new("New"))],
+        }
+        .test();
+    }
+
+    /// Verify that we replace an element when its portal target changes, since its children need
+    /// to be moved into a different container.
+    #[test]
+    fn replace_element_if_portal_target_changed() {
+        DiffTestCase {
+            old: html! { <div portal="body"></div> },
+            new: html! { <div portal="#modals"></div> },
+            expected: vec![
+                Patch::SpecialAttribute(PatchSpecialAttribute::RemovePortal(0)),
+                Patch::Replace {
+                    old_idx: 0,
+                    new_node: &html! { <div portal="#modals"></div> },
+                },
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that we remove the containers of removed portals.
+    #[test]
+    fn remove_portal_of_removed_elements() {
+        DiffTestCase {
+            old: html! {
+              <div>
+                <em portal="body"></em>
+              </div>
+            },
+            new: html! { <div></div> },
+            expected: vec![
+                Patch::RemoveChildren {
+                    parent_old_node_idx: 0,
+                    to_remove: vec![1],
+                },
+                Patch::SpecialAttribute(PatchSpecialAttribute::RemovePortal(1)),
+            ],
+        }
+        .test();
+    }
+
+    /// Verify that a memo with the same hash as the old memo is neither rendered nor diffed, and
+    /// that the nodes after it still get the right node indices.
+    #[test]
+    fn skip_unchanged_memo() {
+        let old = html! {
+          // 0
+          <div>
+            // 1
+            {VirtualNode::memo(1, || html! {
+              <ul>
+                // 3
+                <li>
+                  // 5
+                  {"Row"}
+                </li>
+              </ul>
+            })}
+            // 2
+            <em>
+              // 4
+              {"Old"}
+            </em>
+          </div>
+        };
+        let new = html! {
+          <div>
+            {VirtualNode::memo(1, || unreachable!("The memo should not be rendered."))}
+            <em>{"New"}</em>
+          </div>
+        };
+
+        // The old memo's view is rendered when it gets placed into the DOM.
+        old.to_string();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![Patch::ChangeText(4, &VText::new("New"))]
+        );
+        let memo = &new.as_velement_ref().unwrap().children[0];
+        assert!(memo.as_vmemo_ref().unwrap().is_reused());
+    }
+
+    /// Verify that a memo whose hash changed is diffed against the old memo's view.
+    #[test]
+    fn diff_changed_memo() {
+        DiffTestCase {
+            old: html! {
+              <div>
+                {VirtualNode::memo(1, || html! { <ul><li>{"Old"}</li></ul> })}
+              </div>
+            },
+            new: html! {
+              <div>
+                {VirtualNode::memo(2, || html! { <ul><li>{"New"}</li></ul> })}
+              </div>
+            },
+            expected: vec![Patch::ChangeText(3, &VText::new("New"))],
+        }
+        .test();
+    }
+
+    /// Verify that we can diff a list with more nodes than the stack could hold if every node
+    /// used a stack frame, and that the changed node gets the right node index.
+    #[test]
+    fn diff_large_list() {
+        let old = large_list(LARGE_LIST_LEN, |idx| idx.to_string());
+        let new = large_list(LARGE_LIST_LEN, |idx| {
+            if idx == LARGE_LIST_LEN - 1 {
+                "Changed".to_string()
+            } else {
+                idx.to_string()
+            }
+        });
+
+        // The `<ul>`, then every `<li>`, then every `<li>`'s text.
+        let last_text_idx = 2 * LARGE_LIST_LEN as u32;
+
+        DiffTestCase {
+            old,
+            new,
+            expected: vec![Patch::ChangeText(last_text_idx, &VText::new("Changed"))],
+        }
+        .test();
+    }
+
+    /// Verify that we can remove every node from a large list, which processes a delete job for
+    /// every removed node.
+    #[test]
+    fn remove_large_list() {
+        let old = large_list(LARGE_LIST_LEN, |idx| idx.to_string());
+        let new = html! { <ul></ul> };
+
+        DiffTestCase {
+            old,
+            new,
+            expected: vec![Patch::RemoveChildren {
+                parent_old_node_idx: 0,
+                to_remove: (1..=LARGE_LIST_LEN as u32).collect(),
+            }],
+        }
+        .test();
+    }
+
+    /// Verify that we can replace a large tree, which processes a delete job for every node
+    /// within it.
+    #[test]
+    fn replace_large_tree() {
+        let old = large_list(LARGE_LIST_LEN, |idx| idx.to_string());
+        let new = html! { <ol></ol> };
+
+        DiffTestCase {
+            old,
+            new,
+            expected: vec![Patch::Replace {
+                old_idx: 0,
+                new_node: &html! { <ol></ol> },
+            }],
+        }
+        .test();
+    }
+
+    /// Verify that we can move a node within a large keyed list.
+    #[test]
+    fn move_node_within_large_keyed_list() {
+        let keyed_list = |keys: &[usize]| {
+            let mut list = VElement::new("ul");
+            for key in keys {
+                let mut item = VElement::new("li");
+                item.attrs.insert("key".to_string(), key.to_string().into());
+                list.children.push(item.into());
+            }
+            VirtualNode::from(list)
+        };
+
+        let old_keys: Vec<usize> = (0..LARGE_LIST_LEN).collect();
+        let mut new_keys = old_keys.clone();
+        new_keys.rotate_right(1);
+
+        DiffTestCase {
+            old: keyed_list(&old_keys),
+            new: keyed_list(&new_keys),
+            expected: vec![Patch::MoveNodesBefore {
+                anchor_old_node_idx: 1,
+                to_move: vec![LARGE_LIST_LEN as u32],
+            }],
+        }
+        .test();
+    }
+
+    // Enough nodes to overflow the stack if the diff used a stack frame per node.
+    const LARGE_LIST_LEN: usize = 100_000;
+
+    /// A `<ul>` with `len` `<li>`s that each contain a text node.
+    fn large_list(len: usize, text: impl Fn(usize) -> String) -> VirtualNode {
+        let mut list = VElement::new("ul");
+        for idx in 0..len {
+            let mut item = VElement::new("li");
+            item.children.push(VirtualNode::text(text(idx)));
+            list.children.push(item.into());
+        }
+
+        list.into()
+    }
+
     fn node_with_key(tag: &'static str, key: &'static str) -> VirtualNode {
         let mut node = VirtualNode::element(tag);
         node.as_velement_mut()
@@ -2884,14 +3767,14 @@ mod tests {
         node.as_velement_mut()
             .unwrap()
             .special_attributes
-            .set_on_create_element(on_create_elem_id, |_: web_sys::Element| {});
+            .set_on_create_element_no_args(on_create_elem_id, || {});
     }
 
     fn set_on_remove_elem_with_unique_id(node: &mut VirtualNode, on_remove_elem_id: &'static str) {
         node.as_velement_mut()
             .unwrap()
             .special_attributes
-            .set_on_remove_element(on_remove_elem_id, |_: web_sys::Element| {});
+            .set_on_remove_element_no_args(on_remove_elem_id, || {});
     }
 
     fn set_dangerous_inner_html(node: &mut VirtualNode, html: &str) {
@@ -2921,7 +3804,7 @@ mod tests {
     }
 
     fn mock_event_handler() -> EventHandler {
-        EventHandler::UnsupportedSignature(EventAttribFn(Rc::new(Box::new(JsValue::NULL))))
+        EventHandler::NoArgs(Rc::new(RefCell::new(|| {})))
     }
 
     fn onclick_name() -> EventName {
@@ -3,14 +3,20 @@
 //!
 //! The virtual dom works on both the client and server. On the client we'll render
 //! to an `HtmlElement`, and on the server we render to a `String`.
+//!
+//! Everything that touches the DOM, such as `PercyDom` and applying patches, is only available
+//! on wasm32 targets.
 
 #![deny(missing_docs)]
 
+#[cfg(target_arch = "wasm32")]
 extern crate wasm_bindgen;
 
+#[cfg(target_arch = "wasm32")]
 pub use wasm_bindgen::JsCast;
 // Used so that `html!` calls work when people depend on this crate since `html!` needs
 // access to `Closure` when creating event handlers.
+#[cfg(target_arch = "wasm32")]
 pub use wasm_bindgen::prelude::Closure;
 
 #[cfg(feature = "macro")]
@@ -20,13 +26,17 @@ pub use virtual_node::*;
 pub use crate::diff::*;
 pub use crate::patch::*;
 
+#[cfg(target_arch = "wasm32")]
 pub use self::pdom::PercyDom;
 
 mod diff;
 mod patch;
+#[cfg(target_arch = "wasm32")]
 mod pdom;
 
+#[cfg(target_arch = "wasm32")]
 pub mod render;
+#[cfg(target_arch = "wasm32")]
 pub mod single_page_app;
 
 /// Exports structs and macros that you'll almost always want access to in a virtual-dom
@@ -36,12 +46,18 @@ pub mod prelude {
 
     pub use std::vec::IntoIter;
 
+    #[cfg(target_arch = "wasm32")]
     pub use wasm_bindgen::prelude::Closure;
 
     #[cfg(feature = "macro")]
     pub use html_macro::html;
-    pub use virtual_node::{EventAttribFn, IterableNodes, View};
+    #[cfg(target_arch = "wasm32")]
+    pub use virtual_node::EventAttribFn;
+    pub use virtual_node::{
+        assert_vnode_eq, assert_vnode_snapshot, Component, IterableNodes, NodeRef, Scope, View,
+    };
 
+    #[cfg(target_arch = "wasm32")]
     pub use crate::pdom::PercyDom;
     pub use crate::VirtualNode;
 
@@ -49,6 +65,7 @@ pub mod prelude {
     #[doc(hidden)]
     pub mod __html_macro_helpers__ {
         pub use virtual_node::event;
+        #[cfg(target_arch = "wasm32")]
         pub use web_sys;
     }
 }
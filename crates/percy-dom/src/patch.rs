@@ -1,13 +1,17 @@
 //! Our Patch enum is intentionally kept in it's own file for easy inclusion into
 //! The Percy Book.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+#[cfg(target_arch = "wasm32")]
+use std::collections::HashSet;
 
+#[cfg(target_arch = "wasm32")]
 pub use apply_patches::patch;
 
 use crate::event::{EventHandler, EventName};
-use crate::{AttributeValue, VText, VirtualNode};
+use crate::{AttributeValue, PropertyValue, VComment, VText, VirtualNode};
 
+#[cfg(target_arch = "wasm32")]
 mod apply_patches;
 
 // TODO: pub(crate) BreadthFirstNodeIdx(pub u32);
@@ -108,8 +112,28 @@ pub enum Patch<'a> {
     AddAttributes(BreadthFirstNodeIdx, HashMap<&'a str, &'a AttributeValue>),
     /// Remove attributes that the old node had that the new node doesn't
     RemoveAttributes(BreadthFirstNodeIdx, Vec<&'a str>),
+    /// Set the DOM properties that the new node has that the old node does not, or that have
+    /// changed.
+    SetProperties(BreadthFirstNodeIdx, HashMap<&'a str, &'a PropertyValue>),
+    /// Clear the DOM properties that the old node had that the new node doesn't by setting them
+    /// to `undefined`.
+    RemoveProperties(BreadthFirstNodeIdx, Vec<&'a str>),
+    /// Add the classes that the new node has that the old node does not using
+    /// `classList.add`.
+    AddClasses(BreadthFirstNodeIdx, Vec<&'a str>),
+    /// Remove the classes that the old node had that the new node doesn't using
+    /// `classList.remove`.
+    RemoveClasses(BreadthFirstNodeIdx, Vec<&'a str>),
+    /// Set the inline styles that the new node has that the old node does not, or that have
+    /// changed, using `style.setProperty`.
+    SetStyles(BreadthFirstNodeIdx, Vec<(&'a str, &'a str)>),
+    /// Remove the inline styles that the old node had that the new node doesn't using
+    /// `style.removeProperty`.
+    RemoveStyles(BreadthFirstNodeIdx, Vec<&'a str>),
     /// Change the text of a Text node.
     ChangeText(BreadthFirstNodeIdx, &'a VText),
+    /// Change the text of a Comment node.
+    ChangeComment(BreadthFirstNodeIdx, &'a VComment),
     /// Patches that apply to [`SpecialAttributes`].
     SpecialAttribute(PatchSpecialAttribute<'a>),
     /// Insert events in the EventsByNodeIdx.
@@ -136,12 +160,27 @@ pub enum PatchSpecialAttribute<'a> {
     CallOnCreateElemOnExistingNode(BreadthFirstNodeIdx, &'a VirtualNode),
     /// Call the [`SpecialAttributes.on_remove_elem`] function on the node.
     CallOnRemoveElem(BreadthFirstNodeIdx, &'a VirtualNode),
+    /// Point the new node's [`SpecialAttributes.node_ref`] at the existing DOM node.
+    ///
+    /// We only push this patch for existing nodes.
+    /// New nodes get their node_ref set automatically when they are created.
+    SetNodeRef(BreadthFirstNodeIdx, &'a VirtualNode),
+    /// Clear the old node's [`SpecialAttributes.node_ref`] if it still points at the DOM node.
+    ClearNodeRef(BreadthFirstNodeIdx, &'a VirtualNode),
+    /// Remove the container that the old node's [`SpecialAttributes.portal`] placed its children
+    /// into.
+    RemovePortal(BreadthFirstNodeIdx),
+    /// Call the new node's [`SpecialAttributes.on_render_component`] function on the existing
+    /// node, which re-renders the component that was mounted into it.
+    RenderComponent(BreadthFirstNodeIdx, &'a VirtualNode),
     /// Set the node's innerHTML using the [`SpecialAttributes.dangerous_inner_html`].
     SetDangerousInnerHtml(BreadthFirstNodeIdx, &'a VirtualNode),
     /// Set the node's innerHTML to an empty string.
     RemoveDangerousInnerHtml(BreadthFirstNodeIdx),
 }
 
+// Only used when applying patches, which requires the DOM.
+#[cfg(target_arch = "wasm32")]
 impl<'a> Patch<'a> {
     /// Every Patch is meant to be applied to a specific node within the DOM. Get the
     /// index of the DOM node that this patch should apply to. DOM nodes are indexed
@@ -155,7 +194,14 @@ impl<'a> Patch<'a> {
             Patch::Replace { old_idx, .. } => *old_idx,
             Patch::AddAttributes(node_idx, _) => *node_idx,
             Patch::RemoveAttributes(node_idx, _) => *node_idx,
+            Patch::SetProperties(node_idx, _) => *node_idx,
+            Patch::RemoveProperties(node_idx, _) => *node_idx,
+            Patch::AddClasses(node_idx, _) => *node_idx,
+            Patch::RemoveClasses(node_idx, _) => *node_idx,
+            Patch::SetStyles(node_idx, _) => *node_idx,
+            Patch::RemoveStyles(node_idx, _) => *node_idx,
             Patch::ChangeText(node_idx, _) => *node_idx,
+            Patch::ChangeComment(node_idx, _) => *node_idx,
             Patch::ValueAttributeUnchanged(node_idx, _) => *node_idx,
             Patch::CheckedAttributeUnchanged(node_idx, _) => *node_idx,
             Patch::SpecialAttribute(special) => match special {
@@ -163,6 +209,10 @@ impl<'a> Patch<'a> {
                 PatchSpecialAttribute::SetDangerousInnerHtml(node_idx, _) => *node_idx,
                 PatchSpecialAttribute::RemoveDangerousInnerHtml(node_idx) => *node_idx,
                 PatchSpecialAttribute::CallOnRemoveElem(node_idx, _) => *node_idx,
+                PatchSpecialAttribute::SetNodeRef(node_idx, _) => *node_idx,
+                PatchSpecialAttribute::ClearNodeRef(node_idx, _) => *node_idx,
+                PatchSpecialAttribute::RemovePortal(node_idx) => *node_idx,
+                PatchSpecialAttribute::RenderComponent(node_idx, _) => *node_idx,
             },
             Patch::RemoveEvents(node_idx, _) => *node_idx,
             Patch::AddEvents(node_idx, _) => *node_idx,
@@ -209,9 +259,30 @@ impl<'a> Patch<'a> {
             Patch::RemoveAttributes(node_idx, _) => {
                 to_find.insert(*node_idx);
             }
+            Patch::SetProperties(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
+            Patch::RemoveProperties(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
+            Patch::AddClasses(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
+            Patch::RemoveClasses(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
+            Patch::SetStyles(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
+            Patch::RemoveStyles(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
             Patch::ChangeText(node_idx, _) => {
                 to_find.insert(*node_idx);
             }
+            Patch::ChangeComment(node_idx, _) => {
+                to_find.insert(*node_idx);
+            }
             Patch::ValueAttributeUnchanged(node_idx, _) => {
                 to_find.insert(*node_idx);
             }
@@ -231,6 +302,18 @@ impl<'a> Patch<'a> {
                 PatchSpecialAttribute::CallOnRemoveElem(node_idx, _) => {
                     to_find.insert(*node_idx);
                 }
+                PatchSpecialAttribute::SetNodeRef(node_idx, _) => {
+                    to_find.insert(*node_idx);
+                }
+                PatchSpecialAttribute::ClearNodeRef(node_idx, _) => {
+                    to_find.insert(*node_idx);
+                }
+                PatchSpecialAttribute::RemovePortal(node_idx) => {
+                    to_find.insert(*node_idx);
+                }
+                PatchSpecialAttribute::RenderComponent(node_idx, _) => {
+                    to_find.insert(*node_idx);
+                }
             },
             Patch::RemoveEvents(node_idx, _) => {
                 to_find.insert(*node_idx);
@@ -4,20 +4,30 @@ use std::collections::HashSet;
 use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
-use virtual_node::event::{insert_non_delegated_event, ElementEventsId, VirtualEventNode};
-use virtual_node::VIRTUAL_NODE_MARKER_PROPERTY;
+use virtual_node::event::{
+    insert_non_delegated_event, remove_non_delegated_event, ElementEventsId, VirtualEventNode,
+};
+use virtual_node::namespace;
+use virtual_node::{
+    portal_container, template_shadow_root, TEXT_SEPARATOR, VIRTUAL_NODE_MARKER_PROPERTY,
+};
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
-use web_sys::{Element, HtmlInputElement, HtmlTextAreaElement, Node, Text};
+use web_sys::{
+    Comment, CssStyleDeclaration, Element, HtmlInputElement, HtmlTextAreaElement, Node, Text,
+};
 
 use crate::event::VirtualEvents;
 use crate::patch::Patch;
-use crate::{AttributeValue, PatchSpecialAttribute, VirtualNode};
+use crate::{AttributeValue, PatchSpecialAttribute, Styles, VirtualNode};
 
 /// Apply all of the patches to our old root node in order to create the new root node
 /// that we desire. Also, update the `VirtualEvents` with the new virtual node's event callbacks.
 ///
 /// This is usually used after diffing two virtual nodes.
+///
+/// If the old virtual node was a fragment, the root DOM node is the node that the fragment's nodes
+/// were placed into, and the new virtual node must also be a fragment.
 // Tested in a browser in `percy-dom/tests`
 pub fn patch<N: Into<Node>>(
     root_dom_node: N,
@@ -64,6 +74,14 @@ pub fn patch<N: Into<Node>>(
                 ElementOrText::Text(text_node) => {
                     apply_text_patch(&text_node, &patch, virtual_events, &events_elem.events_node)?;
                 }
+                ElementOrText::Comment(comment_node) => {
+                    apply_comment_patch(
+                        comment_node,
+                        patch,
+                        virtual_events,
+                        &events_elem.events_node,
+                    )?;
+                }
             };
         } else {
             // Right now this can happen if something outside of Percy goes into the DOM and
@@ -96,6 +114,7 @@ struct NodeToProcess {
 enum ElementOrText {
     Element(Element),
     Text(Text),
+    Comment(Comment),
 }
 struct EventsNodeAndParent {
     events_node: Rc<RefCell<VirtualEventNode>>,
@@ -114,6 +133,11 @@ impl PatchContext {
                 let text = ElementOrText::Text(node.clone().unchecked_into());
                 self.found_nodes.insert(node_idx, (node, text, events_node));
             }
+            Node::COMMENT_NODE => {
+                let comment = ElementOrText::Comment(node.clone().unchecked_into());
+                self.found_nodes
+                    .insert(node_idx, (node, comment, events_node));
+            }
             other => unimplemented!("Unsupported root node type: {}", other),
         }
     }
@@ -150,7 +174,7 @@ fn find_nodes(ctx: &mut PatchContext) {
     }
 
     // We use child_nodes() instead of children() because children() ignores text nodes
-    let children = node.child_nodes();
+    let children = children_parent(&node).child_nodes();
     let child_node_count = children.length();
 
     if child_node_count == 0 {
@@ -161,9 +185,18 @@ fn find_nodes(ctx: &mut PatchContext) {
     let events_node_elem = &events_node_borrow.as_element().unwrap();
     let mut next_child = events_node_elem.first_child();
 
+    // An element without any virtual children can still have DOM children that another
+    // `PercyDom` manages, such as a component's host element.
+    if next_child.is_none() {
+        return;
+    }
+
     for i in 0..child_node_count {
         let child_node = children.item(i).unwrap();
 
+        // This also skips the `TEXT_SEPARATOR` comments that separate neighboring text nodes, since they
+        // are never given the virtual node marker. Comment nodes that were created from a
+        // `VirtualNode::Comment` are marked, so they are indexed like any other node.
         if !was_created_by_percy(&child_node) {
             continue;
         }
@@ -171,7 +204,7 @@ fn find_nodes(ctx: &mut PatchContext) {
         let next_node_idx = ctx.next_node_idx;
 
         match child_node.node_type() {
-            Node::ELEMENT_NODE | Node::TEXT_NODE => {
+            Node::ELEMENT_NODE | Node::TEXT_NODE | Node::COMMENT_NODE => {
                 let events_child_node = next_child.unwrap();
                 next_child = events_child_node.borrow().next_sibling().cloned();
 
@@ -184,12 +217,6 @@ fn find_nodes(ctx: &mut PatchContext) {
 
                 ctx.next_node_idx += 1;
             }
-            Node::COMMENT_NODE => {
-                // At this time we do not support user entered comment nodes, so if we see a comment
-                // then it was a delimiter created by percy-dom in order to ensure that two
-                // neighboring text nodes did not get merged into one by the browser. So we skip
-                // over this percy-dom generated comment node.
-            }
             _other => {
                 // Ignoring unsupported child node type
                 // TODO: What do we do with this situation?
@@ -203,22 +230,35 @@ fn overwrite_events(
     events_node: Rc<RefCell<VirtualEventNode>>,
     virtual_events: &mut VirtualEvents,
 ) {
-    if let Some(elem) = node.as_velement_ref() {
-        let events_node = events_node.borrow();
-        let events_node = events_node.as_element().unwrap();
-        let events_id = events_node.events_id();
+    let children = match node {
+        VirtualNode::Element(elem) => elem.dom_children(),
+        // A fragment's events node holds the events of the nodes that the fragment placed into
+        // the DOM.
+        VirtualNode::Fragment(_) => node.dom_nodes(),
+        VirtualNode::Text(_) | VirtualNode::Comment(_) => return,
+        // A reused memo's events node already holds the events of the view that it reused.
+        VirtualNode::Memo(memo) if memo.is_reused() => return,
+        VirtualNode::Memo(memo) => {
+            return overwrite_events(memo.view(), events_node, virtual_events);
+        }
+    };
+
+    let events_node = events_node.borrow();
+    let events_node = events_node.as_element().unwrap();
+    let events_id = events_node.events_id();
 
+    if let Some(elem) = node.as_velement_ref() {
         for (event_name, event) in elem.events.iter() {
             virtual_events.overwrite_event_attrib_fn(&events_id, event_name, event.clone());
         }
+    }
 
-        let mut events_child = events_node.first_child();
+    let mut events_child = events_node.first_child();
 
-        for child in elem.children.iter() {
-            let e = events_child.unwrap();
-            events_child = e.borrow().next_sibling().cloned();
-            overwrite_events(child, e, virtual_events);
-        }
+    for child in children {
+        let e = events_child.unwrap();
+        events_child = e.borrow().next_sibling().cloned();
+        overwrite_events(child, e, virtual_events);
     }
 }
 
@@ -234,7 +274,7 @@ fn apply_element_patch(
             for (attrib_name, attrib_val) in attributes.iter() {
                 match attrib_val {
                     AttributeValue::String(val_str) => {
-                        node.set_attribute(attrib_name, val_str)?;
+                        namespace::set_attribute(node, attrib_name, val_str)?;
 
                         if attrib_name == &"value" {
                             maybe_set_value_property(node, val_str)
@@ -248,9 +288,9 @@ fn apply_element_patch(
                         if *attrib_name == "checked" {
                             maybe_set_checked_property(node, *val_bool);
                         } else if *val_bool {
-                            node.set_attribute(attrib_name, "")?;
+                            namespace::set_attribute(node, attrib_name, "")?;
                         } else {
-                            node.remove_attribute(attrib_name)?;
+                            namespace::remove_attribute(node, attrib_name)?;
                         }
                     }
                 }
@@ -260,7 +300,54 @@ fn apply_element_patch(
         }
         Patch::RemoveAttributes(_node_idx, attributes) => {
             for attrib_name in attributes.iter() {
-                node.remove_attribute(attrib_name)?;
+                namespace::remove_attribute(node, attrib_name)?;
+            }
+
+            Ok(())
+        }
+        Patch::SetProperties(_node_idx, properties) => {
+            for (name, value) in properties.iter() {
+                Reflect::set(node, &JsValue::from_str(name), &(*value).clone().into())?;
+            }
+
+            Ok(())
+        }
+        Patch::AddClasses(_node_idx, classes) => {
+            let class_list = node.class_list();
+            for class in classes.iter() {
+                class_list.add_1(class)?;
+            }
+
+            Ok(())
+        }
+        Patch::RemoveClasses(_node_idx, classes) => {
+            let class_list = node.class_list();
+            for class in classes.iter() {
+                class_list.remove_1(class)?;
+            }
+
+            Ok(())
+        }
+        Patch::SetStyles(_node_idx, styles) => {
+            let style = element_style(node);
+            for (name, value) in styles.iter() {
+                let (value, priority) = Styles::split_priority(value);
+                style.set_property_with_priority(name, value, priority)?;
+            }
+
+            Ok(())
+        }
+        Patch::RemoveStyles(_node_idx, styles) => {
+            let style = element_style(node);
+            for name in styles.iter() {
+                style.remove_property(name)?;
+            }
+
+            Ok(())
+        }
+        Patch::RemoveProperties(_node_idx, properties) => {
+            for name in properties.iter() {
+                Reflect::set(node, &JsValue::from_str(name), &JsValue::UNDEFINED)?;
             }
 
             Ok(())
@@ -269,7 +356,7 @@ fn apply_element_patch(
             old_idx: _,
             new_node,
         } => {
-            let (created_node, events) = new_node.create_dom_node(virtual_events);
+            let (created_node, events) = create_replacement(new_node, node, virtual_events);
 
             node.replace_with_with_node_1(&created_node)?;
 
@@ -282,12 +369,13 @@ fn apply_element_patch(
             anchor_old_node_idx: _,
             new_nodes,
         } => {
-            let parent = node.parent_element().unwrap();
+            let parent = node.parent_node().unwrap();
 
             let events_parent = events_elem_and_parent.parent.as_ref().unwrap();
 
             for new_node in new_nodes {
-                let (created_node, events) = new_node.create_dom_node(virtual_events);
+                let (created_node, events) =
+                    new_node.create_dom_node_within(&parent, virtual_events);
 
                 parent.insert_before(&created_node, Some(&node))?;
                 events_parent.borrow_mut().insert_before(
@@ -302,7 +390,7 @@ fn apply_element_patch(
             anchor_old_node_idx: _,
             to_move,
         } => {
-            let parent = node.parent_element().unwrap();
+            let parent = node.parent_node().unwrap();
 
             let events_parent = events_elem_and_parent.parent.as_ref().unwrap();
             let mut events_parent = events_parent.borrow_mut();
@@ -326,7 +414,7 @@ fn apply_element_patch(
             parent_old_node_idx: _,
             to_remove,
         } => {
-            let parent = node;
+            let parent = children_parent(node);
 
             let events_elem = events_elem_and_parent.events_node.borrow_mut();
             let mut events_parent = events_elem;
@@ -344,13 +432,29 @@ fn apply_element_patch(
             parent_old_node_idx: _,
             new_nodes,
         } => {
-            let parent = &node;
+            let parent = &children_parent(node);
 
             let events_elem = events_elem_and_parent.events_node.borrow_mut();
             let mut events_parent = events_elem;
 
             for new_node in new_nodes {
-                let (created_node, events) = new_node.create_dom_node(virtual_events);
+                let (created_node, events) =
+                    new_node.create_dom_node_within(parent, virtual_events);
+
+                // Separate neighboring text nodes the same way that we do when creating an
+                // element, so that our DOM matches our server side rendered HTML.
+                // See `crates/virtual-node/src/create_element.rs`.
+                let last_child_is_text = parent
+                    .last_child()
+                    .map(|last| last.node_type() == Node::TEXT_NODE)
+                    .unwrap_or(false);
+                if last_child_is_text && created_node.node_type() == Node::TEXT_NODE {
+                    let separator = created_node
+                        .owner_document()
+                        .unwrap()
+                        .create_comment(TEXT_SEPARATOR);
+                    parent.append_child(&separator)?;
+                }
 
                 parent.append_child(&created_node)?;
 
@@ -366,7 +470,7 @@ fn apply_element_patch(
             parent_old_node_idx: _,
             siblings_to_move,
         } => {
-            let parent = node;
+            let parent = children_parent(node);
 
             let events_elem = events_elem_and_parent.events_node.borrow_mut();
             let mut events_parent = events_elem;
@@ -388,6 +492,9 @@ fn apply_element_patch(
         Patch::ChangeText(_node_idx, _new_node) => {
             unreachable!("Elements should not receive ChangeText patches.")
         }
+        Patch::ChangeComment(_node_idx, _new_node) => {
+            unreachable!("Elements should not receive ChangeComment patches.")
+        }
         Patch::ValueAttributeUnchanged(_node_idx, value) => {
             node.set_attribute("value", value.as_string().unwrap())?;
             maybe_set_value_property(node, value.as_string().unwrap());
@@ -409,6 +516,15 @@ fn apply_element_patch(
 
                 Ok(())
             }
+            PatchSpecialAttribute::RenderComponent(_node_idx, new_node) => {
+                new_node
+                    .as_velement_ref()
+                    .unwrap()
+                    .special_attributes
+                    .maybe_call_on_render_component(&node);
+
+                Ok(())
+            }
             PatchSpecialAttribute::CallOnRemoveElem(_, old_node) => {
                 old_node
                     .as_velement_ref()
@@ -418,6 +534,32 @@ fn apply_element_patch(
 
                 Ok(())
             }
+            PatchSpecialAttribute::SetNodeRef(_node_idx, new_node) => {
+                new_node
+                    .as_velement_ref()
+                    .unwrap()
+                    .special_attributes
+                    .maybe_set_node_ref(node);
+
+                Ok(())
+            }
+            PatchSpecialAttribute::ClearNodeRef(_node_idx, old_node) => {
+                old_node
+                    .as_velement_ref()
+                    .unwrap()
+                    .special_attributes
+                    .maybe_clear_node_ref(node);
+
+                Ok(())
+            }
+            PatchSpecialAttribute::RemovePortal(_node_idx) => {
+                if let Some(container) = portal_container(node) {
+                    virtual_events.remove_portal_container(&container);
+                    container.remove();
+                }
+
+                Ok(())
+            }
             PatchSpecialAttribute::SetDangerousInnerHtml(_node_idx, new_node) => {
                 let new_inner_html = new_node
                     .as_velement_ref()
@@ -459,17 +601,11 @@ fn apply_element_patch(
             let events_id = ctx.events_id_for_old_node_idx.get(node_idx).unwrap();
 
             for (event_name, _event) in events {
-                if !event_name.is_delegated() {
-                    let wrapper =
-                        virtual_events.remove_non_delegated_event_wrapper(events_id, event_name);
-                    node.remove_event_listener_with_callback(
-                        event_name.without_on_prefix(),
-                        wrapper.as_ref().as_ref().unchecked_ref(),
-                    )
-                    .unwrap();
+                if event_name.is_delegated() {
+                    virtual_events.remove_event_handler(events_id, event_name);
+                } else {
+                    remove_non_delegated_event(node, event_name, events_id, virtual_events);
                 }
-
-                virtual_events.remove_event_handler(events_id, event_name);
             }
 
             Ok(())
@@ -482,6 +618,33 @@ fn apply_element_patch(
     }
 }
 
+/// The DOM node that a node's children are in.
+///
+/// A declarative shadow root's `<template>` placed its children into a shadow root, and a portal
+/// placed its children into a container within its target.
+fn children_parent(node: &Node) -> Node {
+    if let Some(shadow_root) = template_shadow_root(node) {
+        return shadow_root.into();
+    }
+    if let Some(container) = portal_container(node) {
+        return container.into();
+    }
+
+    node.clone()
+}
+
+/// Create the DOM node that will replace `node`, in the same namespace as the node's parent.
+fn create_replacement(
+    new_node: &VirtualNode,
+    node: &Node,
+    events: &mut VirtualEvents,
+) -> (Node, VirtualEventNode) {
+    match node.parent_node() {
+        Some(parent) => new_node.create_dom_node_within(&parent, events),
+        None => new_node.create_dom_node(events),
+    }
+}
+
 fn apply_text_patch(
     node: &Text,
     patch: &Patch,
@@ -496,7 +659,7 @@ fn apply_text_patch(
             old_idx: _,
             new_node,
         } => {
-            let (elem, enode) = new_node.create_dom_node(events);
+            let (elem, enode) = create_replacement(new_node, node, events);
             node.replace_with_with_node_1(&elem)?;
 
             events_elem.borrow_mut().replace_with_node(enode);
@@ -512,6 +675,42 @@ fn apply_text_patch(
     Ok(())
 }
 
+fn apply_comment_patch(
+    node: &Comment,
+    patch: &Patch,
+    events: &mut VirtualEvents,
+    events_elem: &Rc<RefCell<VirtualEventNode>>,
+) -> Result<(), JsValue> {
+    match patch {
+        Patch::ChangeComment(_node_idx, new_node) => {
+            node.set_node_value(Some(&new_node.text));
+        }
+        Patch::Replace {
+            old_idx: _,
+            new_node,
+        } => {
+            let (elem, enode) = create_replacement(new_node, node, events);
+            node.replace_with_with_node_1(&elem)?;
+
+            events_elem.borrow_mut().replace_with_node(enode);
+        }
+        other => {
+            unreachable!(
+                "Comment nodes should only receive ChangeComment or Replace patches, not {:?}.",
+                other,
+            )
+        }
+    };
+
+    Ok(())
+}
+
+fn element_style(node: &Element) -> CssStyleDeclaration {
+    Reflect::get(node, &JsValue::from_str("style"))
+        .unwrap()
+        .unchecked_into()
+}
+
 // See crates/percy-dom/tests/value_attribute.rs
 fn maybe_set_value_property(node: &Element, value: &str) {
     if let Some(input_node) = node.dyn_ref::<HtmlInputElement>() {
@@ -1,10 +1,11 @@
 //! Diff virtual-doms and patch the real DOM
 
 use crate::diff::diff;
-use crate::event::VirtualEvents;
-use crate::patch::patch;
+use crate::event::{EventName, VirtualEvents};
+use crate::patch::{patch, Patch};
 use std::collections::HashMap;
-use virtual_node::VirtualNode;
+use virtual_node::component::{set_mount_component_fn, ComponentRegistry, ComponentRenderer};
+use virtual_node::{HydrationMismatch, VirtualNode};
 use wasm_bindgen::JsValue;
 use web_sys::{Element, Node};
 
@@ -20,23 +21,36 @@ pub struct PercyDom {
     /// We keep these around so that they don't get dropped,  (and thus stop working).
     pub events: VirtualEvents,
     root_node: Node,
+    /// The components in this `PercyDom`'s DOM, so that their state is kept when they get
+    /// re-rendered by their parent.
+    components: ComponentRegistry,
     // We hold onto these since if we drop the listener it can no longer be called.
-    event_delegation_listeners: HashMap<&'static str, Box<dyn AsRef<JsValue>>>,
+    event_delegation_listeners: HashMap<EventName, Box<dyn AsRef<JsValue>>>,
 }
 
 impl PercyDom {
     /// Create a new `PercyDom`.
     ///
     /// A root `Node` will be created but not added to your DOM.
+    ///
+    /// If your root virtual node is a fragment its nodes are created inside of a
+    /// `DocumentFragment`. Use [`PercyDom::new_append_to_mount`] or
+    /// [`PercyDom::new_replace_mount`] to place them into your DOM, after which the node that they
+    /// were placed into becomes the root node.
     pub fn new(current_vdom: VirtualNode) -> PercyDom {
+        set_mount_component_fn(mount_component);
+
+        let components = ComponentRegistry::new();
         let mut events = VirtualEvents::new();
-        let (created_node, events_node) = current_vdom.create_dom_node(&mut events);
+        let (created_node, events_node) =
+            components.enter(|| current_vdom.create_dom_node(&mut events));
         events.set_root(events_node);
 
         let mut pdom = PercyDom {
             current_vdom,
             root_node: created_node,
             events,
+            components,
             event_delegation_listeners: HashMap::new(),
         };
         pdom.attach_event_listeners();
@@ -49,12 +63,16 @@ impl PercyDom {
     /// A root `Node` will be created and append (as a child) to your passed
     /// in mount element.
     pub fn new_append_to_mount(current_vdom: VirtualNode, mount: &Element) -> PercyDom {
-        let pdom = Self::new(current_vdom);
+        let mut pdom = Self::new(current_vdom);
 
         mount
             .append_child(&pdom.root_node)
             .expect("Could not append child to mount");
 
+        if pdom.current_vdom.as_fragment_ref().is_some() {
+            pdom.set_root_node(mount.clone().into());
+        }
+
         pdom
     }
 
@@ -62,13 +80,61 @@ impl PercyDom {
     ///
     /// A root `Node` will be created and it will replace your passed in mount
     /// element.
+    ///
+    /// If your root virtual node is a fragment, the mount element's parent becomes the root node.
     pub fn new_replace_mount(current_vdom: VirtualNode, mount: Element) -> PercyDom {
-        let pdom = Self::new(current_vdom);
+        let mut pdom = Self::new(current_vdom);
+        let mount_parent = mount.parent_node();
 
         mount
             .replace_with_with_node_1(&pdom.root_node)
             .expect("Could not replace mount element");
 
+        if pdom.current_vdom.as_fragment_ref().is_some() {
+            pdom.set_root_node(
+                mount_parent.expect("A fragment's mount element must have a parent"),
+            );
+        }
+
+        pdom
+    }
+
+    /// Create a new `PercyDom` from DOM nodes that already exist in the page.
+    ///
+    /// Your passed in mount element should be the root element of the HTML that was rendered
+    /// from your `VirtualNode`, typically on the server using `.to_string()`.
+    ///
+    /// Instead of creating new DOM nodes we re-use the existing ones, adding events and calling
+    /// any `on_create_element` functions.
+    ///
+    /// If your root virtual node is a fragment, your passed in mount element should be the element
+    /// that the fragment's nodes were rendered into.
+    ///
+    /// If the DOM does not match your `VirtualNode` a warning is logged to the console for every
+    /// mismatch and the DOM is patched to match your `VirtualNode`.
+    pub fn hydrate(current_vdom: VirtualNode, mount: Element) -> PercyDom {
+        set_mount_component_fn(mount_component);
+
+        let components = ComponentRegistry::new();
+        let mut events = VirtualEvents::new();
+        let mut mismatches = vec![];
+        let (root_node, events_node) = components
+            .enter(|| current_vdom.hydrate_dom_node(mount.into(), &mut events, &mut mismatches));
+        events.set_root(events_node);
+
+        for mismatch in mismatches {
+            warn_hydration_mismatch(&mismatch);
+        }
+
+        let mut pdom = PercyDom {
+            current_vdom,
+            root_node,
+            events,
+            components,
+            event_delegation_listeners: HashMap::new(),
+        };
+        pdom.attach_event_listeners();
+
         pdom
     }
 
@@ -76,25 +142,110 @@ impl PercyDom {
     ///
     /// Then use that diff to patch the real DOM in the user's browser so that they are
     /// seeing the latest state of the application.
+    ///
+    /// Once your root virtual node is a fragment its nodes are always placed into the same root
+    /// node, so a non-fragment root is treated as a fragment with one child.
     pub fn update(&mut self, new_vdom: VirtualNode) {
+        let current_root_is_fragment = self.current_vdom.as_fragment_ref().is_some();
+        let new_vdom = match new_vdom {
+            VirtualNode::Fragment(_) => new_vdom,
+            new_vdom if current_root_is_fragment => VirtualNode::Fragment(vec![new_vdom]),
+            new_vdom => new_vdom,
+        };
+        let root_becomes_fragment =
+            !current_root_is_fragment && new_vdom.as_fragment_ref().is_some();
+        let root_parent = self.root_node.parent_node();
+
         let patches = diff(&self.current_vdom, &new_vdom);
 
-        patch(
-            self.root_node.clone(),
-            &new_vdom,
-            &mut self.events,
-            &patches,
-        )
-        .unwrap();
+        // If the root node gets replaced the replacement takes its place, so we remember where that
+        // place is.
+        let root_is_replaced = !current_root_is_fragment
+            && patches
+                .iter()
+                .any(|patch| matches!(patch, Patch::Replace { old_idx: 0, .. }));
+        let root_next_sibling = self.root_node.next_sibling();
+
+        let (root_node, events) = (self.root_node.clone(), &mut self.events);
+        self.components
+            .enter(|| patch(root_node, &new_vdom, events, &patches))
+            .unwrap();
+
+        // The old root node was replaced by the fragment's nodes, so the node that they were
+        // placed into is the new root node.
+        if root_becomes_fragment {
+            self.set_root_node(
+                root_parent.expect(
+                    "The root node must have a parent in order to be replaced by a fragment",
+                ),
+            );
+        } else if root_is_replaced {
+            // Our event delegation listeners were on the node that got replaced, so they need to
+            // be moved to its replacement.
+            let replacement = match root_next_sibling {
+                Some(next_sibling) => next_sibling.previous_sibling(),
+                None => root_parent.and_then(|parent| parent.last_child()),
+            };
+
+            if let Some(replacement) = replacement {
+                self.set_root_node(replacement);
+            }
+        }
 
         self.current_vdom = new_vdom;
     }
 
     /// Return the root node of your application, the highest ancestor of all other nodes in
     /// your real DOM tree.
+    ///
+    /// If your root virtual node is a fragment this is the node that the fragment's nodes were
+    /// placed into.
     pub fn root_node(&self) -> Node {
         // Note that we're cloning the `web_sys::Node`, not the DOM element.
         // So we're effectively cloning a pointer here, which is fast.
         self.root_node.clone()
     }
+
+    /// Use a new root node, such as the node that a root fragment's nodes were placed into.
+    fn set_root_node(&mut self, root_node: Node) {
+        self.root_node = root_node;
+
+        // Our event delegation listeners need to be on the root node in order to hear events.
+        self.attach_event_listeners();
+    }
+}
+
+/// Every component is rendered into its own `PercyDom`, so that a change to its state only
+/// diffs and patches the component's nodes.
+impl ComponentRenderer for PercyDom {
+    fn update(&mut self, view: VirtualNode) {
+        PercyDom::update(self, view);
+    }
+
+    fn unmount(&mut self) {
+        // Removing the nodes, instead of dropping them, calls their `on_remove_element` functions
+        // and removes their window and document listeners.
+        PercyDom::update(self, VirtualNode::Fragment(vec![]));
+        self.detach_event_listeners();
+    }
+}
+
+/// A host element that already has children was rendered on the server, so we hydrate them.
+fn mount_component(view: VirtualNode, host: &Element) -> Box<dyn ComponentRenderer> {
+    if !host.has_child_nodes() {
+        return Box::new(PercyDom::new_append_to_mount(view, host));
+    }
+
+    // The view was rendered inside of the host element, the same way that a root fragment's nodes
+    // are rendered inside of their mount element.
+    let view = match view {
+        VirtualNode::Fragment(_) => view,
+        view => VirtualNode::Fragment(vec![view]),
+    };
+    Box::new(PercyDom::hydrate(view, host.clone()))
+}
+
+fn warn_hydration_mismatch(mismatch: &HydrationMismatch) {
+    let message = format!("percy-dom hydration mismatch: {}", mismatch);
+    web_sys::console::warn_1(&message.into());
 }
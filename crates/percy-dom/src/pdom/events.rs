@@ -1,71 +1,183 @@
-use crate::event::{EventHandler, EventName, MouseEvent, VirtualEvents, ELEMENT_EVENTS_ID_PROP};
+use crate::event::{EventName, EventOptions, VirtualEvents, ELEMENT_EVENTS_ID_PROP};
 use crate::{Closure, PercyDom};
 use js_sys::Reflect;
+use std::cell::Cell;
+use std::rc::Rc;
 use virtual_node::event::ElementEventsId;
-use wasm_bindgen::JsCast;
+use virtual_node::portal_of_container;
+use wasm_bindgen::{JsCast, JsValue};
 
 impl PercyDom {
     /// Attach all of the event listeners that handle event delegation.
+    ///
+    /// Every delegated event gets its own listener on the root node.
     pub(super) fn attach_event_listeners(&mut self) {
-        self.attach_onclick_listener();
+        for event_name in EventName::delegated_events() {
+            self.attach_delegated_event_listener(event_name);
+        }
+    }
+
+    /// Remove all of the event listeners that handle event delegation from the root node.
+    pub(super) fn detach_event_listeners(&mut self) {
+        for (event_name, callback) in self.event_delegation_listeners.drain() {
+            let (listen_for, _) = delegated_listener(&event_name);
+
+            self.root_node
+                .remove_event_listener_with_callback(
+                    listen_for,
+                    callback.as_ref().as_ref().unchecked_ref(),
+                )
+                .unwrap();
+        }
+
+        self.events.remove_delegated_listeners();
     }
 
-    fn attach_onclick_listener(&mut self) {
-        let event = "click";
-        debug_assert!(EventName::new(format!("on{}", event).into()).is_delegated());
+    fn attach_delegated_event_listener(&mut self, event_name: EventName) {
+        debug_assert!(event_name.is_delegated());
+
+        let (listen_for, bubbles) = delegated_listener(&event_name);
 
         let events = self.events.clone();
 
-        let callback = move |event: web_sys::MouseEvent| {
-            let target = event.target().unwrap();
+        // `.prevent` is the only modifier that a delegated event can have, so an element's handler
+        // is stored under one of these names.
+        let event_names = [
+            event_name.clone(),
+            event_name.clone().with_options(EventOptions {
+                prevent_default: true,
+                ..EventOptions::default()
+            }),
+        ];
+
+        // Events within a portal container that is inside of the root node reach both the
+        // container's listener and the root node's listener, so we mark the events that were
+        // already handled.
+        let handled_prop = format!(
+            "__percy_delegated_{}_{}__",
+            events.events_id_props_prefix(),
+            event_name.without_on_prefix()
+        );
+
+        let callback = move |event: web_sys::Event| {
+            let handled_prop = JsValue::from_str(&handled_prop);
+            if Reflect::has(&event, &handled_prop).unwrap() {
+                return;
+            }
+            Reflect::set(&event, &handled_prop, &JsValue::TRUE).unwrap();
+
+            // Events from inside of a shadow root are retargeted to the shadow root's host, so we
+            // use the composed path to find the element that the event was dispatched to.
+            let target = event.composed_path().get(0);
             // `dyn_into().unwrap()` was crashing in Firefox (but not Chrome) when running a
             //  real-world application, even though our click event integration tests are passing
             //  in Firefox.
             //  This was observed in `web-sys 0.3.61`
             let target_element: web_sys::Element = target.unchecked_into();
 
-            bubble_event(target_element, MouseEvent::new(event), &events);
+            let should_propagate = Rc::new(Cell::new(true));
+            bubble_event(
+                target_element,
+                &event_names,
+                &event,
+                &should_propagate,
+                bubbles,
+                &events,
+            );
         };
         let callback = Box::new(callback) as Box<dyn FnMut(_)>;
         let callback = Closure::wrap(callback);
 
         self.root_node
-            .add_event_listener_with_callback(event, callback.as_ref().unchecked_ref())
+            .add_event_listener_with_callback(listen_for, callback.as_ref().unchecked_ref())
             .unwrap();
+        self.events
+            .add_delegated_listener(listen_for, callback.as_ref().unchecked_ref());
 
         self.event_delegation_listeners
-            .insert(event, Box::new(callback));
+            .insert(event_name, Box::new(callback));
+    }
+}
+
+/// The event that the root node listens for in order to handle a delegated event, and whether or
+/// not the event bubbles up to the target's ancestors.
+fn delegated_listener(event_name: &EventName) -> (&str, bool) {
+    // `focus` and `blur` do not bubble, so we listen for `focusin` and `focusout` instead and
+    // only call the handler of the element that gained or lost focus.
+    match event_name.with_on_prefix() {
+        "onfocus" => ("focusin", false),
+        "onblur" => ("focusout", false),
+        _ => (event_name.without_on_prefix(), true),
     }
 }
 
 // Call the event, then call it on its parent, etc
-fn bubble_event(elem: web_sys::Element, mouse_event: MouseEvent, events: &VirtualEvents) {
+fn bubble_event(
+    elem: web_sys::Element,
+    event_names: &[EventName],
+    event: &web_sys::Event,
+    should_propagate: &Rc<Cell<bool>>,
+    bubbles: bool,
+    events: &VirtualEvents,
+) {
     let events_id = Reflect::get(&elem, &ELEMENT_EVENTS_ID_PROP.into()).unwrap();
     let events_id = events_id.as_string();
 
+    // Elements that belong to a different PercyDom, such as the parent of a nested PercyDom's
+    // root node, have a different prefix.
+    let prefix = events.events_id_props_prefix().to_string();
+    let events_id = events_id
+        .as_deref()
+        .and_then(|events_id| events_id.strip_prefix(prefix.as_str()));
+
     if let Some(events_id) = events_id {
-        let events_id = events_id.trim_start_matches(&events.events_id_props_prefix().to_string());
         let events_id: u32 = events_id.parse().unwrap();
         let events_id = ElementEventsId::new(events_id);
 
-        let cb = events.get_event_handler(&events_id, &EventName::ONCLICK);
+        for event_name in event_names {
+            let cb = events.get_event_handler(&events_id, event_name);
 
-        if let Some(cb) = cb {
-            match cb {
-                EventHandler::NoArgs(no_args) => (no_args.borrow_mut())(),
-                EventHandler::MouseEvent(mouse) => {
-                    (mouse.borrow_mut())(mouse_event.clone());
+            if let Some(cb) = cb {
+                if event_name.options().prevent_default {
+                    event.prevent_default();
                 }
-                _ => panic!(),
-            };
+
+                cb.call(event.clone(), should_propagate);
+            }
         }
     }
 
-    if !mouse_event.should_propagate().get() {
+    // Handlers that take the `web_sys` event can stop propagation without going through our
+    // event types, so we also check the browser event.
+    if !bubbles || !should_propagate.get() || event.cancel_bubble() {
         return;
     }
 
+    if let Some(parent) = parent_element_or_host(&elem) {
+        bubble_event(
+            parent,
+            event_names,
+            event,
+            should_propagate,
+            bubbles,
+            events,
+        );
+    }
+}
+
+// The children of a shadow root do not have a parent element, so we bubble to the shadow root's
+// host. A portal container's children bubble to the portal, not to the portal's target.
+fn parent_element_or_host(elem: &web_sys::Element) -> Option<web_sys::Element> {
+    if let Some(portal) = portal_of_container(elem) {
+        return Some(portal);
+    }
+
     if let Some(parent) = elem.parent_element() {
-        bubble_event(parent, mouse_event, events);
+        return Some(parent);
     }
+
+    elem.parent_node()?
+        .dyn_into::<web_sys::ShadowRoot>()
+        .ok()
+        .map(|shadow_root| shadow_root.host())
 }
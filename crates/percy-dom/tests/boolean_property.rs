@@ -5,6 +5,8 @@
 //! To run all tests in this file:
 //! wasm-pack test --chrome --headless crates/percy-dom --test boolean_property
 
+#![cfg(target_arch = "wasm32")]
+
 use wasm_bindgen_test::*;
 
 use wasm_bindgen::JsCast;
@@ -13,6 +13,8 @@
 //! wasm-pack test --chrome --headless crates/percy-dom --test checked_property
 //! ```
 
+#![cfg(target_arch = "wasm32")]
+
 use wasm_bindgen_test::*;
 
 use percy_dom::event::VirtualEvents;
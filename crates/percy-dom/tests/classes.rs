@@ -0,0 +1,55 @@
+//! Verify that classes get added to created elements and are patched one class at a time.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test classes
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Verify that we add classes when creating an element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test classes -- create_element_with_classes
+#[wasm_bindgen_test]
+fn create_element_with_classes() {
+    let div = html! { <div class=["btn primary", "large"]></div> };
+
+    let (node, _) = div.create_dom_node(&mut VirtualEvents::new());
+    let element: Element = node.dyn_into().unwrap();
+
+    assert_eq!(element.class_name(), "btn primary large");
+}
+
+/// Verify that patching classes only adds and removes the classes that changed, so that a class
+/// that was added to the element by other code survives the patch.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test classes -- patch_classes
+#[wasm_bindgen_test]
+fn patch_classes() {
+    let start = html! { <div class="btn primary"></div> };
+    let end = html! { <div class="btn active"></div> };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    let element: Element = node.clone().dyn_into().unwrap();
+    element.class_list().add_1("focus-visible").unwrap();
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node, &end, &mut events, &patches).unwrap();
+
+    let class_list = element.class_list();
+    assert!(class_list.contains("btn"));
+    assert!(class_list.contains("active"));
+    assert!(class_list.contains("focus-visible"));
+    assert!(!class_list.contains("primary"));
+}
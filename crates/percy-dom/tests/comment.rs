@@ -0,0 +1,99 @@
+//! Tests that ensure that comment nodes get created, diffed and patched in a real browser.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test comment
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use wasm_bindgen_test::*;
+
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod diff_patch_test_case;
+use self::diff_patch_test_case::DiffPatchTest;
+
+/// Verify that we can change a comment's text.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test comment -- change_comment_text
+#[wasm_bindgen_test]
+fn change_comment_text() {
+    DiffPatchTest {
+        desc: "Change a comment's text",
+        old: html! { <div> {VirtualNode::comment("old")} </div> },
+        new: html! { <div> {VirtualNode::comment("new")} </div> },
+        override_expected: None,
+    }
+    .test();
+}
+
+/// Verify that we can replace a comment with an element and an element with a comment.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test comment -- replace_comment
+#[wasm_bindgen_test]
+fn replace_comment() {
+    DiffPatchTest {
+        desc: "Replace a comment with an element",
+        old: html! { <div> {VirtualNode::comment("placeholder")} </div> },
+        new: html! { <div> <em></em> </div> },
+        override_expected: None,
+    }
+    .test();
+
+    DiffPatchTest {
+        desc: "Replace an element with a comment",
+        old: html! { <div> <em></em> </div> },
+        new: html! { <div> {VirtualNode::comment("placeholder")} </div> },
+        override_expected: None,
+    }
+    .test();
+}
+
+/// Verify that a comment in between text nodes is patched instead of being mistaken for one of
+/// the separators that we place in between neighboring text nodes.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test comment -- comments_and_text_separators
+#[wasm_bindgen_test]
+fn comments_and_text_separators() {
+    DiffPatchTest {
+        desc: "Patch comments that are next to text nodes",
+        old: html! {
+          <div>
+            {"a"} {"b"} {VirtualNode::comment("one")} {"c"} <span>{"d"}</span>
+          </div>
+        },
+        new: html! {
+          <div>
+            {"a"} {"b"} {VirtualNode::comment("two")} {"e"} <span>{"f"}</span>
+          </div>
+        },
+        override_expected: None,
+    }
+    .test();
+}
+
+/// Verify that keyed elements can be moved around comments.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test comment -- move_keyed_elements_around_comment
+#[wasm_bindgen_test]
+fn move_keyed_elements_around_comment() {
+    DiffPatchTest {
+        desc: "Swap keyed elements that are on either side of a comment",
+        old: html! {
+          <div>
+            <b key="a"></b> {VirtualNode::comment("separator")} <i key="b"></i>
+          </div>
+        },
+        new: html! {
+          <div>
+            <i key="b"></i> {VirtualNode::comment("separator")} <b key="a"></b>
+          </div>
+        },
+        override_expected: None,
+    }
+    .test();
+}
@@ -0,0 +1,369 @@
+//! Test components that own local state.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test components
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+
+use percy_dom::component::ComponentRegistry;
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+use testing_utilities::{create_mount, get_element_by_id, random_id, send_click_event};
+
+/// Verify that updating a component's state re-renders the component without re-rendering its
+/// parent.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- update_rerenders_only_the_component
+#[wasm_bindgen_test]
+fn update_rerenders_only_the_component() {
+    let counter = Counter::new("counter");
+    let parent_renders = Rc::new(Cell::new(0));
+
+    let render_parent = {
+        let counter = counter.clone();
+        let parent_renders = parent_renders.clone();
+        move || {
+            parent_renders.set(parent_renders.get() + 1);
+            html! { <div><Counter key=counter.key label=counter.label id=counter.id scope=counter.scope.clone() /></div> }
+        }
+    };
+
+    let _pdom = PercyDom::new_append_to_mount(render_parent(), &create_mount());
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Count: 0");
+
+    counter.scope().update_now(|count| *count += 2);
+
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Count: 2");
+    assert_eq!(parent_renders.get(), 1);
+}
+
+/// Verify that a component's event handlers can update its state.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- update_from_event_handler
+#[wasm_bindgen_test]
+fn update_from_event_handler() {
+    let button_id = random_id();
+
+    let _pdom = PercyDom::new_append_to_mount(
+        html! { <div><Toggle key="toggle" id=button_id /></div> },
+        &create_mount(),
+    );
+    assert_eq!(get_element_by_id(button_id).inner_html(), "Open");
+
+    send_click_event(button_id);
+
+    assert_eq!(get_element_by_id(button_id).inner_html(), "Close");
+}
+
+/// Verify that a component's state is kept when its parent re-renders, and that the component is
+/// re-rendered with its new props.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- state_kept_across_parent_renders
+#[wasm_bindgen_test]
+fn state_kept_across_parent_renders() {
+    let counter = Counter::new("kept");
+
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <div><Counter key=counter.key label=counter.label id=counter.id scope=counter.scope.clone() /></div> },
+        &create_mount(),
+    );
+    counter.scope().update_now(|count| *count = 5);
+
+    pdom.update(html! {
+      <div>
+        <span></span>
+        <Counter key=counter.key label="Total" id=counter.id scope=counter.scope.clone() />
+      </div>
+    });
+
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Total: 5");
+}
+
+/// Verify that rendering a component does not touch the DOM. The component is only re-rendered once
+/// its parent gets patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- render_does_not_patch_the_dom
+#[wasm_bindgen_test]
+fn render_does_not_patch_the_dom() {
+    let counter = Counter::new("render");
+    let render_parent = |label| {
+        html! { <div><Counter key=counter.key label=label id=counter.id scope=counter.scope.clone() /></div> }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render_parent("Count"), &create_mount());
+
+    let new_vdom = render_parent("Total");
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Count: 0");
+
+    pdom.update(new_vdom);
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Total: 0");
+}
+
+/// Verify that a component's state is dropped when it is removed, so that it starts over from its
+/// initial state when it is rendered again.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- state_dropped_when_removed
+#[wasm_bindgen_test]
+fn state_dropped_when_removed() {
+    let counter = Counter::new("dropped");
+    let render_counter = || {
+        html! { <div><Counter key=counter.key label=counter.label id=counter.id scope=counter.scope.clone() /></div> }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render_counter(), &create_mount());
+    counter.scope().update_now(|count| *count = 5);
+
+    pdom.update(html! { <div></div> });
+    pdom.update(render_counter());
+
+    assert_eq!(get_element_by_id(counter.id).inner_html(), "Count: 0");
+}
+
+/// Verify that components with different keys have their own state.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- state_per_key
+#[wasm_bindgen_test]
+fn state_per_key() {
+    let first = Counter::new("first");
+    let second = Counter::new("second");
+
+    let _pdom = PercyDom::new_append_to_mount(
+        html! {
+          <div>
+            <Counter key=first.key label=first.label id=first.id scope=first.scope.clone() />
+            <Counter key=second.key label=second.label id=second.id scope=second.scope.clone() />
+          </div>
+        },
+        &create_mount(),
+    );
+
+    second.scope().update_now(|count| *count = 3);
+
+    assert_eq!(get_element_by_id(first.id).inner_html(), "Count: 0");
+    assert_eq!(get_element_by_id(second.id).inner_html(), "Count: 3");
+}
+
+/// Verify that two `PercyDom`s that render a component with the same key do not share its state.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- state_per_percy_dom
+#[wasm_bindgen_test]
+fn state_per_percy_dom() {
+    let first = Counter::new("shared");
+    let second = Counter {
+        id: random_id(),
+        scope: Rc::new(RefCell::new(None)),
+        ..first.clone()
+    };
+
+    let _first_pdom = PercyDom::new_append_to_mount(
+        html! { <div><Counter key=first.key label=first.label id=first.id scope=first.scope.clone() /></div> },
+        &create_mount(),
+    );
+    let _second_pdom = PercyDom::new_append_to_mount(
+        html! { <div><Counter key=second.key label=second.label id=second.id scope=second.scope.clone() /></div> },
+        &create_mount(),
+    );
+
+    second.scope().update_now(|count| *count = 3);
+
+    assert_eq!(get_element_by_id(first.id).inner_html(), "Count: 0");
+    assert_eq!(get_element_by_id(second.id).inner_html(), "Count: 3");
+}
+
+/// Verify that a component can update its state while its view is being rendered. The update is
+/// applied once the view is done.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- update_during_view
+#[wasm_bindgen_test]
+fn update_during_view() {
+    let button_id = random_id();
+
+    let _pdom = PercyDom::new_append_to_mount(
+        html! { <div><Stepper key="stepper" id=button_id /></div> },
+        &create_mount(),
+    );
+    assert_eq!(get_element_by_id(button_id).inner_html(), "0");
+
+    send_click_event(button_id);
+
+    assert_eq!(get_element_by_id(button_id).inner_html(), "11");
+}
+
+/// Verify that rendering two live components of the same type with the same key panics in debug
+/// builds, since they would share their state.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- duplicate_keys_panic
+#[wasm_bindgen_test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "More than one")]
+fn duplicate_keys_panic() {
+    let first = Counter::new("duplicate");
+    let second = Counter {
+        id: random_id(),
+        ..first.clone()
+    };
+
+    let _pdom = PercyDom::new_append_to_mount(
+        html! {
+          <div>
+            <Counter key=first.key label=first.label id=first.id scope=first.scope.clone() />
+            <Counter key=second.key label=second.label id=second.id scope=second.scope.clone() />
+          </div>
+        },
+        &create_mount(),
+    );
+}
+
+/// Verify that a server rendered component gets hydrated without any mismatches, re-using the
+/// DOM nodes that were rendered inside of its host element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test components -- hydrates_server_rendered_component
+#[wasm_bindgen_test]
+fn hydrates_server_rendered_component() {
+    let key: &'static str = Box::leak(format!("hydrated-{}", random_id()).into_boxed_str());
+    let button_id = random_id();
+
+    // On wasm32 a component renders an empty host element, so we write the server's HTML by hand.
+    let server_html = format!(
+        r#"<div><percy-component key="{}" style="display: contents"><button id="{}">Open</button></percy-component></div>"#,
+        key, button_id
+    );
+    let mount = create_mount();
+    mount.set_inner_html(&server_html);
+    let mount = mount.first_element_child().unwrap();
+    let button = get_element_by_id(button_id);
+
+    let vdom = html! { <div><Toggle key=key id=button_id /></div> };
+    let mut mismatches = vec![];
+    ComponentRegistry::new().enter(|| {
+        vdom.hydrate_dom_node(
+            mount.clone().into(),
+            &mut VirtualEvents::new(),
+            &mut mismatches,
+        )
+    });
+
+    let mismatches: Vec<String> = mismatches.iter().map(|m| m.to_string()).collect();
+    assert_eq!(mismatches, Vec::<String>::new());
+    assert_eq!(mount.outer_html(), server_html);
+    assert!(get_element_by_id(button_id).is_same_node(Some(&button)));
+
+    send_click_event(button_id);
+    assert_eq!(button.inner_html(), "Close");
+}
+
+#[derive(Clone)]
+struct Counter {
+    key: &'static str,
+    label: &'static str,
+    id: &'static str,
+    /// Lets the test update the counter's state.
+    scope: Rc<RefCell<Option<Scope<Counter>>>>,
+}
+
+impl Counter {
+    // Tests share the same DOM, so element ids need to be unique across tests.
+    fn new(key: &'static str) -> Self {
+        Counter {
+            key: Box::leak(format!("{}-{}", key, random_id()).into_boxed_str()),
+            label: "Count",
+            id: random_id(),
+            scope: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    fn scope(&self) -> Scope<Counter> {
+        self.scope.borrow().clone().unwrap()
+    }
+}
+
+impl Component for Counter {
+    type State = u32;
+
+    fn key(&self) -> String {
+        self.key.to_string()
+    }
+
+    fn initial_state(&self) -> u32 {
+        0
+    }
+
+    fn view(&self, count: &u32, scope: &Scope<Self>) -> VirtualNode {
+        *self.scope.borrow_mut() = Some(scope.clone());
+
+        let text = format!("{}: {}", self.label, count);
+        html! { <span id=self.id>{text}</span> }
+    }
+}
+
+/// Steps from 0 to 1 while rendering its first view, then by 10 on every click.
+#[derive(Clone)]
+struct Stepper {
+    key: &'static str,
+    id: &'static str,
+}
+
+impl Component for Stepper {
+    type State = u32;
+
+    fn key(&self) -> String {
+        self.key.to_string()
+    }
+
+    fn initial_state(&self) -> u32 {
+        0
+    }
+
+    fn view(&self, count: &u32, scope: &Scope<Self>) -> VirtualNode {
+        if *count == 0 {
+            scope.update_now(|count| *count = 1);
+        }
+
+        let scope = scope.clone();
+        html! {
+          <button id=self.id onclick=move || scope.update_now(|count| *count += 10)>
+            {count.to_string()}
+          </button>
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Toggle {
+    key: &'static str,
+    id: &'static str,
+}
+
+impl Component for Toggle {
+    type State = bool;
+
+    fn key(&self) -> String {
+        self.key.to_string()
+    }
+
+    fn initial_state(&self) -> bool {
+        false
+    }
+
+    fn view(&self, is_open: &bool, scope: &Scope<Self>) -> VirtualNode {
+        let scope = scope.clone();
+
+        let text = if *is_open { "Close" } else { "Open" };
+        html! {
+          <button id=self.id onclick=move || scope.update_now(|is_open| *is_open = !*is_open)>
+            {text}
+          </button>
+        }
+    }
+}
@@ -4,6 +4,8 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test create_dom_node
 
+#![cfg(target_arch = "wasm32")]
+
 extern crate wasm_bindgen_test;
 extern crate web_sys;
 use percy_dom::event::VirtualEvents;
@@ -0,0 +1,216 @@
+//! Verify that we can create and patch custom elements, customized built-in elements and
+//! declarative shadow roots.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test custom_elements
+
+#![cfg(target_arch = "wasm32")]
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use js_sys::{Function, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use crate::testing_utilities::{create_mount, create_node_and_events};
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+use percy_dom::PercyDom;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+
+/// Verify that a customized built-in element is created with its `is` attribute, so that the
+/// browser upgrades it into the custom element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- creates_customized_built_in_element
+#[wasm_bindgen_test]
+fn creates_customized_built_in_element() {
+    js_sys::eval(
+        r#"
+        if (!customElements.get("percy-fancy-button")) {
+            class FancyButton extends HTMLButtonElement {
+                constructor() {
+                    super();
+                    this.upgraded = true;
+                }
+            }
+            customElements.define("percy-fancy-button", FancyButton, { extends: "button" });
+        }
+        "#,
+    )
+    .unwrap();
+
+    let (node, _) = create_node_and_events(html! { <button is="percy-fancy-button"></button> });
+    let button: Element = node.dyn_into().unwrap();
+
+    assert_eq!(
+        button.get_attribute("is").as_deref(),
+        Some("percy-fancy-button")
+    );
+    assert_eq!(
+        Reflect::get(&button, &"upgraded".into()).unwrap(),
+        JsValue::TRUE
+    );
+}
+
+/// Verify that we can listen to a custom element's custom events, and that the macro gives the
+/// closure's argument the `CustomEvent` type.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- custom_event_listener
+#[wasm_bindgen_test]
+fn custom_event_listener() {
+    let detail = Rc::new(RefCell::new(JsValue::UNDEFINED));
+    let detail_clone = detail.clone();
+
+    let widget = html! {
+      <my-widget onmy-widget-change=move |event| {
+          *detail_clone.borrow_mut() = event.detail();
+      }>
+      </my-widget>
+    };
+    let (node, _events) = create_node_and_events(widget);
+
+    let init = CustomEventInit::new();
+    init.set_detail(&"changed".into());
+    let event = CustomEvent::new_with_event_init_dict("my-widget-change", &init).unwrap();
+    node.dispatch_event(&event).unwrap();
+
+    assert_eq!(detail.borrow().as_string().as_deref(), Some("changed"));
+}
+
+/// Verify that a declarative shadow root's children are placed into a shadow root that is
+/// attached to its parent.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- creates_declarative_shadow_root
+#[wasm_bindgen_test]
+fn creates_declarative_shadow_root() {
+    let widget = html! {
+      <my-widget>
+        <template shadowrootmode="open"><b>Shadow</b><slot></slot></template>
+        Light
+      </my-widget>
+    };
+    let (node, _) = create_node_and_events(widget);
+    let widget: Element = node.dyn_into().unwrap();
+
+    let shadow_root = widget.shadow_root().unwrap();
+    assert_eq!(shadow_root.inner_html(), "<b>Shadow</b><slot></slot>");
+}
+
+/// Verify that we can patch the nodes inside of a declarative shadow root.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- patches_declarative_shadow_root
+#[wasm_bindgen_test]
+fn patches_declarative_shadow_root() {
+    let start = html! {
+      <my-widget>
+        <template shadowrootmode="open"><b>Start</b><em></em></template>
+      </my-widget>
+    };
+    let end = html! {
+      <my-widget>
+        <template shadowrootmode="open"><b>End</b><i></i><slot></slot></template>
+      </my-widget>
+    };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node.clone(), &end, &mut events, &patches).unwrap();
+
+    let widget: Element = node.dyn_into().unwrap();
+    assert_eq!(
+        widget.shadow_root().unwrap().inner_html(),
+        "<b>End</b><i></i><slot></slot>"
+    );
+}
+
+/// Verify that delegated events are called for elements that are inside of a shadow root.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- click_inside_of_shadow_root
+#[wasm_bindgen_test]
+fn click_inside_of_shadow_root() {
+    let clicks = Rc::new(Cell::new(0));
+    let clicks_clone = clicks.clone();
+
+    let widget = html! {
+      <my-widget>
+        <template shadowrootmode="open">
+          <button onclick=move || clicks_clone.set(clicks_clone.get() + 1)></button>
+        </template>
+      </my-widget>
+    };
+    let mount = create_mount();
+    let pdom = PercyDom::new_append_to_mount(widget, &mount);
+
+    let widget: Element = pdom.root_node().dyn_into().unwrap();
+    let button: HtmlElement = widget
+        .shadow_root()
+        .unwrap()
+        .query_selector("button")
+        .unwrap()
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    button.click();
+
+    assert_eq!(clicks.get(), 1);
+}
+
+/// Verify that we hydrate a server side rendered declarative shadow root, which the browser
+/// already placed into a shadow root.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test custom_elements -- hydrates_declarative_shadow_root
+#[wasm_bindgen_test]
+fn hydrates_declarative_shadow_root() {
+    let start = html! {
+      <my-widget>
+        <template shadowrootmode="open"><b>Server</b></template>
+        Light
+      </my-widget>
+    };
+    let end = html! {
+      <my-widget>
+        <template shadowrootmode="open"><b>Client</b></template>
+        Light
+      </my-widget>
+    };
+
+    // `innerHTML` ignores declarative shadow roots, so we parse the HTML the same way that the
+    // browser parses a server side rendered page.
+    let mount = create_mount();
+    let set_html_unsafe: Function = Reflect::get(&mount, &"setHTMLUnsafe".into())
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    set_html_unsafe
+        .call1(&mount, &start.to_string().into())
+        .unwrap();
+
+    let widget = mount.first_element_child().unwrap();
+    let shadow_b = widget.shadow_root().unwrap().first_child().unwrap();
+
+    let mut events = VirtualEvents::new();
+    let mut mismatches = vec![];
+    let (node, events_node) =
+        start.hydrate_dom_node(widget.clone().into(), &mut events, &mut mismatches);
+    events.set_root(events_node);
+    assert_eq!(mismatches, vec![]);
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node, &end, &mut events, &patches).unwrap();
+
+    let shadow_root = widget.shadow_root().unwrap();
+    assert!(shadow_root
+        .first_child()
+        .unwrap()
+        .is_same_node(Some(&shadow_b)));
+    assert_eq!(shadow_root.inner_html(), "<b>Client</b>");
+}
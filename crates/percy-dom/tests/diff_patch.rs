@@ -7,6 +7,8 @@
 //! TODO: Move the tests in this file to more focused files.
 //!  For example, we might move the replace tests to a `replace.rs` file.
 
+#![cfg(target_arch = "wasm32")]
+
 extern crate wasm_bindgen_test;
 extern crate web_sys;
 use wasm_bindgen_test::*;
@@ -57,7 +59,7 @@ fn truncate_children() {
 fn remove_attributes() {
     DiffPatchTest {
         desc: "Removes attributes",
-        old: html! { <div style=""> </div>
+        old: html! { <div id="foo"> </div>
         },
         new: html! { <div></div> },
         override_expected: None,
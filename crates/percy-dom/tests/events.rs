@@ -4,8 +4,11 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test events
 
+#![cfg(target_arch = "wasm32")]
+
 use crate::testing_utilities::{
-    create_mount, document, get_element_by_id, random_id, send_click_event, send_input_event,
+    create_mount, document, get_element_by_id, random_id, send_click_event, send_event,
+    send_input_event,
 };
 use percy_dom::event::{EventHandler, EventName, VirtualEvents, ELEMENT_EVENTS_ID_PROP};
 use percy_dom::prelude::*;
@@ -67,7 +70,7 @@ fn click_event_works() {
 /// wasm-pack test --chrome --headless crates/percy-dom --test events -- updated_non_delegated_event_handler
 #[wasm_bindgen_test]
 fn updated_non_delegated_event_handler() {
-    let event = EventName::ONINPUT;
+    let event = EventName::new("onscroll".into());
     assert_eq!(event.is_delegated(), false);
 
     let id = random_id();
@@ -86,7 +89,7 @@ fn updated_non_delegated_event_handler() {
     ));
 
     assert_text_unmodified(&text);
-    send_input_event(id);
+    send_scroll_event(id);
     assert_text_appended(&text, APPEND_TEXT_TWO);
 }
 
@@ -224,7 +227,7 @@ fn replaced_text_with_elem_events_work() {
 /// wasm-pack test --chrome --headless crates/percy-dom --test events -- patch_add_non_delegated_event_listener
 #[wasm_bindgen_test]
 fn patch_add_non_delegated_event_listener() {
-    let event = EventName::ONINPUT;
+    let event = EventName::new("onscroll".into());
     assert_eq!(event.is_delegated(), false);
 
     let id = random_id();
@@ -240,7 +243,7 @@ fn patch_add_non_delegated_event_listener() {
     ));
 
     assert_text_unmodified(&text);
-    send_input_event(id);
+    send_scroll_event(id);
     assert_text_appended(&text, APPEND_TEXT_TWO);
 }
 
@@ -250,7 +253,7 @@ fn patch_add_non_delegated_event_listener() {
 /// wasm-pack test --chrome --headless crates/percy-dom --test events -- patch_remove_non_delegated_event_listener
 #[wasm_bindgen_test]
 fn patch_remove_non_delegated_event_listener() {
-    let event = EventName::ONINPUT;
+    let event = EventName::new("onscroll".into());
     assert_eq!(event.is_delegated(), false);
 
     let id = random_id();
@@ -268,7 +271,7 @@ fn patch_remove_non_delegated_event_listener() {
     assert!(pdom.events.get_event_handler(&events_id, &event).is_none());
 
     assert_text_unmodified(&text);
-    send_input_event(id);
+    send_scroll_event(id);
     assert_text_unmodified(&text);
 }
 
@@ -364,7 +367,7 @@ fn patch_remove_all_events_with_node_idx() {
 /// wasm-pack test --chrome --headless crates/percy-dom --test events -- add_node_before_node_with_events
 #[wasm_bindgen_test]
 fn add_node_before_node_with_events() {
-    let event = EventName::ONINPUT;
+    let event = EventName::new("onscroll".into());
     assert_eq!(event.is_delegated(), false);
 
     let id = random_id();
@@ -397,7 +400,7 @@ fn add_node_before_node_with_events() {
     pdom.update(end);
 
     assert_text_unmodified(&text);
-    send_input_event(id);
+    send_scroll_event(id);
     assert_text_appended(&text, APPEND_TEXT_TWO);
 }
 
@@ -409,7 +412,7 @@ fn add_node_before_node_with_events() {
 /// wasm-pack test --chrome --headless crates/percy-dom --test events -- truncate_children_then_append_child_with_events
 #[wasm_bindgen_test]
 fn truncate_children_then_append_child_with_events() {
-    let event = EventName::ONINPUT;
+    let event = EventName::new("onscroll".into());
     assert_eq!(event.is_delegated(), false);
 
     let id_end = random_id();
@@ -440,7 +443,7 @@ fn truncate_children_then_append_child_with_events() {
     pdom.update(end);
 
     assert_text_unmodified(&text);
-    send_input_event(id_end);
+    send_scroll_event(id_end);
     assert_text_appended(&text, APPEND_TEXT_TWO);
 }
 
@@ -482,6 +485,37 @@ fn closure_with_arguments() {
     assert_eq!(called.get(), true);
 }
 
+/// Verify that the macro gives keyboard event closures a typed event argument.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- typed_keyboard_event
+#[wasm_bindgen_test]
+fn typed_keyboard_event() {
+    let key = Rc::new(RefCell::new(String::new()));
+    let key_clone = key.clone();
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <input
+          id=id
+          onkeydown=move |event| {
+               *key_clone.borrow_mut() = event.key();
+          }
+        />
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    let init = web_sys::KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_key("Enter");
+    let event =
+        web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+    get_element_by_id(id).dispatch_event(&event).unwrap();
+
+    assert_eq!(key.borrow().as_str(), "Enter");
+}
+
 /// Verify that our event delegation bubbles up to parent elements.
 /// We do this by clicking on a child element and verifying that the parent element's onclick
 /// handler is triggered.
@@ -548,6 +582,417 @@ fn stop_propagation_on_delegated_event() {
     assert_eq!(called.get(), false);
 }
 
+/// Verify that bubbling events other than `onclick`, such as `onkeydown`, are delegated to the
+/// root node and bubble up to parent elements.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- delegated_keydown_propagates_to_parent
+#[wasm_bindgen_test]
+fn delegated_keydown_propagates_to_parent() {
+    assert!(EventName::new("onkeydown".into()).is_delegated());
+
+    let key = Rc::new(RefCell::new(String::new()));
+    let key_clone = key.clone();
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <div
+          onkeydown=move |event| {
+               *key_clone.borrow_mut() = event.key();
+          }
+        >
+          <input id=id onkeydown=|| {} />
+        </div>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    let init = web_sys::KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_key("a");
+    let event =
+        web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap();
+    get_element_by_id(id).dispatch_event(&event).unwrap();
+
+    assert_eq!(key.borrow().as_str(), "a");
+}
+
+/// Verify that stopping propagation using the `web_sys` event stops our event delegation from
+/// bubbling the event.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- web_sys_stop_propagation_on_delegated_event
+#[wasm_bindgen_test]
+fn web_sys_stop_propagation_on_delegated_event() {
+    let called = Rc::new(Cell::new(false));
+    let called_clone = called.clone();
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <div oninput=move || called_clone.set(true)>
+          <input
+            id=id
+            oninput=|event: web_sys::InputEvent| {
+               event.stop_propagation();
+            }
+          />
+        </div>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    send_input_event(id);
+    assert_eq!(called.get(), false);
+}
+
+/// Verify that the handlers of a PercyDom that is nested within another PercyDom are called
+/// before the handlers of the outer PercyDom, and can stop the event from reaching them.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- nested_pdom_handlers_called_first
+#[wasm_bindgen_test]
+fn nested_pdom_handlers_called_first() {
+    let text = start_text();
+    let (outer_text, inner_text) = (text.clone(), text.clone());
+
+    let nested_mount_id = random_id();
+    let id = random_id();
+
+    let outer: VirtualNode = html! {
+        <div onclick=move || append_text(&outer_text, "-outer")>
+          <div id=nested_mount_id></div>
+        </div>
+    };
+    let _outer_pdom = PercyDom::new_replace_mount(outer, create_mount());
+
+    let inner: VirtualNode = html! {
+        <button id=id onclick=move || append_text(&inner_text, "-inner")></button>
+    };
+    let _inner_pdom = PercyDom::new_append_to_mount(inner, &get_element_by_id(nested_mount_id));
+
+    send_click_event(id);
+    assert_eq!(&*text.borrow(), "Start Text-inner-outer");
+
+    let stopped = start_text();
+    let (outer_text, inner_text) = (stopped.clone(), stopped.clone());
+
+    let nested_mount_id = random_id();
+    let id = random_id();
+
+    let outer: VirtualNode = html! {
+        <div onclick=move || append_text(&outer_text, "-outer")>
+          <div id=nested_mount_id></div>
+        </div>
+    };
+    let _outer_pdom = PercyDom::new_replace_mount(outer, create_mount());
+
+    let inner: VirtualNode = html! {
+        <button
+          id=id
+          onclick=move |event: percy_dom::event::MouseEvent| {
+              event.stop_propagation();
+              append_text(&inner_text, "-inner");
+          }
+        ></button>
+    };
+    let _inner_pdom = PercyDom::new_append_to_mount(inner, &get_element_by_id(nested_mount_id));
+
+    send_click_event(id);
+    assert_eq!(&*stopped.borrow(), "Start Text-inner");
+}
+
+/// Verify that delegated handlers run after the target's own listeners, such as one that was
+/// added in `on_create_element`, since the root node listens in the bubble phase.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- delegated_handlers_run_after_target_listeners
+#[wasm_bindgen_test]
+fn delegated_handlers_run_after_target_listeners() {
+    let text = start_text();
+    let (parent_text, own_text) = (text.clone(), text.clone());
+
+    let id = random_id();
+
+    let mut button = html! { <button id=id></button> };
+    button
+        .as_velement_mut()
+        .unwrap()
+        .special_attributes
+        .set_on_create_element("own-listener", move |elem: web_sys::Element| {
+            let own_text = own_text.clone();
+            let listener = wasm_bindgen::closure::Closure::wrap(Box::new(move || {
+                append_text(&own_text, "-own")
+            }) as Box<dyn FnMut()>);
+            elem.add_event_listener_with_callback("click", listener.as_ref().unchecked_ref())
+                .unwrap();
+            listener.forget();
+        });
+
+    let node: VirtualNode = html! {
+        <div onclick=move || append_text(&parent_text, "-parent")>{button}</div>
+    };
+    let _pdom = PercyDom::new_replace_mount(node, create_mount());
+
+    send_click_event(id);
+    assert_eq!(&*text.borrow(), "Start Text-own-parent");
+}
+
+/// Verify that typed event handlers can still take a `web_sys::Event`.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- typed_event_handler_takes_web_sys_event
+#[wasm_bindgen_test]
+fn typed_event_handler_takes_web_sys_event() {
+    let event_type = Rc::new(RefCell::new("".to_string()));
+    let event_type_clone = event_type.clone();
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <input
+          id=id
+          oninput=move |event: web_sys::Event| {
+               *event_type_clone.borrow_mut() = event.type_();
+          }
+        />
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    send_input_event(id);
+    assert_eq!(event_type.borrow().as_str(), "input");
+}
+
+/// Verify that `onfocus` and `onblur` are delegated using `focusin` and `focusout`, and that they
+/// do not bubble to parent elements.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- delegated_focus_and_blur_do_not_bubble
+#[wasm_bindgen_test]
+fn delegated_focus_and_blur_do_not_bubble() {
+    let text = start_text();
+    let (parent_text, input_text) = (text.clone(), text.clone());
+    let (parent_blur_text, input_blur_text) = (text.clone(), text.clone());
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <div
+          onfocus=move || append_text(&parent_text, "parent-focus")
+          onblur=move || append_text(&parent_blur_text, "parent-blur")
+        >
+          <input
+            id=id
+            onfocus=move |_event| append_text(&input_text, "-focus")
+            onblur=move |_event| append_text(&input_blur_text, "-blur")
+          />
+        </div>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    for event in ["focusin", "focusout"] {
+        let init = web_sys::FocusEventInit::new();
+        init.set_bubbles(true);
+        let event = web_sys::FocusEvent::new_with_focus_event_init_dict(event, &init).unwrap();
+        get_element_by_id(id).dispatch_event(&event).unwrap();
+    }
+
+    assert_eq!(text.borrow().as_str(), "Start Text-focus-blur");
+}
+
+/// Verify that delegated events still work after the root node gets replaced, since our event
+/// delegation listeners need to move to the new root node.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- delegated_event_after_root_node_replaced
+#[wasm_bindgen_test]
+fn delegated_event_after_root_node_replaced() {
+    let id = random_id();
+    let text = start_text();
+
+    let mount = create_mount();
+    let mut pdom = PercyDom::new_append_to_mount(html! { <span></span> }, &mount);
+    pdom.update(div_node_with_event(
+        id,
+        vec![EventName::ONCLICK],
+        text.clone(),
+        APPEND_TEXT_ONE,
+    ));
+
+    assert!(pdom.root_node().is_same_node(Some(&get_element_by_id(id))));
+    send_click_event(id);
+    assert_text_appended(&text, APPEND_TEXT_ONE);
+}
+
+/// Verify that the `.prevent` modifier prevents the event's default action, for both delegated
+/// and non-delegated events.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- prevent_modifier
+#[wasm_bindgen_test]
+fn prevent_modifier() {
+    let delegated_id = random_id();
+    let non_delegated_id = random_id();
+
+    let node: VirtualNode = html! {
+        <div>
+          <button id=delegated_id onclick.prevent=|| {}></button>
+          <button id=non_delegated_id onclick.prevent.once=|| {}></button>
+        </div>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    for id in [delegated_id, non_delegated_id] {
+        let init = web_sys::MouseEventInit::new();
+        init.set_bubbles(true);
+        init.set_cancelable(true);
+        let event = web_sys::MouseEvent::new_with_mouse_event_init_dict("click", &init).unwrap();
+        get_element_by_id(id).dispatch_event(&event).unwrap();
+
+        assert!(event.default_prevented(), "{}", id);
+    }
+}
+
+/// Verify that a handler with the `.once` modifier is only called the first time that the event
+/// happens.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- once_modifier
+#[wasm_bindgen_test]
+fn once_modifier() {
+    let clicks = Rc::new(Cell::new(0));
+    let clicks_clone = clicks.clone();
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <button id=id onclick.once=move || clicks_clone.set(clicks_clone.get() + 1)></button>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    send_click_event(id);
+    send_click_event(id);
+    assert_eq!(clicks.get(), 1);
+}
+
+/// Verify that a handler with the `.capture` modifier is called before the handlers of the
+/// elements that it contains, and that it can stop the event from reaching them.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- capture_modifier
+#[wasm_bindgen_test]
+fn capture_modifier() {
+    let text = start_text();
+    let (parent_text, child_text) = (text.clone(), text.clone());
+
+    let id = random_id();
+
+    let node: VirtualNode = html! {
+        <div
+          onclick.capture=move |event| {
+              append_text(&parent_text, "-parent");
+              event.stop_propagation();
+          }
+        >
+          <span id=id onclick=move || append_text(&child_text, "-child")></span>
+        </div>
+    };
+    let mount = create_mount();
+    let _pdom = PercyDom::new_replace_mount(node, mount);
+
+    send_click_event(id);
+    assert_eq!(text.borrow().as_str(), "Start Text-parent");
+}
+
+/// Verify that we remove a capturing listener when its event is removed.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- patch_remove_capture_event_listener
+#[wasm_bindgen_test]
+fn patch_remove_capture_event_listener() {
+    let id = random_id();
+    let text = start_text();
+    let text_clone = text.clone();
+
+    let mount = create_mount();
+    let mut pdom = PercyDom::new_replace_mount(
+        html! {
+          <div id=id onclick.capture=move || append_text(&text_clone, APPEND_TEXT_ONE)></div>
+        },
+        mount,
+    );
+    pdom.update(html! { <div id=id></div> });
+
+    send_click_event(id);
+    assert_text_unmodified(&text);
+}
+
+/// Verify that window and document listeners are called, and that they are removed when their
+/// element is removed.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- window_and_document_listeners
+#[wasm_bindgen_test]
+fn window_and_document_listeners() {
+    let text = start_text();
+    let (window_text, document_text) = (text.clone(), text.clone());
+
+    let mount = create_mount();
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! {
+          <div>
+            <span
+              window:onpercy-test-resize=move || append_text(&window_text, "-window")
+              document:onpercy-test-keydown=move || append_text(&document_text, "-document")
+            ></span>
+          </div>
+        },
+        &mount,
+    );
+
+    let window = web_sys::window().unwrap();
+    let dispatch = || {
+        let event = web_sys::Event::new("percy-test-resize").unwrap();
+        window.dispatch_event(&event).unwrap();
+        let event = web_sys::Event::new("percy-test-keydown").unwrap();
+        document().dispatch_event(&event).unwrap();
+    };
+
+    dispatch();
+    assert_eq!(text.borrow().as_str(), "Start Text-window-document");
+
+    pdom.update(html! { <div></div> });
+
+    dispatch();
+    assert_eq!(text.borrow().as_str(), "Start Text-window-document");
+}
+
+/// Verify that window and document listeners are removed when their element gets replaced, and
+/// when the listener is removed from an element that stays in the DOM.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test events -- window_listener_removed_on_replace_and_patch
+#[wasm_bindgen_test]
+fn window_listener_removed_on_replace_and_patch() {
+    let calls = Rc::new(Cell::new(0));
+    let (calls_a, calls_b) = (calls.clone(), calls.clone());
+
+    let mount = create_mount();
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! {
+          <div>
+            <span window:onpercy-test-replace=move || calls_a.set(calls_a.get() + 1)></span>
+            <em window:onpercy-test-replace=move || calls_b.set(calls_b.get() + 1)></em>
+          </div>
+        },
+        &mount,
+    );
+
+    pdom.update(html! {
+      <div>
+        <b></b>
+        <em></em>
+      </div>
+    });
+
+    let event = web_sys::Event::new("percy-test-replace").unwrap();
+    web_sys::window().unwrap().dispatch_event(&event).unwrap();
+
+    assert_eq!(calls.get(), 0);
+}
+
 fn input_node_with_events(
     id: &str,
     events: Vec<EventName>,
@@ -593,6 +1038,11 @@ const START_TEXT: &'static str = "Start Text";
 const APPEND_TEXT_ONE: &'static str = "- append1";
 const APPEND_TEXT_TWO: &'static str = "- append2";
 
+// Scroll events do not bubble, so they are not delegated.
+fn send_scroll_event(id: &str) {
+    send_event::<web_sys::HtmlElement>(id, &web_sys::Event::new("scroll").unwrap());
+}
+
 fn start_text() -> Rc<RefCell<String>> {
     Rc::new(RefCell::new(START_TEXT.to_string()))
 }
@@ -0,0 +1,99 @@
+//! Verify that we can use a fragment as the root of a `PercyDom`.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test fragment
+
+#![cfg(target_arch = "wasm32")]
+
+use crate::testing_utilities::{create_mount, random_id, send_click_event};
+use percy_dom::prelude::*;
+use percy_dom::PercyDom;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+
+/// Verify that a root fragment's nodes are appended to the mount, and that they get patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test fragment -- append_fragment_to_mount
+#[wasm_bindgen_test]
+fn append_fragment_to_mount() {
+    let mount = create_mount();
+
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <> <li>One</li> <li>Two</li> </> },
+        &mount,
+    );
+    assert_eq!(mount.inner_html(), "<li>One</li><li>Two</li>");
+
+    pdom.update(html! { <> <li>One</li> <li id="two">Two</li> <li>Three</li> </> });
+    assert_eq!(
+        mount.inner_html(),
+        r#"<li>One</li><li id="two">Two</li><li>Three</li>"#
+    );
+
+    pdom.update(html! { <> <li>One</li> </> });
+    assert_eq!(mount.inner_html(), "<li>One</li>");
+}
+
+/// Verify that a root fragment's nodes replace the mount, and that its parent becomes the root
+/// node.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test fragment -- replace_mount_with_fragment
+#[wasm_bindgen_test]
+fn replace_mount_with_fragment() {
+    let parent = create_mount();
+    let mount = create_mount();
+    parent.append_child(&mount).unwrap();
+
+    let mut pdom = PercyDom::new_replace_mount(html! { <> <dt></dt> <dd></dd> </> }, mount);
+    assert_eq!(parent.inner_html(), "<dt></dt><dd></dd>");
+
+    pdom.update(html! { <> <dt>Term</dt> <dd>Definition</dd> </> });
+    assert_eq!(parent.inner_html(), "<dt>Term</dt><dd>Definition</dd>");
+}
+
+/// Verify that we can update a root element to a root fragment and back.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test fragment -- root_element_becomes_fragment
+#[wasm_bindgen_test]
+fn root_element_becomes_fragment() {
+    let mount = create_mount();
+
+    let mut pdom = PercyDom::new_append_to_mount(html! { <div></div> }, &mount);
+
+    pdom.update(html! { <> <div></div> <span></span> </> });
+    assert_eq!(mount.inner_html(), "<div></div><span></span>");
+
+    pdom.update(html! { <em></em> });
+    assert_eq!(mount.inner_html(), "<em></em>");
+}
+
+/// Verify that events work within a root fragment, since the event delegation listeners need to
+/// be attached to the node that the fragment's nodes were placed into.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test fragment -- fragment_events
+#[wasm_bindgen_test]
+fn fragment_events() {
+    let id = random_id();
+    let clicks = Rc::new(Cell::new(0));
+    let clicks_clone = clicks.clone();
+
+    let mount = create_mount();
+    let _pdom = PercyDom::new_append_to_mount(
+        html! {
+          <>
+            <span></span>
+            <button id=id onclick=move || clicks_clone.set(clicks_clone.get() + 1)></button>
+          </>
+        },
+        &mount,
+    );
+
+    send_click_event(id);
+    assert_eq!(clicks.get(), 1);
+}
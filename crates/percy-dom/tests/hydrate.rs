@@ -0,0 +1,196 @@
+//! Test hydrating DOM nodes that were rendered from a `VirtualNode`'s `.to_string()`, such as
+//! on the server.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test hydrate
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+use web_sys::{Element, Node};
+
+use crate::testing_utilities::{create_mount, random_id, send_click_event, send_input_event};
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+use percy_dom::PercyDom;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+
+/// Verify that we re-use the DOM nodes that already exist instead of creating new ones.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- reuses_existing_nodes
+#[wasm_bindgen_test]
+fn reuses_existing_nodes() {
+    let id = random_id();
+    let vdom = html! { <div id=id><span>Hello</span> world</div> };
+    let mount = server_render(&vdom);
+
+    let span = mount.first_child().unwrap();
+
+    let pdom = PercyDom::hydrate(vdom, mount.clone());
+
+    let root: Node = mount.into();
+    assert!(pdom.root_node().is_same_node(Some(&root)));
+    assert!(root.first_child().unwrap().is_same_node(Some(&span)));
+}
+
+/// Verify that we add delegated and non-delegated events to the existing elements.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- adds_events
+#[wasm_bindgen_test]
+fn adds_events() {
+    let button_id = random_id();
+    let input_id = random_id();
+
+    let clicks = Rc::new(Cell::new(0));
+    let clicks_clone = clicks.clone();
+    let inputs = Rc::new(Cell::new(0));
+    let inputs_clone = inputs.clone();
+
+    let vdom = html! {
+    <div>
+      <button id=button_id onclick=move || clicks_clone.set(clicks_clone.get() + 1)></button>
+      <input id=input_id oninput=move || inputs_clone.set(inputs_clone.get() + 1) />
+    </div>
+    };
+    let mount = server_render(&vdom);
+
+    let _pdom = PercyDom::hydrate(vdom, mount);
+
+    send_click_event(button_id);
+    send_input_event(input_id);
+
+    assert_eq!(clicks.get(), 1);
+    assert_eq!(inputs.get(), 1);
+}
+
+/// Verify that we call the on_create_element functions of the existing elements.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- calls_on_create_element
+#[wasm_bindgen_test]
+fn calls_on_create_element() {
+    let vdom = html! {
+    <div>
+      <em key="em" on_create_element=|elem: web_sys::Element| elem.set_id("created")></em>
+    </div>
+    };
+    let mount = server_render(&vdom);
+
+    let _pdom = PercyDom::hydrate(vdom, mount.clone());
+
+    assert_eq!(mount.first_element_child().unwrap().id(), "created");
+}
+
+/// Verify that we can hydrate and then patch neighboring text nodes, which are separated by a
+/// `<!--percy:ptns-->` comment in the server rendered HTML.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- neighboring_text_nodes
+#[wasm_bindgen_test]
+fn neighboring_text_nodes() {
+    let vdom = html! { <div>{"Hello"} {"world"}</div> };
+    let mount = server_render(&vdom);
+    assert_eq!(mount.inner_html(), "Hello <!--percy:ptns-->world");
+
+    let mut pdom = PercyDom::hydrate(vdom, mount.clone());
+    pdom.update(html! { <div>{"Hello"} {"there"}</div> });
+
+    assert_eq!(mount.inner_html(), "Hello <!--percy:ptns-->there");
+}
+
+/// Verify that if the DOM does not match the virtual node we patch the DOM to match.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- patches_mismatches
+#[wasm_bindgen_test]
+fn patches_mismatches() {
+    let mount = create_mount();
+    mount.set_inner_html(r#"<div class="old">Old text<em></em><br></div>"#);
+    let mount = mount.first_element_child().unwrap();
+
+    let vdom = html! { <div id="new">New text<strong></strong></div> };
+    let expected = vdom.to_string();
+
+    let mut pdom = PercyDom::hydrate(vdom, mount.clone());
+    assert_eq!(mount.outer_html(), expected);
+
+    pdom.update(html! { <div id="new">New text<strong>Patched</strong></div> });
+    assert_eq!(
+        mount.outer_html(),
+        r#"<div id="new">New text<strong>Patched</strong></div>"#
+    );
+}
+
+/// Verify that if the root element does not match the virtual node we replace it.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- replaces_mismatched_root
+#[wasm_bindgen_test]
+fn replaces_mismatched_root() {
+    let vdom = html! { <span>Server</span> };
+    let mount = server_render(&vdom);
+    let parent = mount.parent_element().unwrap();
+
+    let mut pdom = PercyDom::hydrate(html! { <div>Client</div> }, mount);
+    assert_eq!(parent.inner_html(), "<div>Client</div>");
+
+    pdom.update(html! { <div>Updated</div> });
+    assert_eq!(parent.inner_html(), "<div>Updated</div>");
+}
+
+/// Verify that a server rendered conditional comment gets hydrated without any mismatches.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- hydrates_conditional_comment
+#[wasm_bindgen_test]
+fn hydrates_conditional_comment() {
+    let comment = VirtualNode::comment("[if IE]><p>Old browser</p><![endif]");
+    let vdom = html! { <div>{comment}</div> };
+    let mount = server_render(&vdom);
+    let comment_node = mount.first_child().unwrap();
+
+    assert_eq!(hydration_mismatches(&vdom, &mount), Vec::<String>::new());
+    assert!(mount
+        .first_child()
+        .unwrap()
+        .is_same_node(Some(&comment_node)));
+}
+
+/// Verify that a comment between two text nodes whose text resembles a text separator gets
+/// hydrated as a comment instead of being skipped.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test hydrate -- hydrates_comment_that_resembles_separator
+#[wasm_bindgen_test]
+fn hydrates_comment_that_resembles_separator() {
+    let comment = VirtualNode::comment("ptns");
+    let vdom = html! { <div>{"Hello"}{comment}{"world"}</div> };
+    let mount = server_render(&vdom);
+    assert_eq!(mount.inner_html(), "Hello<!--ptns-->world");
+
+    assert_eq!(hydration_mismatches(&vdom, &mount), Vec::<String>::new());
+    assert_eq!(mount.inner_html(), "Hello<!--ptns-->world");
+}
+
+// Render the virtual node to a string and parse it into the DOM, the same way that a browser
+// would when receiving server side rendered HTML.
+fn server_render(vdom: &VirtualNode) -> Element {
+    let mount = create_mount();
+    mount.set_inner_html(&vdom.to_string());
+
+    mount.first_element_child().unwrap()
+}
+
+// Hydrate the DOM node, returning the mismatches that were found.
+fn hydration_mismatches(vdom: &VirtualNode, mount: &Element) -> Vec<String> {
+    let mut mismatches = vec![];
+    vdom.hydrate_dom_node(
+        mount.clone().into(),
+        &mut VirtualEvents::new(),
+        &mut mismatches,
+    );
+
+    mismatches.iter().map(ToString::to_string).collect()
+}
@@ -10,6 +10,8 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test keyed_lists
 
+#![cfg(target_arch = "wasm32")]
+
 extern crate wasm_bindgen_test;
 extern crate web_sys;
 
@@ -0,0 +1,91 @@
+//! Test memo nodes, which are only rendered and diffed when their hash changes.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test memo
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+use testing_utilities::{create_mount, get_element_by_id, random_id, send_click_event};
+
+/// Verify that an unchanged memo keeps its DOM node and its events without being rendered again,
+/// while the nodes around it still get patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test memo -- unchanged_memo_keeps_dom_and_events
+#[wasm_bindgen_test]
+fn unchanged_memo_keeps_dom_and_events() {
+    let list_id = random_id();
+    let button_id = random_id();
+    let status_id = random_id();
+
+    let renders = Rc::new(Cell::new(0));
+    let clicks = Rc::new(Cell::new(0));
+
+    let render = |status: &'static str| {
+        let renders = renders.clone();
+        let clicks = clicks.clone();
+
+        let list = VirtualNode::memo("rows-v1", move || {
+            renders.set(renders.get() + 1);
+            html! {
+              <ul id=list_id>
+                <li><button id=button_id onclick=move || clicks.set(clicks.get() + 1)></button></li>
+              </ul>
+            }
+        });
+
+        html! { <div>{list} <em id=status_id>{status}</em></div> }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render("Loading"), &create_mount());
+    let list = get_element_by_id(list_id);
+
+    pdom.update(render("Loaded"));
+
+    assert_eq!(renders.get(), 1);
+    assert!(get_element_by_id(list_id).is_same_node(Some(&list)));
+    assert_eq!(get_element_by_id(status_id).inner_html(), "Loaded");
+
+    send_click_event(button_id);
+    assert_eq!(clicks.get(), 1);
+}
+
+/// Verify that a memo whose hash changed gets patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test memo -- changed_memo_is_patched
+#[wasm_bindgen_test]
+fn changed_memo_is_patched() {
+    let list_id = random_id();
+
+    let render = |version: u32| {
+        let list = VirtualNode::memo(version, move || {
+            let text = format!("Version {}", version);
+            html! { <ul id=list_id><li>{text}</li></ul> }
+        });
+
+        html! { <div>{list}</div> }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render(1), &create_mount());
+    let list = get_element_by_id(list_id);
+
+    pdom.update(render(1));
+    pdom.update(render(2));
+
+    assert!(get_element_by_id(list_id).is_same_node(Some(&list)));
+    assert_eq!(
+        get_element_by_id(list_id).inner_html(),
+        "<li>Version 2</li>"
+    );
+}
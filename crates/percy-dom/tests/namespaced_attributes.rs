@@ -0,0 +1,65 @@
+//! Verify that namespaced attributes such as `xlink:href` are set and removed within their
+//! namespace.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test namespaced_attributes
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use percy_dom::event::VirtualEvents;
+use percy_dom::namespace::{XLINK_NAMESPACE, XML_NAMESPACE};
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Verify that we set namespaced attributes when creating an element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test namespaced_attributes -- create_namespaced_attributes
+#[wasm_bindgen_test]
+fn create_namespaced_attributes() {
+    let svg = html! { <svg><use xlink:href="#icon" xml:lang="en" /></svg> };
+
+    let (node, _) = svg.create_dom_node(&mut VirtualEvents::new());
+    let use_elem = use_element(&node);
+
+    assert_eq!(
+        use_elem.get_attribute_ns(Some(XLINK_NAMESPACE), "href"),
+        Some("#icon".to_string())
+    );
+    assert_eq!(
+        use_elem.get_attribute_ns(Some(XML_NAMESPACE), "lang"),
+        Some("en".to_string())
+    );
+}
+
+/// Verify that we update and remove namespaced attributes when patching.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test namespaced_attributes -- patch_namespaced_attributes
+#[wasm_bindgen_test]
+fn patch_namespaced_attributes() {
+    let start = html! { <svg><use xlink:href="#a" xml:lang="en" /></svg> };
+    let end = html! { <svg><use xlink:href="#b" /></svg> };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node.clone(), &end, &mut events, &patches).unwrap();
+
+    let use_elem = use_element(&node);
+    assert_eq!(
+        use_elem.get_attribute_ns(Some(XLINK_NAMESPACE), "href"),
+        Some("#b".to_string())
+    );
+    assert!(!use_elem.has_attribute_ns(Some(XML_NAMESPACE), "lang"));
+}
+
+fn use_element(svg: &Node) -> Element {
+    svg.first_child().unwrap().dyn_into().unwrap()
+}
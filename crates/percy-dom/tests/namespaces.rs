@@ -0,0 +1,110 @@
+//! Verify that elements inherit the SVG and MathML namespaces of their ancestors, both when they
+//! are created and when they are inserted by a patch.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test namespaces
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use percy_dom::event::VirtualEvents;
+use percy_dom::namespace::{MATHML_NAMESPACE, SVG_NAMESPACE};
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const HTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Verify that the descendants of an `<svg>` are SVG elements until a `<foreignObject>`, even if
+/// their tags are also HTML tags.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test namespaces -- create_svg_descendants
+#[wasm_bindgen_test]
+fn create_svg_descendants() {
+    let div = html! {
+      <div>
+        <a></a>
+        <svg>
+          <a><title>Link</title></a>
+          <style></style>
+          <foreignObject><a></a></foreignObject>
+        </svg>
+      </div>
+    };
+
+    let (node, _) = div.create_dom_node(&mut VirtualEvents::new());
+    let div: Element = node.dyn_into().unwrap();
+
+    assert_eq!(namespace(&div, "div > a"), HTML_NAMESPACE);
+    assert_eq!(namespace(&div, "svg"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "svg > a"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "svg title"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "svg > style"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "foreignObject"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "foreignObject > a"), HTML_NAMESPACE);
+}
+
+/// Verify that the descendants of a `<math>` are MathML elements.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test namespaces -- create_mathml_descendants
+#[wasm_bindgen_test]
+fn create_mathml_descendants() {
+    let div = html! { <div><math><mfrac><mi>x</mi><mn>2</mn></mfrac></math></div> };
+
+    let (node, _) = div.create_dom_node(&mut VirtualEvents::new());
+    let div: Element = node.dyn_into().unwrap();
+
+    assert_eq!(namespace(&div, "math"), MATHML_NAMESPACE);
+    assert_eq!(namespace(&div, "mfrac"), MATHML_NAMESPACE);
+    assert_eq!(namespace(&div, "mi"), MATHML_NAMESPACE);
+}
+
+/// Verify that nodes created by the replace, insert before and append children patches inherit
+/// the namespace of the element that they are placed into.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test namespaces -- patched_nodes_inherit_namespace
+#[wasm_bindgen_test]
+fn patched_nodes_inherit_namespace() {
+    let start = html! {
+      <div>
+        <svg>
+          <g key="g"></g>
+          <circle />
+        </svg>
+      </div>
+    };
+    let end = html! {
+      <div>
+        <svg>
+          <a id="inserted"></a>
+          <g key="g"></g>
+          <title id="replaced"></title>
+          <style id="appended"></style>
+        </svg>
+      </div>
+    };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node.clone(), &end, &mut events, &patches).unwrap();
+
+    let div: Element = node.dyn_into().unwrap();
+    assert_eq!(namespace(&div, "#inserted"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "#replaced"), SVG_NAMESPACE);
+    assert_eq!(namespace(&div, "#appended"), SVG_NAMESPACE);
+}
+
+fn namespace(root: &Element, selector: &str) -> String {
+    root.query_selector(selector)
+        .unwrap()
+        .unwrap()
+        .namespace_uri()
+        .unwrap()
+}
@@ -0,0 +1,107 @@
+//! Test the `ref` special attribute.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test node_ref
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use wasm_bindgen_test::*;
+
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+use testing_utilities::create_mount;
+
+/// Verify that a NodeRef points at the element after the element is created.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test node_ref -- set_when_created
+#[wasm_bindgen_test]
+fn set_when_created() {
+    let node_ref = NodeRef::new();
+
+    let _pdom = PercyDom::new_append_to_mount(
+        html! { <div><input ref=node_ref id="created" /></div> },
+        &create_mount(),
+    );
+
+    let input = node_ref.cast::<web_sys::HtmlInputElement>().unwrap();
+    assert_eq!(input.id(), "created");
+}
+
+/// Verify that a NodeRef points at the new element after its element gets replaced.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test node_ref -- follows_replaced_element
+#[wasm_bindgen_test]
+fn follows_replaced_element() {
+    let node_ref = NodeRef::new();
+
+    let mut pdom =
+        PercyDom::new_append_to_mount(html! { <div><em ref=node_ref></em></div> }, &create_mount());
+    let em = node_ref.get().unwrap();
+
+    pdom.update(html! { <div><strong ref=node_ref></strong></div> });
+
+    let strong = node_ref.get().unwrap();
+    assert_eq!(strong.tag_name(), "STRONG");
+    assert!(strong.parent_element().is_some());
+    assert!(em.parent_element().is_none());
+}
+
+/// Verify that a NodeRef keeps pointing at the same element when it is patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test node_ref -- kept_when_patched
+#[wasm_bindgen_test]
+fn kept_when_patched() {
+    let node_ref = NodeRef::new();
+
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <div><em ref=node_ref class="before"></em></div> },
+        &create_mount(),
+    );
+    let em = node_ref.get().unwrap();
+
+    pdom.update(html! { <div><em ref=node_ref class="after"></em></div> });
+
+    assert_eq!(node_ref.get().unwrap(), em);
+    assert_eq!(em.class_name(), "after");
+}
+
+/// Verify that a NodeRef that moves to a different existing element points at that element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test node_ref -- moved_to_other_element
+#[wasm_bindgen_test]
+fn moved_to_other_element() {
+    let node_ref = NodeRef::new();
+
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <div><em ref=node_ref></em><strong></strong></div> },
+        &create_mount(),
+    );
+
+    pdom.update(html! { <div><em></em><strong ref=node_ref></strong></div> });
+
+    assert_eq!(node_ref.get().unwrap().tag_name(), "STRONG");
+}
+
+/// Verify that a NodeRef gets cleared when its element is removed.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test node_ref -- cleared_when_removed
+#[wasm_bindgen_test]
+fn cleared_when_removed() {
+    let node_ref = NodeRef::new();
+
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <div><span><em ref=node_ref></em></span></div> },
+        &create_mount(),
+    );
+    assert!(node_ref.is_set());
+
+    pdom.update(html! { <div></div> });
+
+    assert!(!node_ref.is_set());
+}
@@ -5,6 +5,8 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test pdom
 
+#![cfg(target_arch = "wasm32")]
+
 use console_error_panic_hook;
 use percy_dom::prelude::*;
 use percy_dom::PercyDom;
@@ -89,7 +91,10 @@ fn append_element_with_closure() {
         pdom.update(append_node);
     }
 
-    let input_event = InputEvent::new("input").unwrap();
+    // Input events bubble, which lets our event delegation hear them.
+    let init = InputEventInit::new();
+    init.set_bubbles(true);
+    let input_event = InputEvent::new_with_event_init_dict("input", &init).unwrap();
 
     assert_eq!(&*text.borrow(), "Start Text");
 
@@ -143,7 +148,10 @@ fn updates_active_closure_on_replace() {
         pdom.update(replace_node);
     }
 
-    let input_event = InputEvent::new("input").unwrap();
+    // Input events bubble, which lets our event delegation hear them.
+    let init = InputEventInit::new();
+    init.set_bubbles(true);
+    let input_event = InputEvent::new_with_event_init_dict("input", &init).unwrap();
 
     assert_eq!(&*text.borrow(), "Start Text");
 
@@ -0,0 +1,195 @@
+//! Test portals, which place an element's children into a container within a different element.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test portal
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+extern crate web_sys;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen_test::*;
+
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+mod testing_utilities;
+use testing_utilities::{create_mount, get_element_by_id, random_id, send_click_event};
+
+/// Verify that a portal's children are placed into a container within the target while the
+/// portal element stays where it is, empty.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- children_placed_in_target
+#[wasm_bindgen_test]
+fn children_placed_in_target() {
+    let portal_id = random_id();
+    let child_id = random_id();
+
+    let target = create_mount();
+    let portal_target = target.clone();
+
+    let app = html! {
+      <div>
+        <div id=portal_id portal=portal_target><em id=child_id>Modal</em></div>
+      </div>
+    };
+    let _pdom = PercyDom::new_append_to_mount(app, &create_mount());
+
+    let container = target.first_element_child().unwrap();
+    assert_eq!(container.tag_name().to_lowercase(), "percy-portal");
+    assert!(get_element_by_id(child_id)
+        .parent_element()
+        .unwrap()
+        .is_same_node(Some(&container)));
+    assert_eq!(get_element_by_id(portal_id).child_nodes().length(), 0);
+}
+
+/// Verify that a portal's children get patched.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- patches_portal_children
+#[wasm_bindgen_test]
+fn patches_portal_children() {
+    let child_id = random_id();
+
+    let target = create_mount();
+    let render = |text: &'static str| {
+        let portal_target = target.clone();
+        html! {
+          <div>
+            <div portal=portal_target><em id=child_id>{text}</em></div>
+            <span>After</span>
+          </div>
+        }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render("Old"), &create_mount());
+    let child = get_element_by_id(child_id);
+
+    pdom.update(render("New"));
+
+    assert!(get_element_by_id(child_id).is_same_node(Some(&child)));
+    assert_eq!(child.inner_html(), "New");
+}
+
+/// Verify that removing a portal removes the container that its children were placed into and
+/// calls its children's on_remove_element functions.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- removing_portal_removes_container
+#[wasm_bindgen_test]
+fn removing_portal_removes_container() {
+    let target = create_mount();
+    let portal_target = target.clone();
+
+    let removed = Rc::new(Cell::new(false));
+    let removed_clone = removed.clone();
+
+    let mut child = html! { <em></em> };
+    child
+        .as_velement_mut()
+        .unwrap()
+        .special_attributes
+        .set_on_remove_element("portal-child", move |_elem: web_sys::Element| {
+            removed_clone.set(true);
+        });
+
+    let old = html! { <div><div portal=portal_target>{child}</div></div> };
+    let mut pdom = PercyDom::new_append_to_mount(old, &create_mount());
+    assert_eq!(target.child_element_count(), 1);
+
+    pdom.update(html! { <div></div> });
+
+    assert_eq!(target.child_element_count(), 0);
+    assert!(removed.get());
+}
+
+/// Verify that changing a portal's target moves its children into the new target.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- changing_target_moves_children
+#[wasm_bindgen_test]
+fn changing_target_moves_children() {
+    let child_id = random_id();
+
+    let first_target = create_mount();
+    let second_target = create_mount();
+
+    let render = |target: &web_sys::Element| {
+        let portal_target = target.clone();
+        html! { <div><div portal=portal_target><em id=child_id></em></div></div> }
+    };
+
+    let mut pdom = PercyDom::new_append_to_mount(render(&first_target), &create_mount());
+    pdom.update(render(&second_target));
+
+    assert_eq!(first_target.child_element_count(), 0);
+    assert!(second_target.contains(Some(&get_element_by_id(child_id))));
+}
+
+/// Verify that events within a portal's children are handled, and that they bubble up to the
+/// portal's ancestors instead of the target's ancestors.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- events_bubble_to_portal_ancestors
+#[wasm_bindgen_test]
+fn events_bubble_to_portal_ancestors() {
+    let button_id = random_id();
+
+    let target = create_mount();
+    let portal_target = target.clone();
+
+    let button_clicks = Rc::new(Cell::new(0));
+    let wrapper_clicks = Rc::new(Cell::new(0));
+    let button_clicks_clone = button_clicks.clone();
+    let wrapper_clicks_clone = wrapper_clicks.clone();
+
+    let app = html! {
+      <div onclick=move || wrapper_clicks_clone.set(wrapper_clicks_clone.get() + 1)>
+        <div portal=portal_target>
+          <button
+            id=button_id
+            onclick=move || button_clicks_clone.set(button_clicks_clone.get() + 1)
+          ></button>
+        </div>
+      </div>
+    };
+    let _pdom = PercyDom::new_append_to_mount(app, &create_mount());
+
+    send_click_event(button_id);
+
+    assert_eq!(button_clicks.get(), 1);
+    assert_eq!(wrapper_clicks.get(), 1);
+}
+
+/// Verify that an event within a portal container that is inside of the root node is only
+/// handled once, even though both the container and the root node listen for it.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test portal -- container_within_root_handles_events_once
+#[wasm_bindgen_test]
+fn container_within_root_handles_events_once() {
+    let button_id = random_id();
+    let target_id = random_id();
+
+    let clicks = Rc::new(Cell::new(0));
+    let clicks_clone = clicks.clone();
+
+    // The target needs to exist before the portal is created, so we add the portal afterwards.
+    let mut pdom = PercyDom::new_append_to_mount(
+        html! { <div><div id=target_id></div></div> },
+        &create_mount(),
+    );
+
+    let app = html! {
+      <div>
+        <div id=target_id></div>
+        <div portal=get_element_by_id(target_id)>
+          <button id=button_id onclick=move || clicks_clone.set(clicks_clone.get() + 1)></button>
+        </div>
+      </div>
+    };
+    pdom.update(app);
+
+    send_click_event(button_id);
+
+    assert_eq!(clicks.get(), 1);
+}
@@ -0,0 +1,64 @@
+//! Verify that the `prop:name=value` DOM properties get set on created, hydrated and patched
+//! elements.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test properties
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use js_sys::Reflect;
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Verify that we set properties when creating an element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test properties -- create_element_with_properties
+#[wasm_bindgen_test]
+fn create_element_with_properties() {
+    let data = js_sys::Object::new();
+    let input = html! {
+        <input type="checkbox" prop:indeterminate=true prop:customData=JsValue::from(&data) />
+    };
+
+    let (node, _) = input.create_dom_node(&mut VirtualEvents::new());
+    let input: HtmlInputElement = node.clone().dyn_into().unwrap();
+
+    assert!(input.indeterminate());
+    assert_eq!(get(&node, "customData"), JsValue::from(data));
+
+    // Properties are not attributes.
+    assert!(input.get_attribute("indeterminate").is_none());
+}
+
+/// Verify that we set and clear properties when patching an element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test properties -- patch_properties
+#[wasm_bindgen_test]
+fn patch_properties() {
+    let start = html! { <input prop:indeterminate=false prop:customNumber=5 /> };
+    let end = html! { <input prop:indeterminate=true /> };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    assert_eq!(get(&node, "customNumber"), JsValue::from(5.));
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node.clone(), &end, &mut events, &patches).unwrap();
+
+    let input: HtmlInputElement = node.clone().dyn_into().unwrap();
+    assert!(input.indeterminate());
+    assert_eq!(get(&node, "customNumber"), JsValue::UNDEFINED);
+}
+
+fn get(node: &Node, property: &str) -> JsValue {
+    Reflect::get(node, &property.into()).unwrap()
+}
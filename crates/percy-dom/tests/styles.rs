@@ -0,0 +1,55 @@
+//! Verify that inline styles get set on created elements and are patched one property at a time.
+//!
+//! To run all tests in this file:
+//!
+//! wasm-pack test --chrome --headless crates/percy-dom --test styles
+
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::*;
+
+use percy_dom::event::VirtualEvents;
+use percy_dom::prelude::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Verify that we set styles, including their priority, when creating an element.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test styles -- create_element_with_styles
+#[wasm_bindgen_test]
+fn create_element_with_styles() {
+    let div = html! { <div style="color: red; width: 10px !important"></div> };
+
+    let (node, _) = div.create_dom_node(&mut VirtualEvents::new());
+    let style = node.dyn_into::<HtmlElement>().unwrap().style();
+
+    assert_eq!(style.get_property_value("color").unwrap(), "red");
+    assert_eq!(style.get_property_value("width").unwrap(), "10px");
+    assert_eq!(style.get_property_priority("width"), "important");
+}
+
+/// Verify that patching styles only touches the properties that changed, so that a style that
+/// was set on the element by other code survives the patch.
+///
+/// wasm-pack test --chrome --headless crates/percy-dom --test styles -- patch_styles
+#[wasm_bindgen_test]
+fn patch_styles() {
+    let start = html! { <div style="color: red; width: 10px"></div> };
+    let end = html! { <div style="color: blue"></div> };
+
+    let mut events = VirtualEvents::new();
+    let (node, events_node) = start.create_dom_node(&mut events);
+    events.set_root(events_node);
+
+    let style = node.clone().dyn_into::<HtmlElement>().unwrap().style();
+    style.set_property("opacity", "0.5").unwrap();
+
+    let patches = percy_dom::diff(&start, &end);
+    percy_dom::patch(node, &end, &mut events, &patches).unwrap();
+
+    assert_eq!(style.get_property_value("color").unwrap(), "blue");
+    assert_eq!(style.get_property_value("width").unwrap(), "");
+    assert_eq!(style.get_property_value("opacity").unwrap(), "0.5");
+}
@@ -1,5 +1,7 @@
 //! Various helper functions and types for writing tests.
 
+#![cfg(target_arch = "wasm32")]
+
 use std::cell::RefCell;
 use std::rc::Rc;
 use virtual_node::event::{ElementEventsId, VirtualEventNode, VirtualEvents};
@@ -54,7 +56,11 @@ pub fn send_click_event(id: &str) {
 }
 
 pub fn send_input_event(id: &str) {
-    send_event::<web_sys::HtmlInputElement>(id, &web_sys::InputEvent::new("input").unwrap());
+    let init = web_sys::InputEventInit::new();
+    init.set_bubbles(true);
+    let input_event = web_sys::InputEvent::new_with_event_init_dict("input", &init).unwrap();
+
+    send_event::<web_sys::HtmlInputElement>(id, &input_event);
 }
 
 pub fn send_event<T>(elem_id: &str, event: &web_sys::Event)
@@ -4,6 +4,8 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test text
 
+#![cfg(target_arch = "wasm32")]
+
 extern crate wasm_bindgen_test;
 extern crate web_sys;
 
@@ -111,20 +113,21 @@ fn replace_text_with_element() {
 #[wasm_bindgen_test]
 fn text_node_siblings() {
     // TODO: Requires proc macro APIs that are currently unstable - https://github.com/rust-lang/rust/issues/54725
-    // // NOTE: Since there are two text nodes next to eachother we expect a `<!--ptns-->` separator in
+    // // NOTE: Since there are two text nodes next to eachother we expect a `<!--percy:ptns-->` separator in
     // // between them.
     // // @see virtual_node/mod.rs -> create_dom_node() for more information
     // // TODO: A little more spacing than there should be in between the text nodes ... but doesn't
     // //  impact the user experience so we can look into that later..
     // let override_expected = Some(
-    //     r#"<div id="after"><span> The button has been clicked:  <!--ptns--> world </span></div>"#,
+    //     r#"<div id="after"><span> The button has been clicked:  <!--percy:ptns--> world </span></div>"#,
     // );
 
     // TODO: After the proc macro span APIs stabilize remove this in favor of the above commented out
     //  code.
     //   https://github.com/rust-lang/rust/issues/54725
-    let override_expected =
-        Some(r#"<div id="after"><span>The button has been clicked: <!--ptns-->world</span></div>"#);
+    let override_expected = Some(
+        r#"<div id="after"><span>The button has been clicked: <!--percy:ptns-->world</span></div>"#,
+    );
 
     let old1 = VirtualNode::text("The button has been clicked: ");
     let old2 = VirtualNode::text("hello");
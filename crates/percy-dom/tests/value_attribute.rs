@@ -6,6 +6,8 @@
 //!
 //! wasm-pack test --chrome --headless crates/percy-dom --test value_attribute
 
+#![cfg(target_arch = "wasm32")]
+
 use wasm_bindgen_test::*;
 
 use percy_dom::event::VirtualEvents;
@@ -2,33 +2,51 @@
 
 #![deny(missing_docs)]
 
-#[macro_use]
+#[cfg_attr(target_arch = "wasm32", macro_use)]
 extern crate sunbeam;
 
+#[cfg(target_arch = "wasm32")]
 use self::app::PercyPreviewApp;
+#[cfg(target_arch = "wasm32")]
 use self::render::render_app;
+#[cfg(target_arch = "wasm32")]
 use std::sync::{Arc, Mutex};
 
+#[cfg(target_arch = "wasm32")]
 pub use self::app::async_task_spawner;
+#[cfg(target_arch = "wasm32")]
 use crate::app::AppConfig;
+#[cfg(target_arch = "wasm32")]
 pub use crate::config::WebClientConfig;
+#[cfg(target_arch = "wasm32")]
 use crate::window_messenger::WindowMessenger;
+#[cfg(target_arch = "wasm32")]
 use percy_dom::single_page_app::{intercept_relative_links, set_onpopstate_handler};
+#[cfg(target_arch = "wasm32")]
 use percy_dom::{render::create_render_scheduler, PercyDom, VirtualNode};
+#[cfg(target_arch = "wasm32")]
 use routes::create_router;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
+#[cfg(target_arch = "wasm32")]
 mod app;
+#[cfg(target_arch = "wasm32")]
 mod config;
+#[cfg(target_arch = "wasm32")]
 mod render;
+#[cfg(target_arch = "wasm32")]
 mod routes;
+#[cfg(target_arch = "wasm32")]
 mod view;
 
+#[cfg(target_arch = "wasm32")]
 mod window_messenger;
 
 pub mod all_sunbeam_css;
 
 /// A frontend web application that lets you preview your own application's view components.
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 pub struct PercyPreviewWebClient {
     /// Rerender the application.
@@ -36,6 +54,7 @@ pub struct PercyPreviewWebClient {
     pub rerender: Arc<Mutex<Box<dyn FnMut() -> ()>>>,
 }
 
+#[cfg(target_arch = "wasm32")]
 impl PercyPreviewWebClient {
     /// Create a new preview application and append the application to a DOM node.
     pub fn new_append_to_mount(config: WebClientConfig, dom_selector_of_mount: &str) -> Self {
@@ -62,6 +81,7 @@ impl PercyPreviewWebClient {
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 fn create_app(
     config: WebClientConfig,
     render: Arc<Mutex<Box<dyn FnMut() -> ()>>>,
@@ -80,6 +100,7 @@ fn create_app(
     )
 }
 
+#[cfg(target_arch = "wasm32")]
 fn setup_single_page_application(window_messenger: WindowMessenger) {
     window_messenger.msg_set_path(window().location().pathname().unwrap().to_string());
 
@@ -94,6 +115,7 @@ fn setup_single_page_application(window_messenger: WindowMessenger) {
     });
 }
 
+#[cfg(target_arch = "wasm32")]
 fn create_percy_dom(dom_selector_of_mount: &str, start_view: VirtualNode) -> PercyDom {
     let mount = document()
         .query_selector(dom_selector_of_mount)
@@ -103,14 +125,17 @@ fn create_percy_dom(dom_selector_of_mount: &str, start_view: VirtualNode) -> Per
     pdom
 }
 
+#[cfg(target_arch = "wasm32")]
 fn window() -> web_sys::Window {
     web_sys::window().unwrap()
 }
 
+#[cfg(target_arch = "wasm32")]
 fn document() -> web_sys::Document {
     window().document().unwrap()
 }
 
+#[cfg(target_arch = "wasm32")]
 fn history() -> web_sys::History {
     window().history().unwrap()
 }
@@ -0,0 +1,584 @@
+//! Components that own local state and re-render their own subtree when that state changes.
+//!
+//! A [`View`] is stateless, so changing what it renders means re-rendering the whole application.
+//! A [`Component`] instead keeps its state across re-renders of its parent, and a change to that
+//! state only re-renders the component.
+//!
+//! In the browser a component's view is rendered into its own host element by a nested
+//! `PercyDom`, so the component's parent only ever diffs the empty host element. When rendering
+//! to a `String` the component's view is rendered inside of its host element using its initial
+//! state, and when that HTML gets hydrated the nested `PercyDom` hydrates the host element's
+//! children instead of creating new ones.
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+
+#[cfg(target_arch = "wasm32")]
+use std::any::Any;
+#[cfg(target_arch = "wasm32")]
+use std::cell::Cell;
+#[cfg(target_arch = "wasm32")]
+use std::collections::HashMap;
+#[cfg(all(target_arch = "wasm32", debug_assertions))]
+use std::collections::HashSet;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::closure::Closure;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+
+use crate::{View, VirtualNode};
+
+/// The tag of the element that a component's view gets rendered into.
+pub(crate) const COMPONENT_HOST_TAG: &str = "percy-component";
+
+/// A view that owns local state, such as whether or not a dropdown is open.
+///
+/// The state is created when the component is first rendered and is kept for as long as the
+/// component keeps getting rendered with the same [`Component::key`]. It is dropped when the
+/// component's element is removed.
+///
+/// Every `Component` is also a [`View`], so it can be used in the `html!` macro like any other
+/// component.
+///
+/// # Examples
+///
+/// ```
+/// # use virtual_node::{component::{Component, Scope}, VirtualNode};
+/// #[derive(Clone)]
+/// struct Dropdown {
+///     key: &'static str,
+/// }
+///
+/// impl Component for Dropdown {
+///     type State = bool;
+///
+///     fn key(&self) -> String {
+///         self.key.to_string()
+///     }
+///
+///     fn initial_state(&self) -> bool {
+///         false
+///     }
+///
+///     fn view(&self, is_open: &bool, scope: &Scope<Self>) -> VirtualNode {
+///         let scope = scope.clone();
+///         let toggle = move || scope.update(|is_open| *is_open = !*is_open);
+///         # let _ = toggle;
+///
+///         // html! { <button onclick=toggle>{ if *is_open { "Close" } else { "Open" } }</button> }
+///         VirtualNode::text(if *is_open { "Close" } else { "Open" })
+///     }
+/// }
+/// ```
+pub trait Component: Clone + 'static {
+    /// The component's local state.
+    type State: 'static;
+
+    /// Identifies the component among the other components of the same type, so that its state
+    /// can be found again when its parent re-renders.
+    ///
+    /// Every `PercyDom` has its own registry of components, so every component of the same type
+    /// that is in the same `PercyDom` at the same time needs a unique key. In debug builds
+    /// rendering two live components with the same key panics.
+    ///
+    /// The key is also used as the key of the component's host element, so that a component in
+    /// a keyed list keeps its element when the list is re-ordered.
+    fn key(&self) -> String;
+
+    /// Create the component's state the first time that it is rendered.
+    fn initial_state(&self) -> Self::State;
+
+    /// Render the component using its latest props and its state.
+    ///
+    /// Use the `scope` to update the state, such as from within an event handler.
+    fn view(&self, state: &Self::State, scope: &Scope<Self>) -> VirtualNode;
+}
+
+/// Rendering a component only describes its host element. In the browser the component's state is
+/// found, and its view rendered, once the host element gets patched into the DOM.
+impl<C: Component> View for C {
+    fn render(&self) -> VirtualNode {
+        render_component(self)
+    }
+}
+
+/// A handle to a component's state that can be moved into event handlers.
+///
+/// Updating the state re-renders the component, but not its parent.
+pub struct Scope<C: Component> {
+    instance: Weak<ComponentInstance<C>>,
+}
+
+impl<C: Component> Scope<C> {
+    /// Update the state and re-render the component on the next animation frame.
+    ///
+    /// Any number of updates before the next animation frame lead to one re-render.
+    ///
+    /// An update that is made while the component's view is being rendered gets applied once the
+    /// view is done.
+    ///
+    /// Does nothing if the component has been removed.
+    pub fn update<F: FnOnce(&mut C::State) + 'static>(&self, update: F) {
+        if let Some(instance) = self.instance.upgrade() {
+            instance.queue_update(update);
+
+            #[cfg(target_arch = "wasm32")]
+            instance.schedule_render();
+        }
+    }
+
+    /// Update the state and re-render the component right away.
+    ///
+    /// If the component's view is being rendered the update gets applied once the view is done,
+    /// and the component gets re-rendered on the next animation frame.
+    ///
+    /// Does nothing if the component has been removed.
+    pub fn update_now<F: FnOnce(&mut C::State) + 'static>(&self, update: F) {
+        if let Some(instance) = self.instance.upgrade() {
+            instance.queue_update(update);
+
+            #[cfg(target_arch = "wasm32")]
+            if instance.is_rendering() {
+                instance.schedule_render();
+            } else {
+                instance.rerender();
+            }
+        }
+    }
+}
+
+impl<C: Component> Clone for Scope<C> {
+    fn clone(&self) -> Self {
+        Scope {
+            instance: self.instance.clone(),
+        }
+    }
+}
+
+/// Renders a component's view into its host element, and keeps it up to date.
+///
+/// Implemented by `PercyDom`.
+#[cfg(target_arch = "wasm32")]
+pub trait ComponentRenderer {
+    /// Patch the component's DOM to match its latest view.
+    fn update(&mut self, view: VirtualNode);
+
+    /// Remove the component's DOM from its host element, calling the `on_remove_element`
+    /// functions and removing the event listeners of everything in it.
+    fn unmount(&mut self);
+}
+
+/// Render a component's view into its host element.
+#[cfg(target_arch = "wasm32")]
+pub type MountComponentFn = fn(VirtualNode, &web_sys::Element) -> Box<dyn ComponentRenderer>;
+
+// Used by `PercyDom`, since the nested `PercyDom`s that components get rendered into can't be
+// created from within this crate.
+#[cfg(target_arch = "wasm32")]
+#[doc(hidden)]
+pub fn set_mount_component_fn(mount: MountComponentFn) {
+    MOUNT_COMPONENT.with(|mount_component| mount_component.set(Some(mount)));
+}
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    static MOUNT_COMPONENT: Cell<Option<MountComponentFn>> = Cell::new(None);
+
+    /// The registries of the `PercyDom`s that are creating or patching their DOM, innermost last.
+    static ENTERED_REGISTRIES: RefCell<Vec<ComponentRegistry>> = RefCell::new(vec![]);
+}
+
+/// The components that are in the DOM of one `PercyDom`, so that a component's state can be found
+/// again when its parent re-renders.
+///
+/// Every `PercyDom` has its own registry, so two `PercyDom`s that render the same component with
+/// the same key do not share its state.
+#[cfg(target_arch = "wasm32")]
+#[derive(Clone, Default)]
+pub struct ComponentRegistry {
+    components: Rc<RefCell<HashMap<ComponentId, Rc<dyn Any>>>>,
+    /// The components that were mounted since the registry was entered. A component that gets
+    /// mounted twice in one patch was rendered twice with the same key.
+    #[cfg(debug_assertions)]
+    mounted_since_enter: Rc<RefCell<HashSet<ComponentId>>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl ComponentRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use this registry for the components that get mounted, re-rendered or removed while `f`
+    /// runs, such as while creating, hydrating or patching DOM nodes.
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        ENTERED_REGISTRIES.with(|registries| registries.borrow_mut().push(self.clone()));
+        let result = f();
+        ENTERED_REGISTRIES.with(|registries| registries.borrow_mut().pop());
+
+        #[cfg(debug_assertions)]
+        self.mounted_since_enter.borrow_mut().clear();
+
+        result
+    }
+
+    fn entered() -> Self {
+        ENTERED_REGISTRIES
+            .with(|registries| registries.borrow().last().cloned())
+            .expect("Components can only be rendered into a PercyDom")
+    }
+
+    fn get<C: Component>(&self, id: &ComponentId) -> Option<Rc<ComponentInstance<C>>> {
+        let instance = self.components.borrow().get(id).cloned();
+        instance.and_then(|instance| instance.downcast::<ComponentInstance<C>>().ok())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ComponentId {
+    type_id: TypeId,
+    key: String,
+}
+
+impl ComponentId {
+    fn of<C: Component>(component: &C) -> Self {
+        ComponentId {
+            type_id: TypeId::of::<C>(),
+            key: component.key(),
+        }
+    }
+
+    /// Used as the key of the host element's `on_create_element`, `on_remove_element` and
+    /// `on_render_component` functions, so that they get called when a different component takes
+    /// over the element.
+    #[cfg(target_arch = "wasm32")]
+    fn host_fn_key<C: Component>(&self) -> String {
+        format!("{}:{}", std::any::type_name::<C>(), self.key)
+    }
+}
+
+type StateUpdate<C> = Box<dyn FnOnce(&mut <C as Component>::State)>;
+
+struct ComponentInstance<C: Component> {
+    props: RefCell<C>,
+    state: RefCell<C::State>,
+    /// Updates that were made while the state was borrowed by the view.
+    pending_updates: RefCell<Vec<StateUpdate<C>>>,
+    #[cfg(target_arch = "wasm32")]
+    mounted: RefCell<Option<MountedComponent>>,
+    #[cfg(target_arch = "wasm32")]
+    render_is_scheduled: Cell<bool>,
+    /// The host elements that the component was mounted into before its current one. They get
+    /// removed from the DOM when the patch that replaced them is done, unless another live
+    /// component is using the same key.
+    #[cfg(all(target_arch = "wasm32", debug_assertions))]
+    replaced_hosts: RefCell<Vec<web_sys::Element>>,
+}
+
+#[cfg(target_arch = "wasm32")]
+struct MountedComponent {
+    host: web_sys::Element,
+    renderer: Box<dyn ComponentRenderer>,
+}
+
+impl<C: Component> ComponentInstance<C> {
+    fn new(props: C) -> Rc<Self> {
+        Rc::new(ComponentInstance {
+            state: RefCell::new(props.initial_state()),
+            props: RefCell::new(props),
+            pending_updates: RefCell::new(vec![]),
+            #[cfg(target_arch = "wasm32")]
+            mounted: RefCell::new(None),
+            #[cfg(target_arch = "wasm32")]
+            render_is_scheduled: Cell::new(false),
+            #[cfg(all(target_arch = "wasm32", debug_assertions))]
+            replaced_hosts: RefCell::new(vec![]),
+        })
+    }
+
+    fn view(self: &Rc<Self>) -> VirtualNode {
+        self.apply_pending_updates();
+
+        let scope = Scope {
+            instance: Rc::downgrade(self),
+        };
+
+        let view = self.props.borrow().view(&self.state.borrow(), &scope);
+        // Apply the updates that the view made.
+        self.apply_pending_updates();
+
+        view
+    }
+
+    fn queue_update<F: FnOnce(&mut C::State) + 'static>(&self, update: F) {
+        self.pending_updates.borrow_mut().push(Box::new(update));
+        self.apply_pending_updates();
+    }
+
+    /// Apply the queued updates, unless the view is borrowing the state. An update can queue more
+    /// updates, which get applied in the same pass.
+    fn apply_pending_updates(&self) {
+        let mut state = match self.state.try_borrow_mut() {
+            Ok(state) => state,
+            Err(_) => return,
+        };
+
+        loop {
+            let updates = std::mem::take(&mut *self.pending_updates.borrow_mut());
+            if updates.is_empty() {
+                return;
+            }
+
+            for update in updates {
+                update(&mut state);
+            }
+        }
+    }
+
+    /// The view borrows the state while it is being rendered.
+    #[cfg(target_arch = "wasm32")]
+    fn is_rendering(&self) -> bool {
+        self.state.try_borrow_mut().is_err()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn render_component<C: Component>(props: &C) -> VirtualNode {
+    let instance = ComponentInstance::new(props.clone());
+
+    let mut host = host_node(&ComponentId::of(props));
+    host.as_velement_mut()
+        .unwrap()
+        .children
+        .push(instance.view());
+
+    host
+}
+
+/// Create the component's host element.
+///
+/// The component gets mounted when the host element is created, re-rendered with its new props
+/// when the host element is patched, and unmounted when the host element is removed. All of this
+/// happens while the `PercyDom` that the host element belongs to patches its DOM, using that
+/// `PercyDom`'s [`ComponentRegistry`].
+#[cfg(target_arch = "wasm32")]
+fn render_component<C: Component>(props: &C) -> VirtualNode {
+    let id = ComponentId::of(props);
+
+    let mut host = host_node(&id);
+    let special_attributes = &mut host.as_velement_mut().unwrap().special_attributes;
+
+    let (create_props, create_id) = (props.clone(), id.clone());
+    special_attributes.set_on_create_element(id.host_fn_key::<C>(), move |host| {
+        ComponentInstance::mount(create_props.clone(), &create_id, host);
+    });
+    let (render_props, render_id) = (props.clone(), id.clone());
+    special_attributes.set_on_render_component(id.host_fn_key::<C>(), move |_host| {
+        ComponentInstance::render_with_props(render_props.clone(), &render_id);
+    });
+    special_attributes.set_on_remove_element(id.host_fn_key::<C>(), move |host| {
+        ComponentInstance::<C>::unmount(&id, &host);
+    });
+
+    host
+}
+
+#[cfg(all(target_arch = "wasm32", debug_assertions))]
+fn panic_duplicate_key<C: Component>(key: &str) -> ! {
+    panic!(
+        "More than one {} component is rendered with the key {:?}. Components of the same type \
+         that are in the DOM at the same time need unique keys.",
+        std::any::type_name::<C>(),
+        key
+    );
+}
+
+fn host_node(id: &ComponentId) -> VirtualNode {
+    let mut host = VirtualNode::element(COMPONENT_HOST_TAG);
+
+    let element = host.as_velement_mut().unwrap();
+    element
+        .attrs
+        .insert("key".to_string(), id.key.as_str().into());
+    // The host element should not affect the page's layout.
+    element.styles = "display: contents".into();
+
+    host
+}
+
+#[cfg(target_arch = "wasm32")]
+impl<C: Component> ComponentInstance<C> {
+    /// Find the component's state, or create it if this is the first time that the component is
+    /// being rendered, and render the component into the host element.
+    fn mount(props: C, id: &ComponentId, host: web_sys::Element) {
+        let mount = MOUNT_COMPONENT
+            .with(|mount| mount.get())
+            .expect("Components can only be rendered into a PercyDom");
+        let registry = ComponentRegistry::entered();
+
+        let instance = match registry.get::<C>(id) {
+            Some(instance) => {
+                *instance.props.borrow_mut() = props;
+                instance
+            }
+            None => ComponentInstance::new(props),
+        };
+
+        #[cfg(debug_assertions)]
+        {
+            if !registry.mounted_since_enter.borrow_mut().insert(id.clone()) {
+                panic_duplicate_key::<C>(&id.key);
+            }
+            instance.assert_key_is_unique();
+        }
+
+        // The component's old host element is being replaced.
+        let previous = instance.mounted.borrow_mut().take();
+        if let Some(mut previous) = previous {
+            previous.renderer.unmount();
+
+            #[cfg(debug_assertions)]
+            instance.replaced_hosts.borrow_mut().push(previous.host);
+        }
+
+        let renderer = mount(instance.view(), &host);
+        *instance.mounted.borrow_mut() = Some(MountedComponent { host, renderer });
+
+        registry
+            .components
+            .borrow_mut()
+            .insert(id.clone(), instance as Rc<dyn Any>);
+    }
+
+    /// Re-render a component whose host element was kept by its parent's patch.
+    fn render_with_props(props: C, id: &ComponentId) {
+        if let Some(instance) = ComponentRegistry::entered().get::<C>(id) {
+            *instance.props.borrow_mut() = props;
+            instance.rerender();
+        }
+    }
+
+    /// The host element might have already been replaced by a new one that the component was
+    /// mounted into, in which case there is nothing to do.
+    fn unmount(id: &ComponentId, host: &web_sys::Element) {
+        let registry = ComponentRegistry::entered();
+        let instance = match registry.get::<C>(id) {
+            Some(instance) => instance,
+            None => return,
+        };
+
+        let is_mounted_in_host = match instance.mounted.borrow().as_ref() {
+            Some(mounted) => &mounted.host == host,
+            None => false,
+        };
+        if !is_mounted_in_host {
+            return;
+        }
+
+        let mounted = instance.mounted.borrow_mut().take();
+        if let Some(mut mounted) = mounted {
+            mounted.renderer.unmount();
+        }
+
+        registry.components.borrow_mut().remove(id);
+    }
+
+    /// Panic if another live component is using the same key.
+    ///
+    /// Every component that is rendered with the same key takes over the same state, so a host
+    /// element that the component was moved out of is still in the DOM if another component is
+    /// using the key.
+    #[cfg(debug_assertions)]
+    fn assert_key_is_unique(&self) {
+        let mut replaced_hosts = self.replaced_hosts.borrow_mut();
+        replaced_hosts.retain(|host| host.is_connected());
+
+        if !replaced_hosts.is_empty() {
+            panic_duplicate_key::<C>(&self.props.borrow().key());
+        }
+    }
+
+    fn rerender(self: &Rc<Self>) {
+        if self.mounted.borrow().is_none() {
+            return;
+        }
+
+        #[cfg(debug_assertions)]
+        self.assert_key_is_unique();
+
+        let view = self.view();
+        if let Some(mounted) = self.mounted.borrow_mut().as_mut() {
+            mounted.renderer.update(view);
+        }
+    }
+
+    fn schedule_render(self: &Rc<Self>) {
+        if self.render_is_scheduled.replace(true) {
+            return;
+        }
+
+        let instance = Rc::downgrade(self);
+        let render = Closure::once_into_js(move || {
+            if let Some(instance) = instance.upgrade() {
+                instance.render_is_scheduled.set(false);
+                instance.rerender();
+            }
+        });
+
+        web_sys::window()
+            .unwrap()
+            .request_animation_frame(render.unchecked_ref())
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Greeting {
+        name: &'static str,
+    }
+
+    impl Component for Greeting {
+        type State = &'static str;
+
+        fn key(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn initial_state(&self) -> &'static str {
+            "Hello"
+        }
+
+        fn view(&self, greeting: &&'static str, _scope: &Scope<Self>) -> VirtualNode {
+            VirtualNode::text(format!("{}, {}", greeting, self.name))
+        }
+    }
+
+    /// Verify that when rendering to a `String` a component's view is rendered inside of its host
+    /// element using the component's initial state.
+    #[test]
+    fn renders_initial_state_inside_host() {
+        let node = Greeting { name: "world" }.render();
+
+        assert_eq!(
+            node.to_string(),
+            r#"<percy-component key="world" style="display: contents">Hello, world</percy-component>"#
+        );
+    }
+
+    /// Verify that updating the state of a component that is no longer rendered does nothing.
+    #[test]
+    fn update_after_drop_does_nothing() {
+        let scope: Scope<Greeting> = Scope {
+            instance: Weak::new(),
+        };
+
+        scope.update(|greeting| *greeting = "Bye");
+        scope.update_now(|greeting| *greeting = "Bye");
+    }
+}
@@ -1,13 +1,26 @@
 use js_sys::Reflect;
 use std::cell::RefCell;
 use std::rc::Rc;
+use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
-use web_sys::{Document, Element};
+use web_sys::{
+    CssStyleDeclaration, Document, DocumentFragment, Element, ElementCreationOptions, Node,
+};
 
 use crate::event::{VirtualEventElement, VirtualEvents};
-use crate::{AttributeValue, VElement, VirtualEventNode, VirtualNode};
+use crate::namespace;
+use crate::{
+    AttributeValue, PortalTarget, Styles, VElement, VirtualEventNode, VirtualNode, TEXT_SEPARATOR,
+};
 
 mod add_events;
+mod portal;
+mod shadow_root;
+
+use self::portal::create_portal_container;
+pub use self::portal::{portal_container, portal_of_container};
+pub use self::shadow_root::template_shadow_root;
+pub(crate) use self::shadow_root::{attach_declarative_shadow_root, set_template_shadow_root};
 
 // Used to indicate that a DOM node was created from a virtual-node.
 #[doc(hidden)]
@@ -16,34 +29,59 @@ pub const VIRTUAL_NODE_MARKER_PROPERTY: &'static str = "__v__";
 impl VElement {
     /// Build a DOM element by recursively creating DOM nodes for this element and it's
     /// children, it's children's children, etc.
+    ///
+    /// The `parent_namespace` is the namespace that the element's parent's children get created
+    /// in. See [`namespace::element_namespace`].
     pub(crate) fn create_element_node(
         &self,
+        parent_namespace: Option<&'static str>,
         events: &mut VirtualEvents,
     ) -> (Element, VirtualEventNode) {
         let document = web_sys::window().unwrap().document().unwrap();
 
-        let element = if html_validation::is_svg_namespace(&self.tag) {
-            document
-                .create_element_ns(Some("http://www.w3.org/2000/svg"), &self.tag)
-                .unwrap()
-        } else {
-            document.create_element(&self.tag).unwrap()
+        let element_namespace = namespace::element_namespace(&self.tag, parent_namespace);
+        // A customized built-in element, such as `<button is="fancy-button">`, only gets
+        // upgraded if `is` is passed in when creating it.
+        let options = self.custom_element_is().map(|is| {
+            let options = ElementCreationOptions::new();
+            options.set_is(is);
+            options
+        });
+
+        let element = match (element_namespace, &options) {
+            (Some(element_namespace), None) => document
+                .create_element_ns(Some(element_namespace), &self.tag)
+                .unwrap(),
+            (Some(element_namespace), Some(options)) => document
+                .create_element_ns_with_element_creation_options(
+                    Some(element_namespace),
+                    &self.tag,
+                    options,
+                )
+                .unwrap(),
+            (None, None) => document.create_element(&self.tag).unwrap(),
+            (None, Some(options)) => document
+                .create_element_with_element_creation_options(&self.tag, options)
+                .unwrap(),
         };
         set_virtual_node_marker(&element);
 
         self.attrs.iter().for_each(|(name, value)| {
             match value {
                 AttributeValue::String(s) => {
-                    element.set_attribute(name, s).unwrap();
+                    namespace::set_attribute(&element, name, s).unwrap();
                 }
                 AttributeValue::Bool(b) => {
                     if *b {
-                        element.set_attribute(name, "").unwrap();
+                        namespace::set_attribute(&element, name, "").unwrap();
                     }
                 }
             };
         });
 
+        self.add_classes(&element);
+        self.set_styles(&element);
+
         let mut event_elem = events.create_element_node();
         self.add_events(
             &element,
@@ -51,13 +89,28 @@ impl VElement {
             event_elem.as_element().unwrap().events_id(),
         );
 
-        self.append_children_to_dom(
-            &element,
-            &document,
-            event_elem.as_element_mut().unwrap(),
-            events,
-        );
+        match self.special_attributes.portal() {
+            Some(target) => self.create_portal_children(
+                &element,
+                target,
+                event_elem.as_element_mut().unwrap(),
+                events,
+            ),
+            None => append_children_to_dom(
+                self.dom_children(),
+                &element,
+                namespace::children_namespace(&self.tag, element_namespace),
+                &document,
+                event_elem.as_element_mut().unwrap(),
+                events,
+            ),
+        };
+
+        // Properties are set after the children are appended, since some properties such as a
+        // `<select>`'s `value` depend on the children.
+        self.set_properties(&element);
 
+        self.special_attributes.maybe_set_node_ref(&element);
         self.special_attributes
             .maybe_call_on_create_element(&element);
 
@@ -70,58 +123,169 @@ impl VElement {
 }
 
 impl VElement {
-    fn append_children_to_dom(
+    /// Add the element's classes using `classList.add`.
+    pub(crate) fn add_classes(&self, element: &Element) {
+        let class_list = element.class_list();
+        for class in self.classes.iter() {
+            class_list.add_1(class).unwrap();
+        }
+    }
+
+    /// Set the element's styles using `style.setProperty`.
+    pub(crate) fn set_styles(&self, element: &Element) {
+        if self.styles.is_empty() {
+            return;
+        }
+
+        let style = element_style(element);
+        for (name, value) in self.styles.iter() {
+            let (value, priority) = Styles::split_priority(value);
+            style
+                .set_property_with_priority(name, value, priority)
+                .unwrap();
+        }
+    }
+
+    /// Set the element's DOM properties using `Reflect::set`.
+    pub(crate) fn set_properties(&self, element: &Element) {
+        for (name, value) in self.properties.iter() {
+            Reflect::set(element, &JsValue::from_str(name), &value.clone().into()).unwrap();
+        }
+    }
+}
+
+/// The element's inline style declaration, `element.style`.
+pub(crate) fn element_style(element: &Element) -> CssStyleDeclaration {
+    Reflect::get(element, &JsValue::from_str("style"))
+        .unwrap()
+        .unchecked_into()
+}
+
+impl VElement {
+    /// Create the container that a portal's children get placed into, along with the children.
+    ///
+    /// The container is an HTML element, so the children are created as HTML elements.
+    pub(crate) fn create_portal_children(
         &self,
-        element: &Element,
-        document: &Document,
+        portal: &Element,
+        target: &PortalTarget,
         event_node: &mut VirtualEventElement,
         events: &mut VirtualEvents,
     ) {
-        let mut previous_node_was_text = false;
-
-        self.children.iter().for_each(|child| {
-            let child_events_node = match child {
-                VirtualNode::Text(text_node) => {
-                    let current_node = element.as_ref() as &web_sys::Node;
-
-                    // We ensure that the text siblings are patched by preventing the browser from merging
-                    // neighboring text nodes. Originally inspired by some of React's work from 2016.
-                    //  -> https://reactjs.org/blog/2016/04/07/react-v15.html#major-changes
-                    //  -> https://github.com/facebook/react/pull/5753
-                    //
-                    // `ptns` = Percy text node separator
-                    if previous_node_was_text {
-                        let separator = document.create_comment("ptns");
-                        set_virtual_node_marker(&separator);
-                        current_node
-                            .append_child(separator.as_ref() as &web_sys::Node)
-                            .unwrap();
-                    }
+        let document = portal.owner_document().unwrap();
+        let container = create_portal_container(portal, target, events);
+
+        append_children_to_dom(
+            self.dom_children(),
+            &container,
+            None,
+            &document,
+            event_node,
+            events,
+        );
+    }
+}
+
+/// Build a DOM fragment by recursively creating DOM nodes for the fragment's children.
+///
+/// The fragment's events node is an element node that holds its children's events, since the
+/// fragment's children get placed into the DOM in place of the fragment.
+pub(crate) fn create_fragment_node(
+    fragment: &VirtualNode,
+    parent_namespace: Option<&'static str>,
+    events: &mut VirtualEvents,
+) -> (DocumentFragment, VirtualEventNode) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let dom_fragment = document.create_document_fragment();
+
+    let mut event_elem = events.create_element_node();
+    append_children_to_dom(
+        fragment.dom_nodes(),
+        &dom_fragment,
+        parent_namespace,
+        &document,
+        event_elem.as_element_mut().unwrap(),
+        events,
+    );
+
+    (dom_fragment, event_elem)
+}
+
+/// Create DOM nodes for the children and append them to the parent.
+fn append_children_to_dom(
+    children: Vec<&VirtualNode>,
+    parent: &Node,
+    parent_namespace: Option<&'static str>,
+    document: &Document,
+    event_node: &mut VirtualEventElement,
+    events: &mut VirtualEvents,
+) {
+    let mut previous_node_was_text = false;
 
+    children.into_iter().for_each(|child| {
+        let child = child.resolve_memos();
+
+        let child_events_node = match child {
+            VirtualNode::Text(text_node) => {
+                let current_node = parent;
+
+                // We ensure that the text siblings are patched by preventing the browser from merging
+                // neighboring text nodes. Originally inspired by some of React's work from 2016.
+                //  -> https://reactjs.org/blog/2016/04/07/react-v15.html#major-changes
+                //  -> https://github.com/facebook/react/pull/5753
+                //
+                // See `TEXT_SEPARATOR`.
+                //
+                // Separators are not given the virtual node marker. This is how we tell them
+                // apart from comment nodes that were created from a `VirtualNode::Comment`.
+                if previous_node_was_text {
+                    let separator = document.create_comment(TEXT_SEPARATOR);
                     current_node
-                        .append_child(&text_node.create_text_node())
+                        .append_child(separator.as_ref() as &web_sys::Node)
                         .unwrap();
+                }
 
-                    previous_node_was_text = true;
+                current_node
+                    .append_child(&text_node.create_text_node())
+                    .unwrap();
 
-                    events.create_text_node()
-                }
-                VirtualNode::Element(element_node) => {
-                    previous_node_was_text = false;
+                previous_node_was_text = true;
+
+                events.create_text_node()
+            }
+            VirtualNode::Comment(comment_node) => {
+                previous_node_was_text = false;
+
+                parent
+                    .append_child(&comment_node.create_comment_node())
+                    .unwrap();
 
-                    let (child, child_events) = element_node.create_element_node(events);
-                    let child_elem: Element = child;
+                events.create_text_node()
+            }
+            VirtualNode::Element(element_node) => {
+                previous_node_was_text = false;
 
-                    element.append_child(&child_elem).unwrap();
+                let (child, child_events) =
+                    element_node.create_element_node(parent_namespace, events);
+                let child_elem: Element = child;
 
-                    child_events
+                if element_node.is_declarative_shadow_root() {
+                    attach_declarative_shadow_root(parent, &child_elem);
                 }
-            };
 
-            let child_events_node = Rc::new(RefCell::new(child_events_node));
-            event_node.append_child(child_events_node.clone());
-        });
-    }
+                parent.append_child(&child_elem).unwrap();
+
+                child_events
+            }
+            VirtualNode::Fragment(_) => {
+                unreachable!("Fragments are flattened into their parent's children.")
+            }
+            VirtualNode::Memo(_) => unreachable!("Memos were resolved to their views."),
+        };
+
+        let child_events_node = Rc::new(RefCell::new(child_events_node));
+        event_node.append_child(child_events_node.clone());
+    });
 }
 
 /// Set a property on a node that can be used to know if a node was created by Percy.
@@ -7,7 +7,7 @@ use crate::event::ELEMENT_EVENTS_ID_PROP;
 use js_sys::Reflect;
 
 impl VElement {
-    pub(super) fn add_events(
+    pub(crate) fn add_events(
         &self,
         element: &Element,
         events: &VirtualEvents,
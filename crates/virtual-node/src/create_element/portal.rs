@@ -0,0 +1,61 @@
+//! Portals, which place an element's children into a container that gets appended to a different
+//! element, such as the end of the `<body>`.
+//!
+//! The portal element stays in its parent, empty, so that the parent's DOM children still line up
+//! with its virtual children. The portal element and its container point at each other so that
+//! the nodes inside of the container can be found when patching, and so that events within the
+//! container can bubble up to the portal element.
+
+use js_sys::Reflect;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+use crate::event::VirtualEvents;
+use crate::PortalTarget;
+
+// Used to find the container that a portal placed its children into.
+const PORTAL_CONTAINER_PROPERTY: &str = "__percy_portal_container__";
+// Used to find the portal that a container belongs to.
+const PORTAL_PROPERTY: &str = "__percy_portal__";
+
+/// Create the container that the `portal` element's children get placed into and append it to
+/// the `target`.
+pub(crate) fn create_portal_container(
+    portal: &Element,
+    target: &PortalTarget,
+    events: &mut VirtualEvents,
+) -> Element {
+    let document = portal.owner_document().unwrap();
+
+    let container = document.create_element("percy-portal").unwrap();
+    // The children are laid out as if they were the target's children.
+    container
+        .set_attribute("style", "display: contents")
+        .unwrap();
+
+    Reflect::set(portal, &PORTAL_CONTAINER_PROPERTY.into(), &container).unwrap();
+    Reflect::set(&container, &PORTAL_PROPERTY.into(), portal).unwrap();
+
+    target.element(&document).append_child(&container).unwrap();
+    events.add_portal_container(&container);
+
+    container
+}
+
+/// The container that a portal element placed its children into, if the node is a portal.
+#[doc(hidden)]
+pub fn portal_container(node: &Node) -> Option<Element> {
+    Reflect::get(node, &PORTAL_CONTAINER_PROPERTY.into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
+
+/// The portal element that a container belongs to, if the node is a portal container.
+#[doc(hidden)]
+pub fn portal_of_container(node: &Node) -> Option<Element> {
+    Reflect::get(node, &PORTAL_PROPERTY.into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
@@ -0,0 +1,63 @@
+//! Declarative shadow roots, such as `<template shadowrootmode="open"></template>`.
+//!
+//! When the browser parses a declarative shadow root it attaches a shadow root to the template's
+//! parent element, moves the template's contents into the shadow root and then removes the
+//! template.
+//!
+//! We do the same when creating DOM nodes, except that we keep the now empty `<template>` in its
+//! parent so that the parent's DOM children still line up with its virtual children. The template
+//! holds on to the shadow root so that the nodes inside of it can be found when patching.
+
+use js_sys::Reflect;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, Node, ShadowRoot, ShadowRootInit, ShadowRootMode};
+
+// Used to find the shadow root that a declarative shadow root's template placed its children into.
+const SHADOW_ROOT_PROPERTY: &str = "__percy_shadow_root__";
+
+/// Attach a shadow root to the `host` and move the `template`'s children into it.
+///
+/// Just like in the browser, nothing happens if the `shadowrootmode` is not "open" or "closed" or
+/// if the host already has a shadow root. The children stay inside of the template.
+pub(crate) fn attach_declarative_shadow_root(host: &Node, template: &Element) {
+    let host = match host.dyn_ref::<Element>() {
+        Some(host) => host,
+        None => return,
+    };
+
+    let mode = match template.get_attribute("shadowrootmode").as_deref() {
+        Some("open") => ShadowRootMode::Open,
+        Some("closed") => ShadowRootMode::Closed,
+        _ => return,
+    };
+    let init = ShadowRootInit::new(mode);
+    if template.has_attribute("shadowrootdelegatesfocus") {
+        Reflect::set(&init, &"delegatesFocus".into(), &JsValue::TRUE).unwrap();
+    }
+
+    let shadow_root = match host.attach_shadow(&init) {
+        Ok(shadow_root) => shadow_root,
+        Err(_) => return,
+    };
+
+    while let Some(child) = template.first_child() {
+        shadow_root.append_child(&child).unwrap();
+    }
+
+    set_template_shadow_root(template, &shadow_root);
+}
+
+/// Store the shadow root that the declarative shadow root's template placed its children into.
+pub(crate) fn set_template_shadow_root(template: &Element, shadow_root: &ShadowRoot) {
+    Reflect::set(template, &SHADOW_ROOT_PROPERTY.into(), shadow_root).unwrap();
+}
+
+/// The shadow root that a declarative shadow root's `<template>` placed its children into, if
+/// the node is one.
+#[doc(hidden)]
+pub fn template_shadow_root(node: &Node) -> Option<ShadowRoot> {
+    Reflect::get(node, &SHADOW_ROOT_PROPERTY.into())
+        .ok()?
+        .dyn_into()
+        .ok()
+}
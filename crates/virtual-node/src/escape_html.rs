@@ -0,0 +1,105 @@
+use std::fmt;
+
+/// Writes a string with the characters that have special meaning in HTML replaced by their
+/// character references.
+///
+/// We escape the same set of characters for text nodes and for (double quoted) attribute values
+/// so that user controlled strings can never break out of either.
+pub(crate) struct EscapeHtml<'a>(pub &'a str);
+
+impl fmt::Display for EscapeHtml<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = self.0;
+
+        let mut last_written = 0;
+
+        for (idx, byte) in text.bytes().enumerate() {
+            let escaped = match byte {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'"' => "&quot;",
+                b'\'' => "&#39;",
+                _ => continue,
+            };
+
+            f.write_str(&text[last_written..idx])?;
+            f.write_str(escaped)?;
+
+            last_written = idx + 1;
+        }
+
+        f.write_str(&text[last_written..])
+    }
+}
+
+/// Whether the browser parses the element's contents as raw text instead of as HTML, such as the
+/// contents of a `<script>`.
+pub(crate) fn is_raw_text_element(tag: &str) -> bool {
+    tag == "script" || tag == "style"
+}
+
+/// Writes the text within a raw text element, such as a `<script>` or `<style>`.
+///
+/// The browser does not decode character references within raw text, so the text gets written as
+/// is. The only thing that can end raw text is the element's closing tag, so we write `</script`
+/// as `<\/script`.
+pub(crate) struct RawText<'a> {
+    pub text: &'a str,
+    /// The raw text element's tag.
+    pub tag: &'a str,
+}
+
+impl fmt::Display for RawText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = self.text;
+        let closing_tag = format!("</{}", self.tag);
+
+        // Lowercasing ASCII characters keeps every byte index the same.
+        let lowercase = text.to_ascii_lowercase();
+
+        let mut last_written = 0;
+        for (idx, _) in lowercase.match_indices(&closing_tag) {
+            f.write_str(&text[last_written..=idx])?;
+            f.write_str("\\")?;
+
+            last_written = idx + 1;
+        }
+
+        f.write_str(&text[last_written..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that we escape every character that has special meaning in HTML.
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(
+            EscapeHtml(r#"<a href="x">Tom & Jerry's</a>"#).to_string(),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+    }
+
+    /// Verify that strings without special characters are written unchanged.
+    #[test]
+    fn leaves_other_characters_alone() {
+        assert_eq!(EscapeHtml("Hello, wörld").to_string(), "Hello, wörld");
+    }
+
+    /// Verify that raw text is written as is, except for anything that would close its element.
+    #[test]
+    fn raw_text_only_guards_closing_tag() {
+        let raw_text = RawText {
+            text: "if (a < b && c > d) { x = '</SCRIPT>'; }",
+            tag: "script",
+        };
+
+        assert_eq!(
+            raw_text.to_string(),
+            r"if (a < b && c > d) { x = '<\/SCRIPT>'; }"
+        );
+    }
+}
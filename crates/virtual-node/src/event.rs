@@ -1,27 +1,37 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt;
+#[cfg(target_arch = "wasm32")]
 use std::fmt::Formatter;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 
 pub use self::event_handlers::*;
-pub use self::event_name::EventName;
-pub use self::non_delegated_event_wrapper::insert_non_delegated_event;
+pub use self::event_name::{EventListenerTarget, EventName, EventOptions};
+#[cfg(target_arch = "wasm32")]
+pub use self::non_delegated_event_wrapper::{
+    insert_non_delegated_event, remove_non_delegated_event,
+};
+#[cfg(target_arch = "wasm32")]
 pub(crate) use self::virtual_events::set_events_id;
+#[cfg(target_arch = "wasm32")]
+pub use self::virtual_events::VirtualEvents;
 pub use self::virtual_events::{
-    ElementEventsId, VirtualEventElement, VirtualEventNode, VirtualEvents, ELEMENT_EVENTS_ID_PROP,
+    ElementEventsId, VirtualEventElement, VirtualEventNode, ELEMENT_EVENTS_ID_PROP,
 };
 
 mod event_handlers;
 mod event_name;
+#[cfg(target_arch = "wasm32")]
 mod non_delegated_event_wrapper;
 mod virtual_events;
 
+#[cfg(target_arch = "wasm32")]
 type EventAttribFnInner = std::rc::Rc<dyn AsRef<wasm_bindgen::JsValue>>;
 
 /// Box<dyn AsRef<JsValue>>> is our js_sys::Closure. Stored this way to allow us to store
 /// any Closure regardless of the types of its arguments.
+#[cfg(target_arch = "wasm32")]
 #[derive(Clone)]
 pub struct EventAttribFn(pub EventAttribFnInner);
 
@@ -49,6 +59,7 @@ impl Events {
 
     // Used by the html! macro
     #[doc(hidden)]
+    #[cfg(target_arch = "wasm32")]
     pub fn __insert_unsupported_signature(
         &mut self,
         event_name: EventName,
@@ -57,16 +68,6 @@ impl Events {
         self.events
             .insert(event_name, EventHandler::UnsupportedSignature(event.into()));
     }
-
-    /// Insert a mouse event handler.
-    pub fn insert_mouse_event(
-        &mut self,
-        event_name: EventName,
-        event: Rc<RefCell<dyn FnMut(MouseEvent)>>,
-    ) {
-        self.events
-            .insert(event_name, EventHandler::MouseEvent(event));
-    }
 }
 
 impl Events {
@@ -82,6 +83,7 @@ impl Events {
 // Those PartialEq implementations are used for testing.
 // Maybe we can put some of the event related PartialEq implementations
 // behind a #[cfg(any(test, feature = "__test-utils"))].
+#[cfg(target_arch = "wasm32")]
 impl PartialEq for EventAttribFn {
     fn eq(&self, _other: &Self) -> bool {
         true
@@ -100,18 +102,21 @@ impl fmt::Debug for Events {
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 impl fmt::Debug for EventAttribFn {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "event_handler()")
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 impl From<EventAttribFnInner> for EventAttribFn {
     fn from(inner: EventAttribFnInner) -> Self {
         EventAttribFn(inner)
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 impl Deref for EventAttribFn {
     type Target = EventAttribFnInner;
 
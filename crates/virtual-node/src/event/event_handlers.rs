@@ -1,63 +1,302 @@
+#[cfg(target_arch = "wasm32")]
 use crate::EventAttribFn;
-use std::cell::{Cell, RefCell};
+use std::cell::Cell;
+use std::cell::RefCell;
 use std::fmt::{Debug, Formatter};
+#[cfg(target_arch = "wasm32")]
 use std::ops::Deref;
 use std::rc::Rc;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::event::{EventName, Events};
 
 /// Event handlers such as the closure in `onclick = |event| {}`.
 ///
 /// ## Cloning
 ///
 /// Can be cheaply cloned since since inner types are reference counted.
+///
+/// ## Non wasm32 targets
+///
+/// [`EventHandler::UnsupportedSignature`] is not available on non wasm32 targets, since it holds
+/// a browser type.
 #[derive(Clone)]
 pub enum EventHandler {
     /// A callback that does not contain any arguments.
     NoArgs(Rc<RefCell<dyn FnMut()>>),
-    /// Handle mouse events such as `onclick` and `oninput`
+    /// Handle mouse events such as `onclick` and `onmousemove`.
     MouseEvent(Rc<RefCell<dyn FnMut(MouseEvent)>>),
+    /// Handle keyboard events such as `onkeydown` and `onkeyup`.
+    KeyboardEvent(Rc<RefCell<dyn FnMut(KeyboardEvent)>>),
+    /// Handle input events such as `oninput` and `onbeforeinput`.
+    InputEvent(Rc<RefCell<dyn FnMut(InputEvent)>>),
+    /// Handle focus events such as `onfocus` and `onblur`.
+    FocusEvent(Rc<RefCell<dyn FnMut(FocusEvent)>>),
+    /// Handle pointer events such as `onpointerdown` and `onpointermove`.
+    PointerEvent(Rc<RefCell<dyn FnMut(PointerEvent)>>),
+    /// Handle drag and drop events such as `ondragstart` and `ondrop`.
+    DragEvent(Rc<RefCell<dyn FnMut(DragEvent)>>),
+    /// Handle `onwheel` events.
+    WheelEvent(Rc<RefCell<dyn FnMut(WheelEvent)>>),
+    /// Handle `onsubmit` events.
+    SubmitEvent(Rc<RefCell<dyn FnMut(SubmitEvent)>>),
     /// EventHandler's that we do not have a dedicated type for.
     /// This is useful for custom events.
+    #[cfg(target_arch = "wasm32")]
     UnsupportedSignature(EventAttribFn),
 }
 
-/// A mouse event.
-///
-/// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent)
-#[derive(Clone)]
-pub struct MouseEvent {
-    event: web_sys::MouseEvent,
-    should_propagate: Rc<Cell<bool>>,
+#[cfg(target_arch = "wasm32")]
+impl EventHandler {
+    /// Call the handler with a browser event, wrapping the event in the type that the handler
+    /// expects.
+    ///
+    /// Handlers that are called for the same browser event should share `should_propagate`, so
+    /// that any of them can stop the event from bubbling.
+    pub fn call(&self, event: web_sys::Event, should_propagate: &Rc<Cell<bool>>) {
+        let should_propagate = should_propagate.clone();
+
+        // We use `unchecked_into` since an event can be dispatched with a more general type than
+        // usual, such as `new Event("input")`. The typed handler can still use the event's
+        // `web_sys::Event` methods.
+        match self {
+            EventHandler::NoArgs(no_args) => (no_args.borrow_mut())(),
+            EventHandler::MouseEvent(mouse) => (mouse.borrow_mut())(
+                MouseEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::KeyboardEvent(keyboard) => (keyboard.borrow_mut())(
+                KeyboardEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::InputEvent(input) => (input.borrow_mut())(
+                InputEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::FocusEvent(focus) => (focus.borrow_mut())(
+                FocusEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::PointerEvent(pointer) => (pointer.borrow_mut())(
+                PointerEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::DragEvent(drag) => (drag.borrow_mut())(DragEvent::new_with_propagation(
+                event.unchecked_into(),
+                should_propagate,
+            )),
+            EventHandler::WheelEvent(wheel) => (wheel.borrow_mut())(
+                WheelEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::SubmitEvent(submit) => (submit.borrow_mut())(
+                SubmitEvent::new_with_propagation(event.unchecked_into(), should_propagate),
+            ),
+            EventHandler::UnsupportedSignature(cb) => {
+                let cb: &js_sys::Function = cb.0.as_ref().as_ref().unchecked_ref();
+
+                let context = JsValue::NULL;
+                cb.call1(&context, &event).unwrap();
+            }
+        };
+    }
 }
 
-impl MouseEvent {
-    /// Create a new MouseEvent.
-    pub fn new(event: web_sys::MouseEvent) -> Self {
-        MouseEvent {
-            event,
-            should_propagate: Rc::new(Cell::new(true)),
+/// Define an event type that wraps a `web_sys` event of the same name and keeps track of whether
+/// or not the event should propagate, so that our event delegation knows when to stop bubbling.
+///
+/// Also defines the `Events` methods that insert handlers for the event.
+macro_rules! event_wrapper {
+    ($(#[$docs:meta])* $name:ident, $insert:ident, $insert_with_conversion:ident) => {
+        $(#[$docs])*
+        ///
+        /// On non wasm32 targets there is no browser event to wrap, so the event can only be
+        /// created using
+        #[doc = concat!("[`", stringify!($name), "::synthetic`].")]
+        #[derive(Clone)]
+        pub struct $name {
+            #[cfg(target_arch = "wasm32")]
+            event: web_sys::$name,
+            should_propagate: Rc<Cell<bool>>,
         }
-    }
 
-    /// Prevent the event from propagating.
-    pub fn stop_propagation(&self) {
-        self.should_propagate.set(false);
-        self.event.stop_propagation();
-    }
+        impl $name {
+            #[doc = concat!("Create a new ", stringify!($name), ".")]
+            #[cfg(target_arch = "wasm32")]
+            pub fn new(event: web_sys::$name) -> Self {
+                Self::new_with_propagation(event, Rc::new(Cell::new(true)))
+            }
 
-    /// Whether or not the event should propagate.
-    pub fn should_propagate(&self) -> &Rc<Cell<bool>> {
-        &self.should_propagate
-    }
-}
+            /// Create an event that shares its propagation with the other events that were
+            /// created for the same browser event, so that every handler along the event's path
+            /// can stop it from bubbling.
+            #[cfg(target_arch = "wasm32")]
+            pub(crate) fn new_with_propagation(
+                event: web_sys::$name,
+                should_propagate: Rc<Cell<bool>>,
+            ) -> Self {
+                $name {
+                    event,
+                    should_propagate,
+                }
+            }
 
-impl Deref for MouseEvent {
-    type Target = web_sys::MouseEvent;
+            /// Create an event that was not triggered by a user, such as when dispatching an
+            /// event in a unit test using `VirtualNode::dispatch_event`.
+            ///
+            #[doc = concat!("On wasm32 targets this wraps a new `web_sys::", stringify!($name), "` of the given type.")]
+            pub fn synthetic(event_name: &EventName) -> Self {
+                Self::synthetic_with_propagation(event_name, Rc::new(Cell::new(true)))
+            }
 
-    fn deref(&self) -> &Self::Target {
-        &self.event
-    }
+            /// Create a synthetic event that shares its propagation with other synthetic events,
+            /// so that every handler along a dispatched event's path can stop it from bubbling.
+            #[cfg_attr(not(target_arch = "wasm32"), allow(unused_variables))]
+            pub(crate) fn synthetic_with_propagation(
+                event_name: &EventName,
+                should_propagate: Rc<Cell<bool>>,
+            ) -> Self {
+                $name {
+                    #[cfg(target_arch = "wasm32")]
+                    event: web_sys::$name::new(event_name.without_on_prefix()).unwrap(),
+                    should_propagate,
+                }
+            }
+
+            /// Prevent the event from propagating.
+            pub fn stop_propagation(&self) {
+                self.should_propagate.set(false);
+                #[cfg(target_arch = "wasm32")]
+                self.event.stop_propagation();
+            }
+
+            /// Whether or not the event should propagate.
+            pub fn should_propagate(&self) -> &Rc<Cell<bool>> {
+                &self.should_propagate
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        impl Deref for $name {
+            type Target = web_sys::$name;
+
+            fn deref(&self) -> &Self::Target {
+                &self.event
+            }
+        }
+
+        /// Lets `html!` event handlers take the `web_sys` event instead of our wrapper, such as
+        /// `onkeydown=|event: web_sys::KeyboardEvent| {}`.
+        #[cfg(target_arch = "wasm32")]
+        impl From<$name> for web_sys::$name {
+            fn from(event: $name) -> Self {
+                event.event
+            }
+        }
+
+        /// Lets handlers that were written before this event was typed keep taking a
+        /// `web_sys::Event`.
+        #[cfg(target_arch = "wasm32")]
+        impl From<$name> for web_sys::Event {
+            fn from(event: $name) -> Self {
+                event.event.into()
+            }
+        }
+
+        impl Events {
+            #[doc = concat!("Insert a ", stringify!($name), " handler.")]
+            pub fn $insert(
+                &mut self,
+                event_name: EventName,
+                event: Rc<RefCell<dyn FnMut($name)>>,
+            ) {
+                self.events.insert(event_name, EventHandler::$name(event));
+            }
+
+            // Used by the html! macro, so that a handler can take either our event type or the
+            // `web_sys` event that it wraps.
+            #[doc(hidden)]
+            pub fn $insert_with_conversion<T: From<$name> + 'static>(
+                &mut self,
+                event_name: EventName,
+                mut event: impl FnMut(T) + 'static,
+            ) {
+                self.$insert(
+                    event_name,
+                    Rc::new(RefCell::new(move |e: $name| event(e.into()))),
+                );
+            }
+        }
+    };
 }
 
+event_wrapper!(
+    /// A mouse event, such as `onclick` or `onmousemove`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/MouseEvent)
+    MouseEvent,
+    insert_mouse_event,
+    __insert_mouse_event
+);
+
+event_wrapper!(
+    /// A keyboard event, such as `onkeydown` or `onkeyup`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent)
+    KeyboardEvent,
+    insert_keyboard_event,
+    __insert_keyboard_event
+);
+
+event_wrapper!(
+    /// An input event, such as `oninput` or `onbeforeinput`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/InputEvent)
+    InputEvent,
+    insert_input_event,
+    __insert_input_event
+);
+
+event_wrapper!(
+    /// A focus event, such as `onfocus` or `onblur`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/FocusEvent)
+    FocusEvent,
+    insert_focus_event,
+    __insert_focus_event
+);
+
+event_wrapper!(
+    /// A pointer event, such as `onpointerdown` or `onpointermove`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/PointerEvent)
+    PointerEvent,
+    insert_pointer_event,
+    __insert_pointer_event
+);
+
+event_wrapper!(
+    /// A drag and drop event, such as `ondragstart` or `ondrop`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/DragEvent)
+    DragEvent,
+    insert_drag_event,
+    __insert_drag_event
+);
+
+event_wrapper!(
+    /// A wheel event, `onwheel`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/WheelEvent)
+    WheelEvent,
+    insert_wheel_event,
+    __insert_wheel_event
+);
+
+event_wrapper!(
+    /// A form submit event, `onsubmit`.
+    ///
+    /// [MDN Documentation](https://developer.mozilla.org/en-US/docs/Web/API/SubmitEvent)
+    SubmitEvent,
+    insert_submit_event,
+    __insert_submit_event
+);
+
 // Allows us to easily derive PartialEq for some of the types that contain events.
 // Those PartialEq implementations are used for testing.
 // Maybe we can put some of the event related PartialEq implementations
@@ -1,60 +1,374 @@
 use std::borrow::Cow;
 
-/// The name of the event with the `on` prefix.
+/// The name of the event with the `on` prefix, along with the options for its listener.
 ///
 /// onclick, oninput, onmousemove ... etc
+///
+/// The options can be written as modifiers after the name, such as `onsubmit.prevent` or
+/// `onclick.capture.once`.
+///
+/// A `window:` or `document:` prefix, such as in `window:onresize`, listens for the event on the
+/// window or the document instead of on the element.
 #[derive(Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
-pub struct EventName(Cow<'static, str>);
+pub struct EventName {
+    name: Cow<'static, str>,
+    options: EventOptions,
+    target: EventListenerTarget,
+}
+
+/// Where an event's listener gets added.
+#[derive(Debug, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub enum EventListenerTarget {
+    /// The element that the event handler is on, such as with `onclick`.
+    Element,
+    /// The window, such as with `window:onresize`.
+    ///
+    /// The listener is removed when the element that the event handler is on is removed.
+    Window,
+    /// The document, such as with `document:onkeydown`.
+    ///
+    /// The listener is removed when the element that the event handler is on is removed.
+    Document,
+}
+
+/// Options for an event's listener, set using modifiers such as the `.prevent` in
+/// `onsubmit.prevent`.
+#[derive(Debug, Default, Clone, Copy, Hash, Ord, PartialOrd, Eq, PartialEq)]
+pub struct EventOptions {
+    /// `.prevent` - Call `event.preventDefault()` before calling the handler.
+    pub prevent_default: bool,
+    /// `.passive` - The handler will not call `event.preventDefault()`, so the browser does not
+    /// need to wait for it before scrolling.
+    pub passive: bool,
+    /// `.capture` - Call the handler while the event travels down to its target, before any
+    /// handlers that are called while the event bubbles up.
+    pub capture: bool,
+    /// `.once` - Only call the handler the first time that the event happens.
+    pub once: bool,
+}
+
+impl EventOptions {
+    const NONE: EventOptions = EventOptions {
+        prevent_default: false,
+        passive: false,
+        capture: false,
+        once: false,
+    };
+
+    /// Set the option for a modifier, returning false if the modifier is unknown.
+    fn set_modifier(&mut self, modifier: &str) -> bool {
+        match modifier {
+            "prevent" => self.prevent_default = true,
+            "passive" => self.passive = true,
+            "capture" => self.capture = true,
+            "once" => self.once = true,
+            _ => return false,
+        };
+
+        true
+    }
+}
 
 impl EventName {
     /// Create a new `EventName`.
     ///
+    /// Modifiers at the end of the name, such as the ".prevent" in "onsubmit.prevent", set the
+    /// event's options.
+    ///
+    /// A "window:" or "document:" prefix sets the event's listener target.
+    ///
     /// # Panics
     ///
     /// Panics if the event does not start with "on". Such as "onclick".
     pub fn new(name: Cow<'static, str>) -> Self {
-        EventName(name)
+        let (target, name_start) = if name.starts_with("window:") {
+            (EventListenerTarget::Window, "window:".len())
+        } else if name.starts_with("document:") {
+            (EventListenerTarget::Document, "document:".len())
+        } else {
+            (EventListenerTarget::Element, 0)
+        };
+
+        let mut options = EventOptions::default();
+
+        let mut name_len = name.len();
+        while let Some(dot) = name[..name_len].rfind('.') {
+            if !options.set_modifier(&name[dot + 1..name_len]) {
+                break;
+            }
+            name_len = dot;
+        }
+
+        let name = match name {
+            Cow::Borrowed(name) => Cow::Borrowed(&name[name_start..name_len]),
+            Cow::Owned(name) => Cow::Owned(name[name_start..name_len].to_string()),
+        };
+
+        EventName {
+            name,
+            options,
+            target,
+        }
     }
 
     /// `onclick`, `onmousemove` etc
     pub fn with_on_prefix(&self) -> &str {
-        &self.0.as_ref()
+        self.name.as_ref()
     }
 
     /// `click`, `mousemove` etc
     pub fn without_on_prefix(&self) -> &str {
-        &self.0.as_ref()[2..]
+        &self.name.as_ref()[2..]
+    }
+
+    /// The options for the event's listener.
+    pub fn options(&self) -> EventOptions {
+        self.options
+    }
+
+    /// Use different options for the event's listener.
+    pub fn with_options(mut self, options: EventOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Where the event's listener gets added.
+    pub fn listener_target(&self) -> EventListenerTarget {
+        self.target
     }
 }
 
 impl EventName {
     /// Whether or not this event gets handled by our event delegation system.
     /// If not, the event will be attached to the DOM element.
+    ///
+    /// Events whose listener is capturing, passive or only called once are attached to the DOM
+    /// element, since these are options for the browser's listener.
+    ///
+    /// Window and document events are never delegated.
     pub fn is_delegated(&self) -> bool {
-        if self == &Self::ONCLICK {
-            true
-        } else {
-            false
-        }
+        let options = self.options;
+
+        self.target == EventListenerTarget::Element
+            && DELEGATED_EVENTS.contains(&self.with_on_prefix())
+            && !options.capture
+            && !options.passive
+            && !options.once
+    }
+
+    /// All of the events that get handled by our event delegation system.
+    pub fn delegated_events() -> impl Iterator<Item = EventName> {
+        DELEGATED_EVENTS.iter().map(|event| EventName::from(*event))
+    }
+
+    /// Whether or not the browser bubbles the event up through the ancestors of its target.
+    ///
+    /// Events that we don't know about, such as custom events, are assumed to bubble.
+    pub fn bubbles(&self) -> bool {
+        !NON_BUBBLING_EVENTS.contains(&self.with_on_prefix())
     }
 }
 
+/// Events that only get dispatched to their target, without bubbling up through its ancestors.
+const NON_BUBBLING_EVENTS: &[&str] = &[
+    "onmouseenter",
+    "onmouseleave",
+    "onpointerenter",
+    "onpointerleave",
+    "onfocus",
+    "onblur",
+    "onload",
+    "onunload",
+    "onerror",
+    "onabort",
+    "onscroll",
+    "onscrollend",
+    "onresize",
+    "oninvalid",
+    "ontoggle",
+    "onplay",
+    "onpause",
+    "onended",
+];
+
+/// Events that bubble, so that a single listener on the root node can hear them.
+///
+/// `onfocus` and `onblur` do not bubble, so our event delegation listens for `focusin` and
+/// `focusout` in their place.
+///
+/// We do not delegate wheel and touch events. A listener for these on the root node would make the
+/// browser wait for it before scrolling anywhere in the application.
+const DELEGATED_EVENTS: &[&str] = &[
+    // Mouse
+    "onclick",
+    "ondblclick",
+    "onauxclick",
+    "oncontextmenu",
+    "onmousedown",
+    "onmouseup",
+    "onmousemove",
+    "onmouseover",
+    "onmouseout",
+    // Keyboard
+    "onkeydown",
+    "onkeyup",
+    "onkeypress",
+    // Input and forms
+    "oninput",
+    "onbeforeinput",
+    "onchange",
+    "onselect",
+    "onsubmit",
+    "onreset",
+    // Focus
+    "onfocus",
+    "onblur",
+    "onfocusin",
+    "onfocusout",
+    // Pointer
+    "onpointerdown",
+    "onpointerup",
+    "onpointermove",
+    "onpointerover",
+    "onpointerout",
+    "onpointercancel",
+    "ongotpointercapture",
+    "onlostpointercapture",
+    // Drag and drop
+    "ondrag",
+    "ondragstart",
+    "ondragend",
+    "ondragenter",
+    "ondragleave",
+    "ondragover",
+    "ondrop",
+    // Clipboard
+    "oncopy",
+    "oncut",
+    "onpaste",
+    // Composition
+    "oncompositionstart",
+    "oncompositionupdate",
+    "oncompositionend",
+];
+
 impl EventName {
     /// The "onclick" event
-    pub const ONCLICK: EventName = EventName(Cow::Borrowed("onclick"));
+    pub const ONCLICK: EventName = EventName {
+        name: Cow::Borrowed("onclick"),
+        options: EventOptions::NONE,
+        target: EventListenerTarget::Element,
+    };
 
     /// The "oninput" event
-    pub const ONINPUT: EventName = EventName(Cow::Borrowed("oninput"));
+    pub const ONINPUT: EventName = EventName {
+        name: Cow::Borrowed("oninput"),
+        options: EventOptions::NONE,
+        target: EventListenerTarget::Element,
+    };
 }
 
 impl From<&'static str> for EventName {
     fn from(event_name: &'static str) -> Self {
-        EventName(Cow::Borrowed(event_name))
+        EventName::new(Cow::Borrowed(event_name))
     }
 }
 
 impl From<String> for EventName {
     fn from(event_name: String) -> Self {
-        EventName(Cow::Owned(event_name))
+        EventName::new(Cow::Owned(event_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that events that bubble are delegated, and events that do not bubble are not.
+    #[test]
+    fn delegates_bubbling_events() {
+        for event in [
+            "onclick",
+            "oninput",
+            "onkeydown",
+            "onchange",
+            "onfocus",
+            "onfocusout",
+        ] {
+            assert!(EventName::from(event).is_delegated(), "{}", event);
+        }
+
+        for event in [
+            "onmouseenter",
+            "onscroll",
+            "onwheel",
+            "ontouchstart",
+            "onmy-event",
+        ] {
+            assert!(!EventName::from(event).is_delegated(), "{}", event);
+        }
+    }
+
+    /// Verify that we know which events bubble.
+    #[test]
+    fn bubbling_events() {
+        for event in ["onclick", "oninput", "onfocusin", "onmy-event"] {
+            assert!(EventName::from(event).bubbles(), "{}", event);
+        }
+
+        for event in ["onmouseenter", "onfocus", "onblur", "onscroll"] {
+            assert!(!EventName::from(event).bubbles(), "{}", event);
+        }
+    }
+
+    /// Verify that we parse the modifiers at the end of an event's name into its options.
+    #[test]
+    fn parses_modifiers() {
+        let event = EventName::from("onclick.capture.once");
+        assert_eq!(event.with_on_prefix(), "onclick");
+        assert_eq!(
+            event.options(),
+            EventOptions {
+                capture: true,
+                once: true,
+                ..EventOptions::default()
+            }
+        );
+
+        let event = EventName::from("onsubmit.prevent".to_string());
+        assert_eq!(event.without_on_prefix(), "submit");
+        assert!(event.options().prevent_default);
+
+        // Unknown modifiers are part of the name, such as the dot in a custom event's name.
+        let event = EventName::from("onmy.event.passive");
+        assert_eq!(event.with_on_prefix(), "onmy.event");
+        assert!(event.options().passive);
+    }
+
+    /// Verify that events with options for the browser's listener are not delegated, while
+    /// `.prevent` events still are.
+    #[test]
+    fn listener_options_are_not_delegated() {
+        assert!(EventName::from("onsubmit.prevent").is_delegated());
+
+        for event in ["onclick.capture", "onclick.once", "onpointermove.passive"] {
+            assert!(!EventName::from(event).is_delegated(), "{}", event);
+        }
+    }
+
+    /// Verify that we parse a `window:` or `document:` prefix into the event's listener target.
+    #[test]
+    fn parses_listener_target() {
+        let event = EventName::from("window:onresize");
+        assert_eq!(event.with_on_prefix(), "onresize");
+        assert_eq!(event.listener_target(), EventListenerTarget::Window);
+        assert!(!event.is_delegated());
+
+        let event = EventName::from("document:onkeydown.once".to_string());
+        assert_eq!(event.without_on_prefix(), "keydown");
+        assert_eq!(event.listener_target(), EventListenerTarget::Document);
+        assert!(event.options().once);
+
+        assert_ne!(EventName::from("document:onclick"), EventName::ONCLICK);
     }
 }
@@ -1,9 +1,12 @@
-use crate::event::virtual_events::ElementEventsId;
-use crate::event::{EventHandler, EventName, MouseEvent, VirtualEvents, ELEMENT_EVENTS_ID_PROP};
+use crate::event::virtual_events::{ElementEventsId, EventWrapper};
+use crate::event::{
+    EventHandler, EventListenerTarget, EventName, VirtualEvents, ELEMENT_EVENTS_ID_PROP,
+};
 use js_sys::Reflect;
+use std::cell::Cell;
 use std::rc::Rc;
 use wasm_bindgen::closure::Closure;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::JsCast;
 
 /// Insert a non-delegated event
 pub fn insert_non_delegated_event(
@@ -16,13 +19,14 @@ pub fn insert_non_delegated_event(
     let events_clone = events.clone();
 
     let event_name = onevent.without_on_prefix();
+    let options = onevent.options();
 
-    let on_event = onevent.with_on_prefix().to_string();
-    let on_event_clone = on_event.clone();
+    let onevent_clone = onevent.clone();
+    // The element's events ID can change after it is created, so we look it up when the event
+    // happens. Window and document events happen on a different target than the element.
+    let this_elem = element.clone();
 
     let callback_wrapper = move |event: web_sys::Event| {
-        let this_elem = event.current_target().unwrap();
-
         let events_id = Reflect::get(&this_elem, &ELEMENT_EVENTS_ID_PROP.into()).unwrap();
         let events_id = events_id.as_string();
         let events_id = events_id.unwrap();
@@ -31,33 +35,30 @@ pub fn insert_non_delegated_event(
             events_id.trim_start_matches(&events_clone.events_id_props_prefix().to_string());
         let events_id: u32 = events_id.parse().unwrap();
 
-        let event_name = EventName::new(on_event_clone.clone().into());
         let cb = events_clone
-            .get_event_handler(&ElementEventsId::new(events_id), &event_name)
+            .get_event_handler(&ElementEventsId::new(events_id), &onevent_clone)
             .unwrap();
 
-        match cb {
-            EventHandler::NoArgs(no_args) => (no_args.borrow_mut())(),
-            EventHandler::MouseEvent(mouse) => {
-                (mouse.borrow_mut())(MouseEvent::new(event.dyn_into().unwrap()));
-            }
-            EventHandler::UnsupportedSignature(cb) => {
-                let cb: &js_sys::Function = cb.as_ref().as_ref().unchecked_ref();
-
-                let context = JsValue::NULL;
-                cb.call1(&context, &event).unwrap();
-            }
-        };
+        if options.prevent_default {
+            event.prevent_default();
+        }
+
+        cb.call(event, &Rc::new(Cell::new(true)));
     };
 
     let callback_wrapper = Box::new(callback_wrapper) as Box<dyn FnMut(_) -> ()>;
     let callback_wrapper = Closure::wrap(callback_wrapper);
 
-    let current_elem: &web_sys::EventTarget = element.dyn_ref().unwrap();
-    current_elem
-        .add_event_listener_with_callback(
+    let listener_options = web_sys::AddEventListenerOptions::new();
+    listener_options.set_capture(options.capture);
+    listener_options.set_passive(options.passive);
+    listener_options.set_once(options.once);
+
+    listener_target(element, onevent)
+        .add_event_listener_with_callback_and_add_event_listener_options(
             event_name,
             callback_wrapper.as_ref().as_ref().unchecked_ref(),
+            &listener_options,
         )
         .unwrap();
 
@@ -68,3 +69,48 @@ pub fn insert_non_delegated_event(
         Some(Rc::new(callback_wrapper)),
     );
 }
+
+/// Remove a non-delegated event, including its event listener.
+pub fn remove_non_delegated_event(
+    element: &web_sys::Element,
+    onevent: &EventName,
+    events_id: &ElementEventsId,
+    events: &mut VirtualEvents,
+) {
+    let wrapper = events.remove_non_delegated_event_wrapper(events_id, onevent);
+    remove_event_listener(&listener_target(element, onevent), onevent, &wrapper);
+
+    events.remove_event_handler(events_id, onevent);
+}
+
+/// The target that an event's listener gets added to, such as the window for `window:onresize`.
+fn listener_target(element: &web_sys::Element, onevent: &EventName) -> web_sys::EventTarget {
+    global_listener_target(onevent).unwrap_or_else(|| element.clone().into())
+}
+
+/// The window or document that a `window:` or `document:` event's listener gets added to.
+pub(crate) fn global_listener_target(onevent: &EventName) -> Option<web_sys::EventTarget> {
+    let window = web_sys::window().unwrap();
+
+    match onevent.listener_target() {
+        EventListenerTarget::Element => None,
+        EventListenerTarget::Window => Some(window.into()),
+        EventListenerTarget::Document => Some(window.document().unwrap().into()),
+    }
+}
+
+/// Remove an event's listener from its target.
+pub(crate) fn remove_event_listener(
+    target: &web_sys::EventTarget,
+    onevent: &EventName,
+    wrapper: &EventWrapper,
+) {
+    // A capturing listener can only be removed by saying that it is capturing.
+    target
+        .remove_event_listener_with_callback_and_bool(
+            onevent.without_on_prefix(),
+            wrapper.as_ref().as_ref().unchecked_ref(),
+            onevent.options().capture,
+        )
+        .unwrap();
+}
@@ -1,9 +1,18 @@
+#[cfg(target_arch = "wasm32")]
 use crate::event::event_name::EventName;
+#[cfg(target_arch = "wasm32")]
+use crate::event::non_delegated_event_wrapper::{global_listener_target, remove_event_listener};
+#[cfg(target_arch = "wasm32")]
 use crate::event::EventHandler;
+#[cfg(target_arch = "wasm32")]
 use js_sys::Reflect;
-use std::cell::{Ref, RefCell, RefMut};
+use std::cell::RefCell;
+#[cfg(target_arch = "wasm32")]
+use std::cell::{Ref, RefMut};
+#[cfg(target_arch = "wasm32")]
 use std::collections::HashMap;
 use std::rc::Rc;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
 // Every real DOM element that we create gets a property set on it that can be used to look up
@@ -31,6 +40,7 @@ impl ElementEventsId {
 //  removes_old_non_delegated_event_listeners test.
 // A future optimization could be using a feature flag to determine whether to Rc or Box this.
 // i.e. #[cfg(feature = "__test-utils")]
+#[cfg(target_arch = "wasm32")]
 pub(crate) type EventWrapper = Rc<dyn AsRef<JsValue>>;
 
 /// When we create a DOM node, we store all of it's closures and all of it's children's closures
@@ -49,6 +59,7 @@ pub(crate) type EventWrapper = Rc<dyn AsRef<JsValue>>;
 /// ## Cloning
 ///
 /// VirtualEvents can be cloned cheaply. Clones share the same inner data.
+#[cfg(target_arch = "wasm32")]
 #[derive(Clone)]
 pub struct VirtualEvents {
     inner: Rc<RefCell<VirtualEventsInner>>,
@@ -56,6 +67,7 @@ pub struct VirtualEvents {
     events_id_props_prefix: f64,
 }
 
+#[cfg(target_arch = "wasm32")]
 struct VirtualEventsInner {
     root: Rc<RefCell<VirtualEventNode>>,
     events: HashMap<ElementEventsId, Rc<RefCell<HashMap<EventName, EventHandler>>>>,
@@ -66,6 +78,13 @@ struct VirtualEventsInner {
     /// This setup allows us to replace the `EventHandler` after every render without needing
     /// to re-attach event listeners.
     non_delegated_event_wrappers: HashMap<ElementEventsId, HashMap<EventName, EventWrapper>>,
+    /// The elements that portals placed their children into.
+    ///
+    /// Events within a portal container do not bubble up to the root node, so the delegated
+    /// event listeners also get attached to every portal container.
+    portal_containers: Vec<web_sys::Element>,
+    /// The event types and listeners that handle event delegation.
+    delegated_listeners: Vec<(String, js_sys::Function)>,
     next_events_id: u32,
 }
 
@@ -78,6 +97,8 @@ pub struct VirtualEventNode {
     next_sibling: Option<Rc<RefCell<VirtualEventNode>>>,
 }
 
+// Event nodes are only created by `VirtualEvents`, which only exists on wasm32 targets.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 #[derive(Debug)]
 enum VirtualEventNodeVariant {
     Element(VirtualEventElement),
@@ -97,6 +118,7 @@ struct VirtualEventElementChildren {
     last_child: Rc<RefCell<VirtualEventNode>>,
 }
 
+#[cfg(target_arch = "wasm32")]
 impl VirtualEvents {
     /// Create a new EventsByNodeIdx.
     pub fn new() -> Self {
@@ -223,10 +245,72 @@ impl VirtualEvents {
     }
 
     /// Remove all event handlers for a node.
+    ///
+    /// Window and document listeners get removed from the window or document, since unlike an
+    /// element's listeners they would otherwise outlive the element.
     pub fn remove_node(&self, events_id: &ElementEventsId) {
         let mut borrow = self.borrow_mut();
         borrow.events.remove(events_id);
-        borrow.non_delegated_event_wrappers.remove(events_id);
+
+        let wrappers = borrow.non_delegated_event_wrappers.remove(events_id);
+        for (event_name, wrapper) in wrappers.into_iter().flatten() {
+            if let Some(target) = global_listener_target(&event_name) {
+                remove_event_listener(&target, &event_name, &wrapper);
+            }
+        }
+    }
+
+    /// Attach an event listener that handles event delegation to every portal container, including
+    /// the ones that get created later.
+    pub fn add_delegated_listener(&self, event_type: &str, listener: &js_sys::Function) {
+        let mut borrow = self.borrow_mut();
+
+        for container in borrow.portal_containers.iter() {
+            container
+                .add_event_listener_with_callback(event_type, listener)
+                .unwrap();
+        }
+
+        borrow
+            .delegated_listeners
+            .push((event_type.to_string(), listener.clone()));
+    }
+
+    /// Remove the event listeners that handle event delegation from every portal container.
+    pub fn remove_delegated_listeners(&self) {
+        let mut borrow = self.borrow_mut();
+        let borrow = &mut *borrow;
+
+        for container in borrow.portal_containers.iter() {
+            remove_delegated_listeners(container, &borrow.delegated_listeners);
+        }
+
+        borrow.delegated_listeners.clear();
+    }
+
+    /// Track an element that a portal placed its children into, attaching the event listeners
+    /// that handle event delegation to it.
+    pub(crate) fn add_portal_container(&self, container: &web_sys::Element) {
+        let mut borrow = self.borrow_mut();
+
+        for (event_type, listener) in borrow.delegated_listeners.iter() {
+            container
+                .add_event_listener_with_callback(event_type, listener)
+                .unwrap();
+        }
+
+        borrow.portal_containers.push(container.clone());
+    }
+
+    /// Stop tracking an element that a portal placed its children into.
+    pub fn remove_portal_container(&self, container: &web_sys::Element) {
+        let mut borrow = self.borrow_mut();
+        let borrow = &mut *borrow;
+
+        remove_delegated_listeners(container, &borrow.delegated_listeners);
+        borrow
+            .portal_containers
+            .retain(|tracked| tracked != container);
     }
 
     /// Create a new element node.
@@ -267,6 +351,19 @@ impl VirtualEvents {
     }
 }
 
+#[cfg(target_arch = "wasm32")]
+fn remove_delegated_listeners(
+    container: &web_sys::Element,
+    delegated_listeners: &[(String, js_sys::Function)],
+) {
+    for (event_type, listener) in delegated_listeners {
+        container
+            .remove_event_listener_with_callback(event_type, listener)
+            .unwrap();
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
 impl VirtualEventsInner {
     fn new() -> Self {
         let root = VirtualEventNode {
@@ -280,6 +377,8 @@ impl VirtualEventsInner {
             root: Rc::new(RefCell::new(root)),
             events: HashMap::new(),
             non_delegated_event_wrappers: HashMap::new(),
+            portal_containers: vec![],
+            delegated_listeners: vec![],
             next_events_id: 0,
         }
     }
@@ -384,6 +483,7 @@ impl VirtualEventNode {
 
 impl VirtualEventElement {
     /// Create a new VirtualEventNode for the given events id.
+    #[cfg(target_arch = "wasm32")]
     fn new(events_id: ElementEventsId) -> Self {
         VirtualEventElement {
             events_id,
@@ -430,6 +530,7 @@ impl VirtualEventElement {
     }
 }
 
+#[cfg(target_arch = "wasm32")]
 pub(crate) fn set_events_id(node: &JsValue, events: &VirtualEvents, events_id: ElementEventsId) {
     Reflect::set(
         &node.into(),
@@ -442,16 +543,15 @@ pub(crate) fn set_events_id(node: &JsValue, events: &VirtualEvents, events_id: E
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
 
     /// Verify that we can append children to a virtual event node.
     #[test]
     fn append_children() {
-        let events = VirtualEvents::new_with_prefix(1.);
-
-        let mut node = events.create_element_node();
+        let mut node = create_element_node();
         let elem = node.as_element_mut().unwrap();
 
-        let children = create_element_nodes(&events, 3);
+        let children = create_element_nodes(3);
         for child in &children {
             elem.append_child(child.clone());
         }
@@ -462,11 +562,9 @@ mod tests {
     /// Verify that we can insert nodes before another node in the virtual event nodes.
     #[test]
     fn insert_before() {
-        let events = VirtualEvents::new_with_prefix(1.);
+        let children = create_element_nodes(3);
 
-        let children = create_element_nodes(&events, 3);
-
-        let mut node = events.create_element_node();
+        let mut node = create_element_node();
 
         {
             let elem = node.as_element_mut().unwrap();
@@ -487,11 +585,9 @@ mod tests {
     /// Verify that we can remove a node from its siblings.
     #[test]
     fn remove_node_from_siblings() {
-        let events = VirtualEvents::new_with_prefix(1.);
-
-        let children = create_element_nodes(&events, 3);
+        let children = create_element_nodes(3);
 
-        let mut node = events.create_element_node();
+        let mut node = create_element_node();
 
         {
             let elem = node.as_element_mut().unwrap();
@@ -516,11 +612,9 @@ mod tests {
     /// Verify that we can replace a node with another node.
     #[test]
     fn replace_node() {
-        let events = VirtualEvents::new_with_prefix(1.);
-
-        let children = create_element_nodes(&events, 3);
+        let children = create_element_nodes(3);
 
-        let mut node = events.create_element_node();
+        let mut node = create_element_node();
 
         {
             let elem = node.as_element_mut().unwrap();
@@ -529,7 +623,7 @@ mod tests {
             }
         }
 
-        let new_node = events.create_element_node();
+        let new_node = create_element_node();
         let new_node_events_id = new_node.as_element().unwrap().events_id;
 
         assert_eq!(node_events_id(&children[1]) == new_node_events_id, false);
@@ -546,20 +640,31 @@ mod tests {
         );
     }
 
-    fn create_element_nodes(
-        events: &VirtualEvents,
-        count: usize,
-    ) -> Vec<Rc<RefCell<VirtualEventNode>>> {
+    fn create_element_nodes(count: usize) -> Vec<Rc<RefCell<VirtualEventNode>>> {
         (0..count)
             .into_iter()
             .map(|_| {
-                let child = events.create_element_node();
+                let child = create_element_node();
                 let child = Rc::new(RefCell::new(child));
                 child
             })
             .collect()
     }
 
+    // Every node gets a unique events ID so that we can tell them apart.
+    fn create_element_node() -> VirtualEventNode {
+        static NEXT_EVENTS_ID: AtomicU32 = AtomicU32::new(0);
+
+        VirtualEventNode {
+            variant: VirtualEventNodeVariant::Element(VirtualEventElement {
+                events_id: ElementEventsId(NEXT_EVENTS_ID.fetch_add(1, Ordering::SeqCst)),
+                children: None,
+            }),
+            previous_sibling: None,
+            next_sibling: None,
+        }
+    }
+
     fn assert_elem_children_equal(
         elem: &VirtualEventElement,
         expected: &[Rc<RefCell<VirtualEventNode>>],
@@ -0,0 +1,740 @@
+//! Parse HTML strings, such as fragments from a CMS or test fixtures, into `VirtualNode`s.
+
+use std::fmt;
+
+use html_validation::{is_self_closing, is_svg_namespace};
+
+use crate::escape_html::is_raw_text_element;
+use crate::{AttributeValue, VElement, VirtualNode, TEXT_SEPARATOR};
+
+impl VirtualNode {
+    /// Parse an HTML string into the `VirtualNode`s that it describes.
+    ///
+    /// Parsing is strict. Every element that is not self closing (see
+    /// `html_validation::is_self_closing`) must be explicitly closed, in order.
+    ///
+    /// - Tag names are lowercased, except for SVG tags (see `html_validation::is_svg_namespace`)
+    ///   such as `linearGradient`, which keep their case.
+    ///
+    /// - Attribute names are lowercased, except within `<svg>` and `<math>` elements, where
+    ///   attributes such as `viewBox` keep their case.
+    ///
+    /// - Attributes without a value, such as `disabled`, become `AttributeValue::Bool(true)`.
+    ///
+    /// - The `class` and `style` attributes are parsed into the element's `classes` and `styles`,
+    ///   the same way that the `html!` macro does.
+    ///
+    /// - Comments become `VirtualNode::Comment`s, except for the `<!--percy:ptns-->` comments that
+    ///   `.to_string()` renders in between neighboring text nodes, which are skipped so that the
+    ///   output of `.to_string()` can be parsed back into the same `VirtualNode`s.
+    ///
+    /// - The contents of `<script>`, `<style>`, `<textarea>` and `<title>` elements are not parsed
+    ///   as HTML. Character references are decoded within `<textarea>` and `<title>` elements.
+    ///
+    /// - `<!DOCTYPE ..>` declarations are skipped.
+    ///
+    /// ```
+    /// # use virtual_node::{AttributeValue, VirtualNode};
+    /// let nodes = VirtualNode::from_html(r#"<button class="big" disabled>Tom &amp; Jerry</button>"#)
+    ///     .unwrap();
+    ///
+    /// let button = nodes[0].as_velement_ref().unwrap();
+    /// assert_eq!(button.tag, "button");
+    /// assert!(button.classes.contains("big"));
+    /// assert_eq!(button.attrs["disabled"], AttributeValue::Bool(true));
+    /// assert_eq!(button.children[0].as_vtext_ref().unwrap().text, "Tom & Jerry");
+    /// ```
+    pub fn from_html(html: &str) -> Result<Vec<VirtualNode>, ParseError> {
+        HtmlParser { html, pos: 0 }.parse()
+    }
+}
+
+/// An error while parsing HTML with [`VirtualNode::from_html`].
+///
+/// Every offset is the byte offset into the HTML string where the problem was found.
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    /// A tag or comment was never finished, such as `<div class="a"` or `<!-- comment`.
+    UnterminatedTag {
+        /// Where the tag or comment starts.
+        offset: usize,
+    },
+    /// A closing tag did not match the element that was most recently opened.
+    MismatchedClosingTag {
+        /// The tag of the element that was most recently opened.
+        expected: String,
+        /// The tag of the closing tag.
+        found: String,
+        /// Where the closing tag starts.
+        offset: usize,
+    },
+    /// A closing tag was found when there were no open elements.
+    UnexpectedClosingTag {
+        /// The tag of the closing tag.
+        tag: String,
+        /// Where the closing tag starts.
+        offset: usize,
+    },
+    /// The HTML ended before an element was closed.
+    UnclosedElement {
+        /// The tag of the element.
+        tag: String,
+        /// Where the element's opening tag starts.
+        offset: usize,
+    },
+}
+
+struct HtmlParser<'a> {
+    html: &'a str,
+    pos: usize,
+}
+
+impl<'a> HtmlParser<'a> {
+    fn parse(mut self) -> Result<Vec<VirtualNode>, ParseError> {
+        let mut roots = vec![];
+        // The elements that we've parsed the opening tag for but not the closing tag, along with
+        // the offset of their opening tag.
+        let mut open_elements: Vec<(VElement, usize)> = vec![];
+
+        while self.pos < self.html.len() {
+            let offset = self.pos;
+            let rest = self.rest();
+
+            let node: VirtualNode = if rest.starts_with("<!--") {
+                match self.parse_comment(offset)? {
+                    TEXT_SEPARATOR => continue,
+                    comment => VirtualNode::comment(comment),
+                }
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                self.skip_past(">", offset)?;
+                continue;
+            } else if rest.starts_with("</") {
+                let tag = self.parse_closing_tag()?;
+
+                // Self closing elements never have children, so there is nothing to close.
+                // This allows for HTML such as `<circle r="5"></circle>`.
+                if is_self_closing(&tag) {
+                    continue;
+                }
+
+                match open_elements.pop() {
+                    Some((element, _)) if element.tag == tag => element.into(),
+                    Some((element, _)) => {
+                        return Err(ParseError::MismatchedClosingTag {
+                            expected: element.tag,
+                            found: tag,
+                            offset,
+                        });
+                    }
+                    None => return Err(ParseError::UnexpectedClosingTag { tag, offset }),
+                }
+            } else if starts_tag(rest) {
+                let in_foreign_content = open_elements
+                    .iter()
+                    .any(|(element, _)| is_foreign_element(&element.tag));
+                let (mut element, has_closing_slash) =
+                    self.parse_opening_tag(in_foreign_content)?;
+
+                if has_closing_slash || is_self_closing(&element.tag) {
+                    element.into()
+                } else if let Some(text) = self.parse_raw_text(&element.tag, offset)? {
+                    // An empty element, such as `<textarea></textarea>`, has no text node.
+                    if !text.is_empty() {
+                        element.children.push(VirtualNode::text(text));
+                    }
+                    element.into()
+                } else {
+                    open_elements.push((element, offset));
+                    continue;
+                }
+            } else {
+                VirtualNode::text(self.parse_text())
+            };
+
+            match open_elements.last_mut() {
+                Some((parent, _)) => parent.children.push(node),
+                None => roots.push(node),
+            };
+        }
+
+        if let Some((element, offset)) = open_elements.pop() {
+            return Err(ParseError::UnclosedElement {
+                tag: element.tag,
+                offset,
+            });
+        }
+
+        Ok(roots)
+    }
+
+    /// Parse an opening tag such as `<div id="a" hidden>`.
+    ///
+    /// Returns the element along with whether or not the tag ended with `/>`.
+    ///
+    /// Attribute names keep their case if the element is within an `<svg>` or `<math>` element.
+    fn parse_opening_tag(
+        &mut self,
+        in_foreign_content: bool,
+    ) -> Result<(VElement, bool), ParseError> {
+        let offset = self.pos;
+        self.pos += 1;
+
+        let mut element = VElement::new(normalize_tag(self.take_name()));
+        let is_foreign = in_foreign_content || is_foreign_element(&element.tag);
+
+        loop {
+            self.skip_whitespace();
+
+            let rest = self.rest();
+            if rest.starts_with('>') {
+                self.pos += 1;
+                return Ok((element, false));
+            } else if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok((element, true));
+            } else if rest.starts_with('/') {
+                self.pos += 1;
+                continue;
+            }
+
+            let name = self.take_name();
+            if name.is_empty() {
+                return Err(ParseError::UnterminatedTag { offset });
+            }
+            let name = if is_foreign {
+                name.to_string()
+            } else {
+                name.to_ascii_lowercase()
+            };
+
+            self.skip_whitespace();
+            let value = if self.rest().starts_with('=') {
+                self.pos += 1;
+                self.skip_whitespace();
+
+                let value = self.take_attribute_value(offset)?;
+                AttributeValue::String(unescape_html(value))
+            } else {
+                AttributeValue::Bool(true)
+            };
+
+            match value {
+                AttributeValue::String(class) if name == "class" => {
+                    element.classes = class.into();
+                }
+                AttributeValue::String(style) if name == "style" => {
+                    element.styles = style.into();
+                }
+                value => {
+                    element.attrs.insert(name, value);
+                }
+            };
+        }
+    }
+
+    /// Parse a closing tag such as `</div>`, returning its tag.
+    fn parse_closing_tag(&mut self) -> Result<String, ParseError> {
+        let offset = self.pos;
+        self.pos += 2;
+
+        let tag = normalize_tag(self.take_name());
+        self.skip_past(">", offset)?;
+
+        Ok(tag)
+    }
+
+    /// The contents of `<script>`, `<style>`, `<textarea>` and `<title>` elements are not HTML,
+    /// so we take everything up until the closing tag. Only `<textarea>` and `<title>` elements
+    /// decode character references.
+    ///
+    /// Returns `None` for all other elements.
+    fn parse_raw_text(&mut self, tag: &str, offset: usize) -> Result<Option<String>, ParseError> {
+        let is_escapable = is_escapable_raw_text_element(tag);
+        if !is_raw_text_element(tag) && !is_escapable {
+            return Ok(None);
+        }
+
+        let closing_tag = format!("</{}", tag);
+        let end = self
+            .rest()
+            .to_ascii_lowercase()
+            .find(&closing_tag)
+            .ok_or_else(|| ParseError::UnclosedElement {
+                tag: tag.to_string(),
+                offset,
+            })?;
+
+        let text = &self.rest()[..end];
+        let text = if is_escapable {
+            unescape_html(text)
+        } else {
+            text.to_string()
+        };
+        self.pos += end;
+        self.parse_closing_tag()?;
+
+        Ok(Some(text))
+    }
+
+    fn parse_text(&mut self) -> String {
+        let rest = self.rest();
+
+        // A `<` that does not start a tag, such as in `1 < 2`, is part of the text.
+        let mut end = rest.len();
+        for (idx, _) in rest.match_indices('<').skip_while(|(idx, _)| *idx == 0) {
+            if starts_markup(&rest[idx..]) {
+                end = idx;
+                break;
+            }
+        }
+
+        self.pos += end;
+        unescape_html(&rest[..end])
+    }
+
+    fn take_name(&mut self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/' || c == '=')
+            .unwrap_or(rest.len());
+
+        self.pos += end;
+        &rest[..end]
+    }
+
+    fn take_attribute_value(&mut self, tag_offset: usize) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+
+        match rest.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                let end = rest[1..]
+                    .find(quote)
+                    .ok_or(ParseError::UnterminatedTag { offset: tag_offset })?;
+
+                self.pos += end + 2;
+                Ok(&rest[1..end + 1])
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(rest.len());
+
+                self.pos += end;
+                Ok(&rest[..end])
+            }
+        }
+    }
+
+    /// Parse a comment such as `<!-- some comment -->`, returning its text.
+    fn parse_comment(&mut self, offset: usize) -> Result<&'a str, ParseError> {
+        self.pos += "<!--".len();
+
+        let rest = self.rest();
+        let end = rest
+            .find("-->")
+            .ok_or(ParseError::UnterminatedTag { offset })?;
+
+        self.pos += end + "-->".len();
+        Ok(&rest[..end])
+    }
+
+    /// Move to right after the next occurrence of the `pattern`.
+    fn skip_past(&mut self, pattern: &str, offset: usize) -> Result<(), ParseError> {
+        let end = self
+            .rest()
+            .find(pattern)
+            .ok_or(ParseError::UnterminatedTag { offset })?;
+
+        self.pos += end + pattern.len();
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.html[self.pos..]
+    }
+}
+
+/// Whether or not the HTML starts with an opening tag, such as `<div`.
+fn starts_tag(html: &str) -> bool {
+    let mut chars = html.chars();
+    chars.next() == Some('<') && chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+}
+
+/// Whether or not the HTML starts with a tag, a closing tag, a comment or a declaration.
+fn starts_markup(html: &str) -> bool {
+    starts_tag(html) || html.starts_with("</") || html.starts_with("<!") || html.starts_with("<?")
+}
+
+/// Whether the element's contents are raw text in which character references are decoded, such
+/// as the contents of a `<textarea>`.
+fn is_escapable_raw_text_element(tag: &str) -> bool {
+    tag == "textarea" || tag == "title"
+}
+
+/// SVG and MathML elements, whose descendants are not HTML elements, so their attribute names are
+/// case sensitive.
+fn is_foreign_element(tag: &str) -> bool {
+    tag == "svg" || tag == "math"
+}
+
+/// HTML tags are case insensitive, but SVG tags such as `linearGradient` are not.
+fn normalize_tag(tag: &str) -> String {
+    if is_svg_namespace(tag) {
+        tag.to_string()
+    } else {
+        tag.to_ascii_lowercase()
+    }
+}
+
+/// Replace character references such as `&amp;` and `&#39;` with the characters that they
+/// refer to.
+///
+/// Unknown character references are left as is.
+fn unescape_html(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let reference = rest
+            .find(';')
+            .and_then(|end| Some((end, character_reference(&rest[1..end])?)));
+
+        match reference {
+            Some((end, c)) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        };
+    }
+    unescaped.push_str(rest);
+
+    unescaped
+}
+
+/// The character for a character reference's name, such as the `amp` in `&amp;`.
+fn character_reference(name: &str) -> Option<char> {
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        _ => {
+            let code =
+                if let Some(hex) = name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else {
+                    name.strip_prefix('#')?.parse().ok()?
+                };
+
+            return std::char::from_u32(code);
+        }
+    };
+
+    Some(c)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnterminatedTag { offset } => {
+                write!(f, "The tag at byte {} is never terminated.", offset)
+            }
+            ParseError::MismatchedClosingTag {
+                expected,
+                found,
+                offset,
+            } => write!(
+                f,
+                "Expected </{}> but found </{}> at byte {}.",
+                expected, found, offset
+            ),
+            ParseError::UnexpectedClosingTag { tag, offset } => write!(
+                f,
+                "Found </{}> at byte {} but there was no element to close.",
+                tag, offset
+            ),
+            ParseError::UnclosedElement { tag, offset } => {
+                write!(f, "The <{}> at byte {} is never closed.", tag, offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Classes, Styles, VText};
+
+    /// Verify that we can parse nested elements, their attributes and their text.
+    #[test]
+    fn nested_elements() {
+        let nodes =
+            VirtualNode::from_html(r#"<div id="a"><span class='b'>Hi</span> there</div>"#).unwrap();
+
+        let mut span = VElement::new("span");
+        span.classes = "b".into();
+        span.children.push(VirtualNode::text("Hi"));
+
+        let mut div = VElement::new("div");
+        div.attrs.insert("id".to_string(), "a".into());
+        div.children.push(span.into());
+        div.children.push(VirtualNode::text(" there"));
+
+        assert_eq!(nodes, vec![div.into()]);
+    }
+
+    /// Verify that attributes without a value are parsed as boolean attributes, and that
+    /// unquoted attribute values are supported.
+    #[test]
+    fn attributes() {
+        let nodes = VirtualNode::from_html(r#"<input disabled type=checkbox value="">"#).unwrap();
+        let input = nodes[0].as_velement_ref().unwrap();
+
+        assert_eq!(input.attrs["disabled"], AttributeValue::Bool(true));
+        assert_eq!(
+            input.attrs["type"],
+            AttributeValue::String("checkbox".into())
+        );
+        assert_eq!(input.attrs["value"], AttributeValue::String("".into()));
+    }
+
+    /// Verify that HTML attribute names are lowercased, while SVG attribute names keep their case.
+    #[test]
+    fn attribute_names_case() {
+        let nodes =
+            VirtualNode::from_html(r#"<DIV CLASS=x ID=y><svg viewBox="0 0 1 1"></svg></DIV>"#)
+                .unwrap();
+        let div = nodes[0].as_velement_ref().unwrap();
+
+        assert!(div.classes.contains("x"));
+        assert_eq!(div.attrs["id"], AttributeValue::String("y".into()));
+        assert!(!div.attrs.contains_key("ID"));
+
+        let svg = div.children[0].as_velement_ref().unwrap();
+        assert!(svg.attrs.contains_key("viewBox"));
+    }
+
+    /// Verify that the style attribute is parsed into the element's styles.
+    #[test]
+    fn style_attribute() {
+        let nodes =
+            VirtualNode::from_html(r#"<div style="color: red; width: 10px;"></div>"#).unwrap();
+        let div = nodes[0].as_velement_ref().unwrap();
+
+        assert_eq!(div.styles, Styles::from("color: red; width: 10px"));
+        assert!(!div.attrs.contains_key("style"));
+    }
+
+    /// Verify that the class attribute is parsed into the element's classes.
+    #[test]
+    fn class_attribute() {
+        let nodes = VirtualNode::from_html(r#"<div class=" btn  primary"></div>"#).unwrap();
+        let div = nodes[0].as_velement_ref().unwrap();
+
+        assert_eq!(div.classes, Classes::from(["btn", "primary"]));
+        assert!(!div.attrs.contains_key("class"));
+    }
+
+    /// Verify that self closing elements do not need a closing tag and never have children.
+    #[test]
+    fn self_closing_elements() {
+        let nodes = VirtualNode::from_html("<p>a<br>b<img src=x.png/></p><hr />").unwrap();
+
+        let p = nodes[0].as_velement_ref().unwrap();
+        assert_eq!(p.children.len(), 4);
+        assert_eq!(p.children[1], VirtualNode::element("br"));
+        assert_eq!(nodes[1], VirtualNode::element("hr"));
+    }
+
+    /// Verify that SVG tags keep their case while HTML tags are lowercased, and that SVG
+    /// elements can be self closing or explicitly closed.
+    #[test]
+    fn svg_elements() {
+        let nodes = VirtualNode::from_html(
+            r#"<DIV><svg viewBox="0 0 1 1"><linearGradient></linearGradient><circle r="1"></circle><path/></svg></DIV>"#,
+        )
+        .unwrap();
+
+        let div = nodes[0].as_velement_ref().unwrap();
+        assert_eq!(div.tag, "div");
+
+        let svg = div.children[0].as_velement_ref().unwrap();
+        let tags: Vec<&str> = svg
+            .children
+            .iter()
+            .map(|child| child.as_velement_ref().unwrap().tag.as_str())
+            .collect();
+        assert_eq!(tags, vec!["linearGradient", "circle", "path"]);
+        assert_eq!(
+            svg.attrs["viewBox"],
+            AttributeValue::String("0 0 1 1".into())
+        );
+    }
+
+    /// Verify that we replace character references in text and attribute values.
+    #[test]
+    fn character_references() {
+        let nodes = VirtualNode::from_html(
+            r#"<a title="&quot;&#39;">&lt;b&gt; &amp; &#x41;&#66; &copy; 1 < 2</a>"#,
+        )
+        .unwrap();
+        let a = nodes[0].as_velement_ref().unwrap();
+
+        assert_eq!(a.attrs["title"], AttributeValue::String(r#""'"#.into()));
+        assert_eq!(a.children, vec![VirtualNode::text("<b> & AB &copy; 1 < 2")]);
+    }
+
+    /// Verify that we parse comments, and skip text separators and declarations.
+    #[test]
+    fn parses_comments() {
+        let nodes = VirtualNode::from_html(
+            "<!DOCTYPE html><!-- <div> --><em>Hello<!--percy:ptns-->world</em>",
+        )
+        .unwrap();
+
+        let mut em = VElement::new("em");
+        em.children.push(VirtualNode::text("Hello"));
+        em.children.push(VirtualNode::text("world"));
+
+        assert_eq!(nodes, vec![VirtualNode::comment(" <div> "), em.into()]);
+    }
+
+    /// Verify that a comment that only resembles a text separator is kept.
+    #[test]
+    fn keeps_comments_that_resemble_text_separators() {
+        let mut div = VElement::new("div");
+        div.children.push(VirtualNode::text("Hello"));
+        div.children.push(VirtualNode::comment("ptns"));
+        div.children.push(VirtualNode::text("world"));
+        let div: VirtualNode = div.into();
+
+        assert_eq!(VirtualNode::from_html(&div.to_string()).unwrap(), vec![div]);
+    }
+
+    /// Verify that the contents of script and style elements are not parsed as HTML.
+    #[test]
+    fn raw_text_elements() {
+        let nodes =
+            VirtualNode::from_html("<script>if (a < b && c) {}</SCRIPT><style>a > b {}</style>")
+                .unwrap();
+
+        assert_eq!(
+            nodes[0].as_velement_ref().unwrap().children,
+            vec![VirtualNode::text("if (a < b && c) {}")]
+        );
+        assert_eq!(
+            nodes[1].as_velement_ref().unwrap().children,
+            vec![VirtualNode::text("a > b {}")]
+        );
+    }
+
+    /// Verify that the contents of textarea and title elements are not parsed as HTML, but that
+    /// their character references are decoded.
+    #[test]
+    fn escapable_raw_text_elements() {
+        let nodes = VirtualNode::from_html(
+            "<textarea>a</b> &amp; <i></TEXTAREA><title><b>Tom &amp; Jerry</title><textarea></textarea>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            nodes[0].as_velement_ref().unwrap().children,
+            vec![VirtualNode::text("a</b> & <i>")]
+        );
+        assert_eq!(
+            nodes[1].as_velement_ref().unwrap().children,
+            vec![VirtualNode::text("<b>Tom & Jerry")]
+        );
+        assert!(nodes[2].as_velement_ref().unwrap().children.is_empty());
+    }
+
+    /// Verify that parsing the output of `.to_string()` gives us back the same virtual nodes.
+    #[test]
+    fn round_trips_to_string() {
+        let mut list = VElement::new("ul");
+        for idx in 0..3 {
+            let mut item = VElement::new("li");
+            item.attrs.insert("data-idx".to_string(), idx.into());
+            item.children.push(VText::new("<First> & 'Second'").into());
+            item.children.push(VText::new("Third").into());
+            list.children.push(item.into());
+        }
+
+        let mut button = VElement::new("button");
+        button.attrs.insert("disabled".to_string(), true.into());
+        button
+            .attrs
+            .insert("title".to_string(), r#"Say "hi""#.into());
+
+        let mut root = VElement::new("div");
+        root.children.push(list.into());
+        root.children.push(VirtualNode::element("br"));
+        root.children.push(button.into());
+        let root: VirtualNode = root.into();
+
+        assert_eq!(
+            VirtualNode::from_html(&root.to_string()).unwrap(),
+            vec![root]
+        );
+    }
+
+    /// Verify that we return an error for HTML that we cannot parse.
+    #[test]
+    fn errors() {
+        let tests = [
+            ("<div", ParseError::UnterminatedTag { offset: 0 }),
+            (
+                r#"<p><a href="x></a></p>"#,
+                ParseError::UnterminatedTag { offset: 3 },
+            ),
+            ("a<!-- b", ParseError::UnterminatedTag { offset: 1 }),
+            (
+                "<div><span></div>",
+                ParseError::MismatchedClosingTag {
+                    expected: "span".to_string(),
+                    found: "div".to_string(),
+                    offset: 11,
+                },
+            ),
+            (
+                "<p></p></p>",
+                ParseError::UnexpectedClosingTag {
+                    tag: "p".to_string(),
+                    offset: 7,
+                },
+            ),
+            (
+                "<main><p></p>",
+                ParseError::UnclosedElement {
+                    tag: "main".to_string(),
+                    offset: 0,
+                },
+            ),
+        ];
+
+        for (html, expected) in tests.iter() {
+            assert_eq!(
+                &VirtualNode::from_html(html).unwrap_err(),
+                expected,
+                "{}",
+                html
+            );
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Hydrate DOM nodes that already exist in the page, such as those that were rendered on the
+//! server, instead of creating new ones.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+use crate::component::COMPONENT_HOST_TAG;
+use crate::create_element::{
+    attach_declarative_shadow_root, set_template_shadow_root, set_virtual_node_marker,
+};
+use crate::event::{VirtualEventElement, VirtualEvents};
+use crate::namespace;
+use crate::{
+    AttributeValue, VComment, VElement, VText, VirtualEventNode, VirtualNode, TEXT_SEPARATOR,
+};
+
+/// A difference between a DOM node that was being hydrated and the [`VirtualNode`] that it was
+/// being hydrated with.
+///
+/// The DOM is patched to match the `VirtualNode` whenever a mismatch is found.
+#[derive(Debug, PartialEq)]
+pub enum HydrationMismatch {
+    /// A DOM node was a different kind of node than the virtual node, so it was replaced.
+    Replaced {
+        /// The virtual node that we expected to find, such as `<div>` or `"some text"`.
+        expected: String,
+        /// The name of the DOM node that we found instead, such as `SPAN` or `#text`.
+        found: String,
+    },
+    /// A text node had different text than the virtual text node, so its text was changed.
+    Text {
+        /// The text of the virtual text node.
+        expected: String,
+        /// The text of the DOM text node.
+        found: String,
+    },
+    /// A comment node had different text than the virtual comment node, so its text was changed.
+    Comment {
+        /// The text of the virtual comment node.
+        expected: String,
+        /// The text of the DOM comment node.
+        found: String,
+    },
+    /// An attribute had a different value than the virtual node's attribute, so it was set to
+    /// the virtual node's value or removed.
+    Attribute {
+        /// The tag of the element, such as `div`.
+        tag: String,
+        /// The name of the attribute, such as `id`.
+        name: String,
+        /// The virtual node's value for the attribute, or `None` if it should not be present.
+        expected: Option<String>,
+        /// The DOM element's value for the attribute, or `None` if it was not present.
+        found: Option<String>,
+    },
+    /// There was no DOM node for a virtual node, so one was created.
+    Missing {
+        /// The virtual node that we expected to find.
+        expected: String,
+    },
+    /// There was a DOM node that did not correspond to any virtual node, so it was removed.
+    Extra {
+        /// The name of the DOM node, such as `SPAN` or `#text`.
+        found: String,
+    },
+}
+
+impl VirtualNode {
+    /// Hydrate a DOM node that was rendered from this `VirtualNode`, typically by calling
+    /// `.to_string()` on the server.
+    ///
+    /// Instead of creating new DOM nodes we walk the existing DOM nodes alongside the virtual
+    /// nodes, marking them as Percy nodes, adding their events and calling their
+    /// `on_create_element` functions.
+    ///
+    /// Every mismatch between the DOM and the virtual nodes gets pushed to `mismatches` and then
+    /// patched so that the DOM matches the virtual nodes.
+    ///
+    /// A fragment is hydrated using the children of the passed in node, which should be the
+    /// element that the fragment was rendered into.
+    ///
+    /// Returns the hydrated node along with its events. The returned node is only different from
+    /// the passed in node if the passed in node did not match and had to be replaced.
+    pub fn hydrate_dom_node(
+        &self,
+        node: Node,
+        events: &mut VirtualEvents,
+        mismatches: &mut Vec<HydrationMismatch>,
+    ) -> (Node, VirtualEventNode) {
+        match self {
+            VirtualNode::Text(text_node) if node.node_type() == Node::TEXT_NODE => {
+                text_node.hydrate_text_node(&node, mismatches);
+                (node, events.create_text_node())
+            }
+            VirtualNode::Comment(comment_node) if node.node_type() == Node::COMMENT_NODE => {
+                comment_node.hydrate_comment_node(&node, mismatches);
+                (node, events.create_text_node())
+            }
+            VirtualNode::Element(element_node) if element_node.matches_dom_node(&node) => {
+                let events_node =
+                    element_node.hydrate_element_node(node.unchecked_ref(), events, mismatches);
+                (node, events_node)
+            }
+            VirtualNode::Memo(memo) => memo.view().hydrate_dom_node(node, events, mismatches),
+            VirtualNode::Fragment(_) => {
+                let mut events_node = events.create_element_node();
+                hydrate_children(
+                    self.dom_nodes(),
+                    &node,
+                    events_node.as_element_mut().unwrap(),
+                    events,
+                    mismatches,
+                );
+                (node, events_node)
+            }
+            _ => {
+                mismatches.push(HydrationMismatch::Replaced {
+                    expected: self.hydration_description(),
+                    found: node.node_name(),
+                });
+
+                match node.parent_node() {
+                    Some(parent) => {
+                        let (created_node, events_node) =
+                            self.create_dom_node_within(&parent, events);
+                        parent.replace_child(&created_node, &node).unwrap();
+                        (created_node, events_node)
+                    }
+                    None => self.create_dom_node(events),
+                }
+            }
+        }
+    }
+
+    fn hydration_description(&self) -> String {
+        match self {
+            VirtualNode::Element(element_node) => format!("<{}>", element_node.tag),
+            VirtualNode::Text(text_node) => format!("{:?}", text_node.text),
+            VirtualNode::Comment(comment_node) => format!("<!--{}-->", comment_node.text),
+            VirtualNode::Fragment(_) => "<>".to_string(),
+            VirtualNode::Memo(memo) => memo.view().hydration_description(),
+        }
+    }
+}
+
+impl VElement {
+    fn matches_dom_node(&self, node: &Node) -> bool {
+        node.node_type() == Node::ELEMENT_NODE
+            && node
+                .unchecked_ref::<Element>()
+                .tag_name()
+                .eq_ignore_ascii_case(&self.tag)
+    }
+
+    fn hydrate_element_node(
+        &self,
+        element: &Element,
+        events: &mut VirtualEvents,
+        mismatches: &mut Vec<HydrationMismatch>,
+    ) -> VirtualEventNode {
+        set_virtual_node_marker(element);
+
+        self.hydrate_attributes(element, mismatches);
+
+        let mut event_elem = events.create_element_node();
+        self.add_events(
+            element,
+            events,
+            event_elem.as_element().unwrap().events_id(),
+        );
+
+        // A portal's children were not rendered on the server, so we create them. The inner HTML
+        // was rendered in place of the children, and none of it is managed by Percy.
+        if let Some(target) = self.special_attributes.portal() {
+            self.create_portal_children(
+                element,
+                target,
+                event_elem.as_element_mut().unwrap(),
+                events,
+            );
+        } else if self.tag == COMPONENT_HOST_TAG {
+            // A component's view was rendered inside of its host element on the server. The
+            // component's own `PercyDom` hydrates it when the host's `on_create_element` function
+            // mounts the component.
+        } else if self.special_attributes.dangerous_inner_html.is_none() {
+            hydrate_children(
+                self.dom_children(),
+                element,
+                event_elem.as_element_mut().unwrap(),
+                events,
+                mismatches,
+            );
+        }
+
+        // Properties are not a part of the server rendered HTML, so we always set them.
+        self.set_properties(element);
+
+        self.special_attributes.maybe_set_node_ref(element);
+        self.special_attributes
+            .maybe_call_on_create_element(element);
+
+        event_elem
+    }
+
+    /// The browser removes a declarative shadow root's `<template>` after placing its children
+    /// into a shadow root that it attaches to the `host`, so we hydrate the shadow root's
+    /// children and then put an empty template back in its place, just like when creating it.
+    ///
+    /// Closed shadow roots can not be accessed, so they get reported as missing.
+    fn hydrate_declarative_shadow_root(
+        &self,
+        host: &Node,
+        next_sibling: Option<&Node>,
+        events: &mut VirtualEvents,
+        mismatches: &mut Vec<HydrationMismatch>,
+    ) -> VirtualEventNode {
+        let shadow_root = host
+            .dyn_ref::<Element>()
+            .and_then(|host| host.shadow_root());
+        let shadow_root = match shadow_root {
+            Some(shadow_root) => shadow_root,
+            None => {
+                mismatches.push(HydrationMismatch::Missing {
+                    expected: format!("<{}>", self.tag),
+                });
+
+                let (template, events_node) =
+                    self.create_element_node(namespace::dom_children_namespace(host), events);
+                attach_declarative_shadow_root(host, &template);
+                host.insert_before(&template, next_sibling).unwrap();
+                return events_node;
+            }
+        };
+
+        let document = host.owner_document().unwrap();
+        let template = document.create_element(&self.tag).unwrap();
+        set_virtual_node_marker(&template);
+
+        for (name, value) in self.rendered_attributes() {
+            match value.as_ref() {
+                AttributeValue::String(s) => namespace::set_attribute(&template, name, s).unwrap(),
+                AttributeValue::Bool(true) => {
+                    namespace::set_attribute(&template, name, "").unwrap()
+                }
+                AttributeValue::Bool(false) => {}
+            };
+        }
+
+        let mut event_elem = events.create_element_node();
+        self.add_events(
+            &template,
+            events,
+            event_elem.as_element().unwrap().events_id(),
+        );
+
+        hydrate_children(
+            self.dom_children(),
+            &shadow_root,
+            event_elem.as_element_mut().unwrap(),
+            events,
+            mismatches,
+        );
+
+        set_template_shadow_root(&template, &shadow_root);
+        host.insert_before(&template, next_sibling).unwrap();
+
+        event_elem
+    }
+
+    fn hydrate_attributes(&self, element: &Element, mismatches: &mut Vec<HydrationMismatch>) {
+        // The classes and styles were rendered into the `class` and `style` attributes.
+        let attrs = self.rendered_attributes();
+
+        for (name, value) in attrs.iter() {
+            let value: &AttributeValue = value;
+            let expected = match value {
+                AttributeValue::String(s) => Some(s.as_str()),
+                AttributeValue::Bool(true) => Some(""),
+                AttributeValue::Bool(false) => None,
+            };
+            let found = element.get_attribute(name);
+
+            // A boolean attribute is present no matter what its value is.
+            let matches = match value {
+                AttributeValue::String(_) => found.as_deref() == expected,
+                AttributeValue::Bool(b) => found.is_some() == *b,
+            };
+            if matches {
+                continue;
+            }
+
+            mismatches.push(HydrationMismatch::Attribute {
+                tag: self.tag.clone(),
+                name: name.to_string(),
+                expected: expected.map(|e| e.to_string()),
+                found,
+            });
+
+            match expected {
+                Some(expected) => namespace::set_attribute(element, name, expected).unwrap(),
+                None => namespace::remove_attribute(element, name).unwrap(),
+            };
+        }
+
+        for name in element.get_attribute_names().iter() {
+            let name = name.as_string().unwrap();
+            if attrs.iter().any(|(rendered, _)| *rendered == name) {
+                continue;
+            }
+
+            mismatches.push(HydrationMismatch::Attribute {
+                tag: self.tag.clone(),
+                expected: None,
+                found: element.get_attribute(&name),
+                name: name.clone(),
+            });
+            element.remove_attribute(&name).unwrap();
+        }
+    }
+}
+
+/// Hydrate the DOM children of the `element` with the virtual nodes that should be placed into it.
+fn hydrate_children(
+    children: Vec<&VirtualNode>,
+    element: &Node,
+    event_node: &mut VirtualEventElement,
+    events: &mut VirtualEvents,
+    mismatches: &mut Vec<HydrationMismatch>,
+) {
+    let document = element.owner_document().unwrap();
+
+    let dom_children = element.child_nodes();
+    let dom_children: Vec<Node> = (0..dom_children.length())
+        .map(|idx| dom_children.item(idx).unwrap())
+        .collect();
+    let mut dom_children = dom_children.into_iter().peekable();
+
+    let mut previous_node_was_text = false;
+
+    for child in children {
+        let child = child.resolve_memos();
+
+        let child_events_node = match child {
+            VirtualNode::Text(text_node) => {
+                // Neighboring text nodes are rendered with a `<!--percy:ptns-->` comment in between
+                // them so that the browser does not merge them into one text node.
+                // See `create_element.rs` for more information.
+                if previous_node_was_text {
+                    match dom_children.peek() {
+                        Some(separator) if is_text_separator(separator) => {
+                            dom_children.next();
+                        }
+                        next => {
+                            let separator = document.create_comment(TEXT_SEPARATOR);
+                            element.insert_before(&separator, next).unwrap();
+                        }
+                    };
+                }
+                previous_node_was_text = true;
+
+                match dom_children.next_if(|node| node.node_type() == Node::TEXT_NODE) {
+                    Some(dom_text) => {
+                        text_node.hydrate_text_node(&dom_text, mismatches);
+                    }
+                    None => {
+                        // An empty text node does not render anything, so we don't expect
+                        // to find it in the DOM.
+                        if !text_node.text.is_empty() {
+                            mismatches.push(HydrationMismatch::Missing {
+                                expected: child.hydration_description(),
+                            });
+                        }
+
+                        element
+                            .insert_before(&text_node.create_text_node(), dom_children.peek())
+                            .unwrap();
+                    }
+                };
+
+                events.create_text_node()
+            }
+            VirtualNode::Fragment(_) => {
+                unreachable!("Fragments are flattened into their parent's children.")
+            }
+            VirtualNode::Memo(_) => unreachable!("Memos were resolved to their views."),
+            VirtualNode::Element(element_node) if element_node.is_declarative_shadow_root() => {
+                previous_node_was_text = false;
+
+                element_node.hydrate_declarative_shadow_root(
+                    element,
+                    dom_children.peek(),
+                    events,
+                    mismatches,
+                )
+            }
+            VirtualNode::Element(_) | VirtualNode::Comment(_) => {
+                previous_node_was_text = false;
+
+                match dom_children.next() {
+                    Some(dom_child) => {
+                        let (_node, child_events) =
+                            child.hydrate_dom_node(dom_child, events, mismatches);
+                        child_events
+                    }
+                    None => {
+                        mismatches.push(HydrationMismatch::Missing {
+                            expected: child.hydration_description(),
+                        });
+
+                        let (created_node, child_events) =
+                            child.create_dom_node_within(element, events);
+                        element.append_child(&created_node).unwrap();
+                        child_events
+                    }
+                }
+            }
+        };
+
+        event_node.append_child(Rc::new(RefCell::new(child_events_node)));
+    }
+
+    for extra in dom_children {
+        mismatches.push(HydrationMismatch::Extra {
+            found: extra.node_name(),
+        });
+        element.remove_child(&extra).unwrap();
+    }
+}
+
+impl VText {
+    fn hydrate_text_node(&self, node: &Node, mismatches: &mut Vec<HydrationMismatch>) {
+        set_virtual_node_marker(node);
+
+        let found = node.node_value().unwrap_or_default();
+        if found != self.text {
+            mismatches.push(HydrationMismatch::Text {
+                expected: self.text.clone(),
+                found,
+            });
+            node.set_node_value(Some(&self.text));
+        }
+    }
+}
+
+impl VComment {
+    fn hydrate_comment_node(&self, node: &Node, mismatches: &mut Vec<HydrationMismatch>) {
+        set_virtual_node_marker(node);
+
+        let found = node.node_value().unwrap_or_default();
+        if found != self.text {
+            mismatches.push(HydrationMismatch::Comment {
+                expected: self.text.clone(),
+                found,
+            });
+            node.set_node_value(Some(&self.text));
+        }
+    }
+}
+
+// Separators are not given the virtual node marker, so that they can be told apart from the
+// comment nodes that were hydrated from a `VirtualNode::Comment`.
+fn is_text_separator(node: &Node) -> bool {
+    node.node_type() == Node::COMMENT_NODE && node.node_value().as_deref() == Some(TEXT_SEPARATOR)
+}
+
+impl fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HydrationMismatch::Replaced { expected, found } => {
+                write!(f, "Expected {} but found {}. It was replaced.", expected, found)
+            }
+            HydrationMismatch::Text { expected, found } => write!(
+                f,
+                "Expected text {:?} but found {:?}. The text was changed.",
+                expected, found
+            ),
+            HydrationMismatch::Attribute {
+                tag,
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected <{}> attribute {:?} to be {:?} but found {:?}. The attribute was patched.",
+                tag, name, expected, found
+            ),
+            HydrationMismatch::Comment { expected, found } => write!(
+                f,
+                "Expected comment {:?} but found {:?}. The comment was changed.",
+                expected, found
+            ),
+            HydrationMismatch::Missing { expected } => {
+                write!(f, "Expected {} but found nothing. It was created.", expected)
+            }
+            HydrationMismatch::Extra { found } => {
+                write!(f, "Found an unexpected {}. It was removed.", found)
+            }
+        }
+    }
+}
@@ -25,8 +25,13 @@ impl IntoIterator for IterableNodes {
     // TODO: Is this possible with an array [VirtualNode] instead of a vec?
     type IntoIter = ::std::vec::IntoIter<VirtualNode>;
 
+    /// Fragments are flattened, so their children get placed in the fragment's parent.
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        if !self.0.iter().any(|node| node.as_fragment_ref().is_some()) {
+            return self.0.into_iter();
+        }
+
+        self.0.into_iter().flatten().collect::<Vec<_>>().into_iter()
     }
 }
 
@@ -1,32 +1,54 @@
 //! The virtual_node module exposes the `VirtualNode` struct and methods that power our
 //! virtual dom.
-
-// TODO: A few of these dependencies (including js_sys) are used to power events.. yet events
-// only work on wasm32 targets. So we should start sprinkling some
-//
-// #[cfg(target_arch = "wasm32")]
-// #[cfg(not(target_arch = "wasm32"))]
-//
-// Around in order to get rid of dependencies that we don't need in non wasm32 targets
+//!
+//! The DOM specific functionality, such as creating and hydrating DOM nodes and the closures
+//! that power events, is only available on wasm32 targets. On other targets, such as when
+//! rendering to a `String` on the server, a virtual node does not hold any browser types.
 
 use std::fmt;
 
+use crate::velement::write_children;
+
+#[cfg(target_arch = "wasm32")]
 use crate::event::{VirtualEventNode, VirtualEvents};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
 use web_sys::{self, Node};
 
-pub use self::create_element::VIRTUAL_NODE_MARKER_PROPERTY;
+pub use self::component::{Component, Scope};
+#[cfg(target_arch = "wasm32")]
+pub use self::create_element::{
+    portal_container, portal_of_container, template_shadow_root, VIRTUAL_NODE_MARKER_PROPERTY,
+};
+#[cfg(target_arch = "wasm32")]
 pub use self::event::EventAttribFn;
+pub use self::from_html::ParseError;
+#[cfg(target_arch = "wasm32")]
+pub use self::hydrate::HydrationMismatch;
 pub use self::iterable_nodes::*;
+pub use self::vcomment::*;
 pub use self::velement::*;
+pub use self::vmemo::*;
 pub use self::vtext::*;
 
+pub mod component;
 pub mod event;
+pub mod namespace;
+pub mod ssr;
 pub mod test_utils;
 
+#[cfg(target_arch = "wasm32")]
 mod create_element;
+mod escape_html;
+mod from_html;
+#[cfg(target_arch = "wasm32")]
+mod hydrate;
 
 mod iterable_nodes;
+mod vcomment;
 mod velement;
+mod vmemo;
 mod vtext;
 
 /// When building your views you'll typically use the `html!` macro to generate
@@ -54,6 +76,17 @@ pub enum VirtualNode {
     /// order to enable custom methods like `create_text_node()` on the
     /// wrapped type.
     Text(VText),
+    /// A comment node (node type `COMMENT_NODE`), such as `<!-- some comment -->`.
+    Comment(VComment),
+    /// A list of nodes without a parent element of their own, such as `html! { <> <li/> <li/> </> }`.
+    ///
+    /// A fragment does not create a DOM node. Its children get placed into the DOM, in order, in
+    /// place of the fragment, as if they were the fragment's parent's children.
+    Fragment(Vec<VirtualNode>),
+    /// A node whose view is only rendered and diffed when its hash changes.
+    ///
+    /// A memo places its view, which is a single node, into the DOM. See [`VMemo`].
+    Memo(VMemo),
 }
 
 impl VirtualNode {
@@ -89,6 +122,58 @@ impl VirtualNode {
         VirtualNode::Text(VText::new(text.into()))
     }
 
+    /// Create a new virtual comment node with the given text.
+    ///
+    /// These get patched into the DOM using `document.createComment`
+    ///
+    /// ```
+    /// # use virtual_node::VirtualNode;
+    /// let _comment = VirtualNode::comment("My comment node");
+    /// ```
+    pub fn comment<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        VirtualNode::Comment(VComment::new(text.into()))
+    }
+
+    /// Create a new fragment with the given children.
+    ///
+    /// Any fragments within the children are flattened into this fragment.
+    ///
+    /// ```
+    /// # use virtual_node::VirtualNode;
+    /// let _fragment = VirtualNode::fragment(vec![
+    ///     VirtualNode::element("dt"),
+    ///     VirtualNode::element("dd"),
+    /// ]);
+    /// ```
+    pub fn fragment(children: Vec<VirtualNode>) -> Self {
+        VirtualNode::Fragment(children.into_iter().flatten().collect())
+    }
+
+    /// Create a new memo that only renders its view when it is needed.
+    ///
+    /// When a memo gets diffed against an old memo whose key has the same hash, the memo's view
+    /// is never rendered and the old memo's DOM node is kept as it is, without diffing any of its
+    /// descendants.
+    ///
+    /// The view must be a single node, not a fragment.
+    ///
+    /// ```
+    /// # use virtual_node::VirtualNode;
+    /// let rows_version = 3;
+    ///
+    /// let _table = VirtualNode::memo(rows_version, || VirtualNode::element("table"));
+    /// ```
+    pub fn memo<K, F>(key: K, render: F) -> Self
+    where
+        K: std::hash::Hash,
+        F: FnOnce() -> VirtualNode + 'static,
+    {
+        VirtualNode::Memo(VMemo::new(key, render))
+    }
+
     /// Return a [`VElement`] reference, if this is an [`Element`] variant.
     ///
     /// [`VElement`]: struct.VElement.html
@@ -135,17 +220,149 @@ impl VirtualNode {
         }
     }
 
+    /// Return a [`VComment`] reference, if this is a [`Comment`] variant.
+    ///
+    /// [`VComment`]: struct.VComment.html
+    /// [`Comment`]: enum.VirtualNode.html#variant.Comment
+    pub fn as_vcomment_ref(&self) -> Option<&VComment> {
+        match self {
+            VirtualNode::Comment(ref comment_node) => Some(comment_node),
+            _ => None,
+        }
+    }
+
+    /// Return a mutable [`VComment`] reference, if this is a [`Comment`] variant.
+    ///
+    /// [`VComment`]: struct.VComment.html
+    /// [`Comment`]: enum.VirtualNode.html#variant.Comment
+    pub fn as_vcomment_mut(&mut self) -> Option<&mut VComment> {
+        match self {
+            VirtualNode::Comment(ref mut comment_node) => Some(comment_node),
+            _ => None,
+        }
+    }
+
+    /// Return a reference to the fragment's children, if this is a [`Fragment`] variant.
+    ///
+    /// [`Fragment`]: enum.VirtualNode.html#variant.Fragment
+    pub fn as_fragment_ref(&self) -> Option<&Vec<VirtualNode>> {
+        match self {
+            VirtualNode::Fragment(ref children) => Some(children),
+            _ => None,
+        }
+    }
+
+    /// Return a mutable reference to the fragment's children, if this is a [`Fragment`]
+    /// variant.
+    ///
+    /// [`Fragment`]: enum.VirtualNode.html#variant.Fragment
+    pub fn as_fragment_mut(&mut self) -> Option<&mut Vec<VirtualNode>> {
+        match self {
+            VirtualNode::Fragment(ref mut children) => Some(children),
+            _ => None,
+        }
+    }
+
+    /// Return a [`VMemo`] reference, if this is a [`Memo`] variant.
+    ///
+    /// [`VMemo`]: struct.VMemo.html
+    /// [`Memo`]: enum.VirtualNode.html#variant.Memo
+    pub fn as_vmemo_ref(&self) -> Option<&VMemo> {
+        match self {
+            VirtualNode::Memo(ref memo) => Some(memo),
+            _ => None,
+        }
+    }
+
+    /// The node that gets placed into the DOM for this node.
+    ///
+    /// For a memo this is the memo's view, which gets rendered if it has not been rendered yet.
+    /// Every other node is returned as is.
+    pub fn resolve_memos(&self) -> &VirtualNode {
+        let mut node = self;
+        while let VirtualNode::Memo(memo) = node {
+            node = memo.view();
+        }
+        node
+    }
+
+    /// The nodes that this node places into the DOM.
+    ///
+    /// A fragment places its children, with any nested fragments flattened. Every other node,
+    /// including a memo, places itself.
+    pub fn dom_nodes(&self) -> Vec<&VirtualNode> {
+        let mut nodes = vec![];
+        push_dom_nodes(self, &mut nodes);
+        nodes
+    }
+
     /// Create and return a [`web_sys::Node`] along with its events.
+    ///
+    /// A fragment's children are created inside of a [`web_sys::DocumentFragment`]. Its events
+    /// node is an element node whose children hold the events for the fragment's children.
+    ///
+    /// Elements are created as if they were placed into an HTML element. Use
+    /// [`VirtualNode::create_dom_node_within`] to create nodes that will be placed into an SVG or
+    /// MathML element.
+    #[cfg(target_arch = "wasm32")]
     pub fn create_dom_node(&self, events: &mut VirtualEvents) -> (Node, VirtualEventNode) {
+        self.create_dom_node_in_namespace(None, events)
+    }
+
+    /// Create and return a [`web_sys::Node`] that will be placed into the `parent` DOM node,
+    /// along with its events.
+    ///
+    /// The created elements inherit the namespace of the parent, so an `<a>` that will be placed
+    /// into an `<svg>` is created as an SVG element.
+    ///
+    /// A declarative shadow root, such as `<template shadowrootmode="open">`, places its children
+    /// into a shadow root that gets attached to the parent.
+    #[cfg(target_arch = "wasm32")]
+    pub fn create_dom_node_within(
+        &self,
+        parent: &Node,
+        events: &mut VirtualEvents,
+    ) -> (Node, VirtualEventNode) {
+        let (node, events_node) =
+            self.create_dom_node_in_namespace(namespace::dom_children_namespace(parent), events);
+
+        if let VirtualNode::Element(element_node) = self.resolve_memos() {
+            if element_node.is_declarative_shadow_root() {
+                create_element::attach_declarative_shadow_root(parent, node.unchecked_ref());
+            }
+        }
+
+        (node, events_node)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn create_dom_node_in_namespace(
+        &self,
+        parent_namespace: Option<&'static str>,
+        events: &mut VirtualEvents,
+    ) -> (Node, VirtualEventNode) {
         match self {
             VirtualNode::Text(text_node) => (
                 text_node.create_text_node().into(),
                 events.create_text_node(),
             ),
+            // Just like a text node, a comment node cannot have events.
+            VirtualNode::Comment(comment_node) => (
+                comment_node.create_comment_node().into(),
+                events.create_text_node(),
+            ),
             VirtualNode::Element(element_node) => {
-                let (elem, events) = element_node.create_element_node(events);
+                let (elem, events) = element_node.create_element_node(parent_namespace, events);
                 (elem.into(), events)
             }
+            VirtualNode::Fragment(_) => {
+                let (fragment, events) =
+                    create_element::create_fragment_node(self, parent_namespace, events);
+                (fragment.into(), events)
+            }
+            VirtualNode::Memo(memo) => memo
+                .view()
+                .create_dom_node_in_namespace(parent_namespace, events),
         }
     }
 
@@ -181,6 +398,30 @@ impl VirtualNode {
     }
 }
 
+impl VElement {
+    /// The nodes that this element's children place into the DOM, with any fragments flattened.
+    ///
+    /// See [`VirtualNode::dom_nodes`].
+    pub fn dom_children(&self) -> Vec<&VirtualNode> {
+        let mut nodes = vec![];
+        for child in self.children.iter() {
+            push_dom_nodes(child, &mut nodes);
+        }
+        nodes
+    }
+}
+
+fn push_dom_nodes<'a>(node: &'a VirtualNode, nodes: &mut Vec<&'a VirtualNode>) {
+    match node {
+        VirtualNode::Fragment(children) => {
+            for child in children.iter() {
+                push_dom_nodes(child, nodes);
+            }
+        }
+        _ => nodes.push(node),
+    }
+}
+
 /// A trait with common functionality for rendering front-end views.
 pub trait View {
     /// Render a VirtualNode, or any IntoIter<VirtualNode>
@@ -202,6 +443,12 @@ impl From<VText> for VirtualNode {
     }
 }
 
+impl From<VComment> for VirtualNode {
+    fn from(other: VComment) -> Self {
+        VirtualNode::Comment(other)
+    }
+}
+
 impl From<VElement> for VirtualNode {
     fn from(other: VElement) -> Self {
         VirtualNode::Element(other)
@@ -225,8 +472,17 @@ impl IntoIterator for VirtualNode {
     // TODO: ::std::iter::Once<VirtualNode> to avoid allocation
     type IntoIter = ::std::vec::IntoIter<VirtualNode>;
 
+    /// A fragment iterates over its children, with any nested fragments flattened. Every other
+    /// node iterates over itself.
     fn into_iter(self) -> Self::IntoIter {
-        vec![self].into_iter()
+        match self {
+            VirtualNode::Fragment(children) => children
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .into_iter(),
+            node => vec![node].into_iter(),
+        }
     }
 }
 
@@ -241,6 +497,9 @@ impl fmt::Debug for VirtualNode {
         match self {
             VirtualNode::Element(e) => write!(f, "Node::{:?}", e),
             VirtualNode::Text(t) => write!(f, "Node::{:?}", t),
+            VirtualNode::Comment(c) => write!(f, "Node::{:?}", c),
+            VirtualNode::Fragment(children) => write!(f, "Node::Fragment({:?})", children),
+            VirtualNode::Memo(m) => write!(f, "Node::{:?}", m),
         }
     }
 }
@@ -251,6 +510,9 @@ impl fmt::Display for VirtualNode {
         match self {
             VirtualNode::Element(element) => write!(f, "{}", element),
             VirtualNode::Text(text) => write!(f, "{}", text),
+            VirtualNode::Comment(comment) => write!(f, "{}", comment),
+            VirtualNode::Fragment(_) => write_children(self.dom_nodes(), f),
+            VirtualNode::Memo(memo) => write!(f, "{}", memo),
         }
     }
 }
@@ -311,4 +573,201 @@ mod tests {
 
         assert_eq!(button.to_string(), expected);
     }
+
+    /// Verify that namespaced attributes keep their prefix when rendered.
+    #[test]
+    fn namespaced_attribute_rendered_with_prefix() {
+        let mut use_elem = VElement::new("use");
+        use_elem.attrs.insert("xlink:href".into(), "#icon".into());
+
+        let expected = r##"<use xlink:href="#icon">"##;
+        assert_eq!(VirtualNode::Element(use_elem).to_string(), expected);
+    }
+
+    /// Verify that a declarative shadow root is rendered as a `<template>` inside of its host,
+    /// which is where the browser's HTML parser looks for it.
+    #[test]
+    fn declarative_shadow_root_rendered_as_template() {
+        let mut template = VElement::new("template");
+        template
+            .attrs
+            .insert("shadowrootmode".into(), "open".into());
+        template.children.push(VirtualNode::element("slot"));
+
+        let mut host = VElement::new("my-widget");
+        host.children.push(template.into());
+        host.children.push(VirtualNode::text("Light DOM"));
+
+        let expected = r#"<my-widget><template shadowrootmode="open"><slot></slot></template>Light DOM</my-widget>"#;
+        assert_eq!(VirtualNode::Element(host).to_string(), expected);
+    }
+
+    /// Verify that classes are rendered into the class attribute, after any classes from a
+    /// manually inserted class attribute.
+    #[test]
+    fn classes_rendered_into_class_attribute() {
+        let mut div = VElement::new("div");
+        div.classes = Classes::from("btn primary");
+
+        let mut div = VirtualNode::Element(div);
+        assert_eq!(div.to_string(), r#"<div class="btn primary"></div>"#);
+
+        let div = div.as_velement_mut().unwrap();
+        div.attrs.insert("class".into(), "large btn".into());
+
+        assert_eq!(div.to_string(), r#"<div class="large btn primary"></div>"#);
+    }
+
+    /// Verify that styles are rendered into the style attribute, after any styles from a manually
+    /// inserted style attribute.
+    #[test]
+    fn styles_rendered_into_style_attribute() {
+        let mut div = VElement::new("div");
+        div.styles = Styles::from([("color", "red"), ("width", "10px")]);
+
+        let mut div = VirtualNode::Element(div);
+        let expected = r#"<div style="color: red; width: 10px"></div>"#;
+        assert_eq!(div.to_string(), expected);
+
+        let div = div.as_velement_mut().unwrap();
+        div.attrs.insert("style".into(), "margin: 0;".into());
+
+        let expected = r#"<div style="margin: 0; color: red; width: 10px"></div>"#;
+        assert_eq!(div.to_string(), expected);
+    }
+
+    /// Verify that attribute values get escaped so that they cannot break out of their quotes.
+    #[test]
+    fn escapes_attribute_values() {
+        let mut div = VElement::new("div");
+        div.attrs
+            .insert("title".into(), r#""><script>'&'</script>"#.into());
+
+        let expected =
+            r#"<div title="&quot;&gt;&lt;script&gt;&#39;&amp;&#39;&lt;/script&gt;"></div>"#;
+
+        assert_eq!(VirtualNode::Element(div).to_string(), expected);
+    }
+
+    /// Verify that text children get escaped so that they cannot create elements.
+    #[test]
+    fn escapes_text_children() {
+        let mut div = VElement::new("div");
+        div.children.push(VirtualNode::text("</div><img src=x>"));
+
+        let expected = "<div>&lt;/div&gt;&lt;img src=x&gt;</div>";
+
+        assert_eq!(VirtualNode::Element(div).to_string(), expected);
+    }
+
+    /// Verify that the text within script and style elements is not escaped, since the browser
+    /// does not decode character references within them, and that it can be parsed back into the
+    /// same text.
+    #[test]
+    fn raw_text_children_not_escaped() {
+        for tag in ["script", "style"] {
+            let mut element = VElement::new(tag);
+            element.children.push(VirtualNode::text("a < b && c > d"));
+            let element = VirtualNode::Element(element);
+
+            let html = element.to_string();
+            assert_eq!(html, format!("<{}>a < b && c > d</{}>", tag, tag));
+            assert_eq!(VirtualNode::from_html(&html).unwrap(), vec![element]);
+        }
+    }
+
+    /// Verify that we render a separator in between neighboring text nodes so that the browser
+    /// does not merge them when parsing our HTML.
+    #[test]
+    fn separates_neighboring_text_nodes() {
+        let mut div = VElement::new("div");
+        div.children.push(VirtualNode::text("Hello"));
+        div.children.push(VirtualNode::text("world"));
+        div.children.push(VirtualNode::element("br"));
+        div.children.push(VirtualNode::text("!"));
+
+        let expected = "<div>Hello<!--percy:ptns-->world<br>!</div>";
+
+        assert_eq!(VirtualNode::Element(div).to_string(), expected);
+    }
+
+    /// Verify that the dangerous inner HTML is rendered without being escaped.
+    #[test]
+    fn renders_dangerous_inner_html_unescaped() {
+        let mut div = VElement::new("div");
+        div.special_attributes.dangerous_inner_html = Some("<span>Hi & bye</span>".to_string());
+
+        let expected = "<div><span>Hi & bye</span></div>";
+
+        assert_eq!(VirtualNode::Element(div).to_string(), expected);
+    }
+
+    /// Verify that the dangerous inner HTML takes the place of the element's children, the same
+    /// way that it does when we create a DOM element.
+    #[test]
+    fn dangerous_inner_html_replaces_children() {
+        let mut div = VElement::new("div");
+        div.children.push(VirtualNode::text("Ignored"));
+        div.special_attributes.dangerous_inner_html = Some("<b>Used</b>".to_string());
+
+        assert_eq!(
+            VirtualNode::Element(div).to_string(),
+            "<div><b>Used</b></div>"
+        );
+    }
+
+    /// Verify that nested fragments are flattened when creating a fragment and when iterating
+    /// over a fragment.
+    #[test]
+    fn flattens_nested_fragments() {
+        let fragment = VirtualNode::fragment(vec![
+            VirtualNode::text("a"),
+            VirtualNode::Fragment(vec![VirtualNode::text("b"), VirtualNode::text("c")]),
+        ]);
+        let expected = vec![
+            VirtualNode::text("a"),
+            VirtualNode::text("b"),
+            VirtualNode::text("c"),
+        ];
+
+        assert_eq!(fragment.as_fragment_ref().unwrap(), &expected);
+
+        let nested = VirtualNode::Fragment(vec![VirtualNode::Fragment(vec![
+            VirtualNode::text("a"),
+            VirtualNode::text("b"),
+        ])]);
+        assert_eq!(nested.into_iter().collect::<Vec<_>>(), expected[..2]);
+    }
+
+    /// Verify that a fragment renders its children, and that text nodes are separated across the
+    /// boundaries of nested fragments.
+    #[test]
+    fn fragment_to_string() {
+        let mut div = VElement::new("div");
+        div.children = vec![
+            VirtualNode::text("a"),
+            VirtualNode::Fragment(vec![VirtualNode::text("b"), VirtualNode::element("br")]),
+        ];
+
+        let fragment = VirtualNode::Fragment(vec![div.into(), VirtualNode::text("c")]);
+
+        assert_eq!(fragment.to_string(), "<div>a<!--percy:ptns-->b<br></div>c");
+    }
+
+    /// Verify that comments get rendered, and that a comment in between two text nodes already
+    /// separates them so that they don't need a separator.
+    #[test]
+    fn comment_to_string() {
+        let mut div = VElement::new("div");
+        div.children = vec![
+            VirtualNode::text("a"),
+            VirtualNode::comment(" placeholder "),
+            VirtualNode::text("b"),
+        ];
+
+        assert_eq!(
+            VirtualNode::from(div).to_string(),
+            "<div>a<!-- placeholder -->b</div>"
+        );
+    }
 }
@@ -0,0 +1,154 @@
+//! XML namespaces, such as the SVG namespace of the elements inside of an `<svg>` or the XLink
+//! namespace of the `xlink:href` in `<use xlink:href="#icon" />`.
+//!
+//! An element's namespace depends on where it is in the tree, not just on its tag. The `<a>` in
+//! `<svg><a></a></svg>` is an SVG element, while the `<a>` in `<div><a></a></div>` is an HTML
+//! element.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue};
+#[cfg(target_arch = "wasm32")]
+use web_sys::{Element, Node};
+
+/// The SVG namespace, used for `<svg>` and its descendants.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+
+/// The MathML namespace, used for `<math>` and its descendants.
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// The XLink namespace, used for attributes such as `xlink:href`.
+pub const XLINK_NAMESPACE: &str = "http://www.w3.org/1999/xlink";
+
+/// The XML namespace, used for attributes such as `xml:lang`.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// The XMLNS namespace, used for namespace declarations such as `xmlns:xlink`.
+pub const XMLNS_NAMESPACE: &str = "http://www.w3.org/2000/xmlns/";
+
+/// The namespace that an element gets created in, given the namespace that its parent's children
+/// get created in. `None` is the HTML namespace.
+///
+/// `<svg>` and `<math>` start the SVG and MathML namespaces, and their descendants inherit them.
+///
+/// Outside of an `<svg>`, tags that only exist in SVG, such as `<circle>`, are still created in
+/// the SVG namespace so that they can be created on their own and then placed into an `<svg>`.
+///
+/// ```
+/// # use virtual_node::namespace::{element_namespace, SVG_NAMESPACE};
+/// assert_eq!(element_namespace("a", Some(SVG_NAMESPACE)), Some(SVG_NAMESPACE));
+/// assert_eq!(element_namespace("a", None), None);
+/// assert_eq!(element_namespace("circle", None), Some(SVG_NAMESPACE));
+/// ```
+pub fn element_namespace(tag: &str, parent_namespace: Option<&str>) -> Option<&'static str> {
+    match tag {
+        "svg" => return Some(SVG_NAMESPACE),
+        "math" => return Some(MATHML_NAMESPACE),
+        _ => {}
+    };
+
+    match parent_namespace.and_then(known_namespace) {
+        Some(namespace) => Some(namespace),
+        None if html_validation::is_svg_namespace(tag) => Some(SVG_NAMESPACE),
+        None => None,
+    }
+}
+
+/// The namespace that an element's children get created in.
+///
+/// The children of an SVG `<foreignObject>` are back in the HTML namespace.
+pub fn children_namespace(tag: &str, namespace: Option<&str>) -> Option<&'static str> {
+    match namespace.and_then(known_namespace) {
+        Some(SVG_NAMESPACE) if tag == "foreignObject" => None,
+        namespace => namespace,
+    }
+}
+
+/// The namespace that a DOM node's children get created in.
+///
+/// Used when creating nodes that will be placed into an existing DOM node, such as when patching.
+#[cfg(target_arch = "wasm32")]
+pub fn dom_children_namespace(parent: &Node) -> Option<&'static str> {
+    let element = parent.dyn_ref::<Element>()?;
+    children_namespace(&element.local_name(), element.namespace_uri().as_deref())
+}
+
+/// The namespace of an attribute, based on its prefix.
+///
+/// Attributes without a known prefix, such as `href` or `data-id`, are not in a namespace.
+///
+/// ```
+/// # use virtual_node::namespace::{attribute_namespace, XLINK_NAMESPACE, XMLNS_NAMESPACE};
+/// assert_eq!(attribute_namespace("xlink:href"), Some(XLINK_NAMESPACE));
+/// assert_eq!(attribute_namespace("xmlns:xlink"), Some(XMLNS_NAMESPACE));
+/// assert_eq!(attribute_namespace("href"), None);
+/// ```
+pub fn attribute_namespace(name: &str) -> Option<&'static str> {
+    if name == "xmlns" {
+        return Some(XMLNS_NAMESPACE);
+    }
+
+    let (prefix, _local_name) = split_prefix(name)?;
+    match prefix {
+        "xlink" => Some(XLINK_NAMESPACE),
+        "xml" => Some(XML_NAMESPACE),
+        "xmlns" => Some(XMLNS_NAMESPACE),
+        _ => None,
+    }
+}
+
+/// Set an attribute, using `setAttributeNS` if the attribute is in a namespace.
+#[cfg(target_arch = "wasm32")]
+pub fn set_attribute(element: &Element, name: &str, value: &str) -> Result<(), JsValue> {
+    match attribute_namespace(name) {
+        Some(namespace) => element.set_attribute_ns(Some(namespace), name, value),
+        None => element.set_attribute(name, value),
+    }
+}
+
+/// Remove an attribute, using `removeAttributeNS` if the attribute is in a namespace.
+#[cfg(target_arch = "wasm32")]
+pub fn remove_attribute(element: &Element, name: &str) -> Result<(), JsValue> {
+    match attribute_namespace(name) {
+        Some(namespace) => {
+            let local_name = split_prefix(name).map(|(_, local)| local).unwrap_or(name);
+            element.remove_attribute_ns(Some(namespace), local_name)
+        }
+        None => element.remove_attribute(name),
+    }
+}
+
+fn known_namespace(namespace: &str) -> Option<&'static str> {
+    match namespace {
+        SVG_NAMESPACE => Some(SVG_NAMESPACE),
+        MATHML_NAMESPACE => Some(MATHML_NAMESPACE),
+        _ => None,
+    }
+}
+
+/// Split a prefixed name such as `xlink:href` into `("xlink", "href")`.
+fn split_prefix(name: &str) -> Option<(&str, &str)> {
+    let colon = name.find(':')?;
+    Some((&name[..colon], &name[colon + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that descendants of `<svg>` and `<math>` inherit their namespace, until an SVG
+    /// `<foreignObject>`.
+    #[test]
+    fn namespace_inheritance() {
+        let svg = element_namespace("svg", None);
+        assert_eq!(svg, Some(SVG_NAMESPACE));
+        assert_eq!(element_namespace("title", children_namespace("svg", svg)), svg);
+
+        let foreign_object = element_namespace("foreignObject", svg);
+        assert_eq!(foreign_object, Some(SVG_NAMESPACE));
+        assert_eq!(children_namespace("foreignObject", foreign_object), None);
+
+        let math = element_namespace("math", None);
+        assert_eq!(math, Some(MATHML_NAMESPACE));
+        assert_eq!(element_namespace("mi", children_namespace("math", math)), math);
+    }
+}
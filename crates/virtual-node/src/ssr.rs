@@ -0,0 +1,585 @@
+//! Render a `VirtualNode` to HTML on the server without needing to build up the entire page in
+//! one `String`.
+//!
+//! `render_to_writer` renders into any `std::io::Write`, and `render_to_chunks` returns an
+//! iterator of byte chunks that a web server can start sending to the client before the rest of
+//! the tree has been rendered.
+//!
+//! With the `stream` feature the chunks are also a `futures_core::Stream`, for async web servers.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+
+use crate::escape_html::{is_raw_text_element, RawText};
+use crate::velement::write_attributes;
+use crate::{AttributeValue, VElement, VirtualNode, TEXT_SEPARATOR};
+
+/// Render a `VirtualNode` to HTML, writing it to the `writer` as it gets rendered.
+///
+/// You'll typically want to wrap unbuffered writers, such as a `TcpStream`, in a
+/// `std::io::BufWriter`.
+///
+/// ```
+/// # use virtual_node::{ssr::render_to_writer, VirtualNode};
+/// let mut html = vec![];
+/// render_to_writer(&VirtualNode::text("Hello"), &mut html).unwrap();
+///
+/// assert_eq!(html, b"Hello");
+/// ```
+pub fn render_to_writer<W: io::Write>(node: &VirtualNode, mut writer: W) -> io::Result<()> {
+    write!(writer, "{}", node)
+}
+
+/// Render a `VirtualNode` to HTML lazily, one chunk of roughly `chunk_size` bytes at a time.
+///
+/// Every chunk is rendered when it is requested, so a web server can start sending the first
+/// chunks to the client before the rest of the tree has been rendered. A `chunk_size` of `0` is
+/// treated as `1`.
+///
+/// The returned iterator owns the `VirtualNode`. With the `stream` feature it is also a
+/// `futures_core::Stream` that can be used as a response body. Rendering a chunk never waits, so
+/// every chunk is ready as soon as it is polled.
+///
+/// ```
+/// # use virtual_node::{ssr::render_to_chunks, VirtualNode};
+/// let chunks: Vec<Vec<u8>> = render_to_chunks(VirtualNode::text("Hello"), 8 * 1024).collect();
+///
+/// assert_eq!(chunks, vec![b"Hello".to_vec()]);
+/// ```
+pub fn render_to_chunks(node: VirtualNode, chunk_size: usize) -> HtmlChunks {
+    HtmlChunks::new(String::new(), node, String::new(), chunk_size)
+}
+
+/// The parts of an HTML document that surround your application's `VirtualNode`.
+///
+/// ```
+/// # use virtual_node::{ssr::DocumentShell, VirtualNode};
+/// let mut shell = DocumentShell::default();
+/// shell.html_attrs.insert("lang".to_string(), "en".into());
+/// shell.head = "<title>My App</title>".to_string();
+///
+/// let mut html = vec![];
+/// shell.render_to_writer(&VirtualNode::element("main"), &mut html).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(html).unwrap(),
+///     r#"<!DOCTYPE html><html lang="en"><head><title>My App</title></head><body><main></main></body></html>"#
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct DocumentShell {
+    /// Attributes for the `<html>` element, such as `lang`.
+    pub html_attrs: HashMap<String, AttributeValue>,
+    /// HTML that gets rendered inside of the `<head>` element, such as `<title>` and `<link>`
+    /// elements.
+    ///
+    /// This is rendered as is, without escaping, so it should never contain untrusted input.
+    pub head: String,
+    /// Attributes for the `<body>` element.
+    pub body_attrs: HashMap<String, AttributeValue>,
+    /// HTML that gets rendered at the end of the `<body>` element, after your `VirtualNode`,
+    /// such as `<script>` elements.
+    ///
+    /// This is rendered as is, without escaping, so it should never contain untrusted input.
+    pub body_end: String,
+}
+
+impl DocumentShell {
+    /// Render a complete HTML document with the `body` inside of the `<body>` element, writing it
+    /// to the `writer` as it gets rendered.
+    pub fn render_to_writer<W: io::Write>(
+        &self,
+        body: &VirtualNode,
+        mut writer: W,
+    ) -> io::Result<()> {
+        writer.write_all(self.start().as_bytes())?;
+        render_to_writer(body, &mut writer)?;
+        writer.write_all(self.end().as_bytes())
+    }
+
+    /// Render a complete HTML document with the `body` inside of the `<body>` element, lazily,
+    /// one chunk at a time. See [`render_to_chunks`].
+    pub fn render_to_chunks(&self, body: VirtualNode, chunk_size: usize) -> HtmlChunks {
+        HtmlChunks::new(self.start(), body, self.end(), chunk_size)
+    }
+
+    fn start(&self) -> String {
+        let mut start = "<!DOCTYPE html><html".to_string();
+        write_attributes(&self.html_attrs, &mut start).unwrap();
+        write!(start, "><head>{}</head><body", self.head).unwrap();
+        write_attributes(&self.body_attrs, &mut start).unwrap();
+        start += ">";
+
+        start
+    }
+
+    fn end(&self) -> String {
+        format!("{}</body></html>", self.body_end)
+    }
+}
+
+/// An iterator over the chunks of HTML of a rendered `VirtualNode`.
+///
+/// Created by [`render_to_chunks`] and [`DocumentShell::render_to_chunks`].
+pub struct HtmlChunks {
+    root: VirtualNode,
+    start: String,
+    end: String,
+    chunk_size: usize,
+    progress: Progress,
+    /// The elements that we've rendered the opening tag for but not the closing tag for.
+    open_elements: Vec<OpenElement>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Progress {
+    NotStarted,
+    Rendering,
+    Finished,
+}
+
+struct OpenElement {
+    /// This element's index in its parent's children.
+    /// Unused for the root element.
+    idx_in_parent: usize,
+    next_child_idx: usize,
+    previous_node_was_text: bool,
+}
+
+impl HtmlChunks {
+    fn new(start: String, mut root: VirtualNode, end: String, chunk_size: usize) -> Self {
+        flatten_nested_fragments(&mut root);
+
+        HtmlChunks {
+            root,
+            start,
+            end,
+            // Every chunk needs at least one byte, otherwise we would never make progress.
+            chunk_size: chunk_size.max(1),
+            progress: Progress::NotStarted,
+            open_elements: vec![],
+        }
+    }
+
+    /// Render the next opening tag, closing tag or text node.
+    fn render_next(&mut self, html: &mut String) {
+        let HtmlChunks {
+            root,
+            open_elements,
+            ..
+        } = self;
+
+        match self.progress {
+            Progress::NotStarted => {
+                html.push_str(&self.start);
+                self.progress = Progress::Rendering;
+
+                if render_node_start(root, html) {
+                    open_elements.push(OpenElement::new(0));
+                    return;
+                }
+            }
+            Progress::Rendering => {
+                let (children, element) = open_children(root, open_elements);
+                let open = open_elements.last_mut().unwrap();
+
+                if let Some(child) = children.get(open.next_child_idx) {
+                    let child_idx = open.next_child_idx;
+                    open.next_child_idx += 1;
+
+                    let child = child.resolve_memos();
+
+                    // The text within a raw text element is written as is, just like in
+                    // `VElement`'s `Display`.
+                    let raw_text_tag = element
+                        .map(|element| element.tag.as_str())
+                        .filter(|tag| is_raw_text_element(tag));
+                    if let (Some(tag), VirtualNode::Text(text)) = (raw_text_tag, child) {
+                        let text = RawText {
+                            text: &text.text,
+                            tag,
+                        };
+                        write!(html, "{}", text).unwrap();
+                        return;
+                    }
+
+                    // Neighboring text nodes are separated, just like in `VElement`'s `Display`.
+                    let is_text = child.as_vtext_ref().is_some();
+                    if is_text && open.previous_node_was_text {
+                        write!(html, "<!--{}-->", TEXT_SEPARATOR).unwrap();
+                    }
+                    open.previous_node_was_text = is_text;
+
+                    if render_node_start(child, html) {
+                        open_elements.push(OpenElement::new(child_idx));
+                    }
+                    return;
+                }
+
+                // A root fragment does not have any tags of its own.
+                if let Some(element) = element {
+                    element.write_close_tag(html).unwrap();
+                }
+                open_elements.pop();
+
+                if !open_elements.is_empty() {
+                    return;
+                }
+            }
+            Progress::Finished => return,
+        };
+
+        html.push_str(&self.end);
+        self.progress = Progress::Finished;
+    }
+}
+
+impl Iterator for HtmlChunks {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.progress == Progress::Finished {
+            return None;
+        }
+
+        let mut html = String::with_capacity(self.chunk_size);
+        while html.len() < self.chunk_size && self.progress != Progress::Finished {
+            self.render_next(&mut html);
+        }
+
+        Some(html.into_bytes())
+    }
+}
+
+/// Every chunk is rendered as soon as it is polled.
+#[cfg(feature = "stream")]
+impl futures_core::Stream for HtmlChunks {
+    type Item = Vec<u8>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        std::task::Poll::Ready(self.get_mut().next())
+    }
+}
+
+impl OpenElement {
+    fn new(idx_in_parent: usize) -> Self {
+        OpenElement {
+            idx_in_parent,
+            next_child_idx: 0,
+            previous_node_was_text: false,
+        }
+    }
+}
+
+/// Render a text node, a comment node or an element's opening tag.
+///
+/// Returns true if the node is an element or fragment whose children still need to be rendered,
+/// in which case the closing tag has not been rendered yet.
+fn render_node_start(node: &VirtualNode, html: &mut String) -> bool {
+    match node {
+        VirtualNode::Text(text) => {
+            write!(html, "{}", text).unwrap();
+            false
+        }
+        VirtualNode::Comment(comment) => {
+            write!(html, "{}", comment).unwrap();
+            false
+        }
+        VirtualNode::Element(element) => {
+            element.write_open_tag(html).unwrap();
+
+            // A portal's children are only rendered in the browser, just like in `VElement`'s
+            // `Display`.
+            let has_children =
+                !element.children.is_empty() && element.special_attributes.portal().is_none();
+
+            if let Some(inner_html) = &element.special_attributes.dangerous_inner_html {
+                html.push_str(inner_html);
+            } else if has_children {
+                return true;
+            }
+
+            element.write_close_tag(html).unwrap();
+            false
+        }
+        VirtualNode::Fragment(children) => !children.is_empty(),
+        VirtualNode::Memo(memo) => render_node_start(memo.view(), html),
+    }
+}
+
+/// Get the children of the innermost element that we've rendered the opening tag for but not
+/// the closing tag, along with that element.
+///
+/// The element is `None` if the innermost open node is a root fragment.
+fn open_children<'a>(
+    root: &'a VirtualNode,
+    open_elements: &[OpenElement],
+) -> (&'a [VirtualNode], Option<&'a VElement>) {
+    let (mut children, mut element) = match root.resolve_memos() {
+        VirtualNode::Fragment(children) => (children.as_slice(), None),
+        root => {
+            let root = root.as_velement_ref().unwrap();
+            (root.children.as_slice(), Some(root))
+        }
+    };
+
+    for open in open_elements.iter().skip(1) {
+        let open_element = children[open.idx_in_parent]
+            .resolve_memos()
+            .as_velement_ref()
+            .unwrap();
+
+        children = &open_element.children;
+        element = Some(open_element);
+    }
+
+    (children, element)
+}
+
+/// Replace the fragments within every element's children with the fragment's children, so that
+/// every open element can be found using its index in its parent's children.
+///
+/// Memos are rendered so that the fragments within their views can be flattened too.
+fn flatten_nested_fragments(node: &mut VirtualNode) {
+    let children = match node {
+        VirtualNode::Text(_) | VirtualNode::Comment(_) => return,
+        VirtualNode::Element(element) => &mut element.children,
+        VirtualNode::Fragment(children) => children,
+        VirtualNode::Memo(memo) => {
+            // A view is only shared after a memo reused it while being diffed in the browser.
+            if let Some(view) = memo.view_mut() {
+                flatten_nested_fragments(view);
+            }
+            return;
+        }
+    };
+
+    if children
+        .iter()
+        .any(|child| child.as_fragment_ref().is_some())
+    {
+        *children = std::mem::take(children).into_iter().flatten().collect();
+    }
+
+    for child in children.iter_mut() {
+        flatten_nested_fragments(child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VText;
+
+    /// Verify that the chunks add up to the same HTML that `.to_string()` renders.
+    #[test]
+    fn chunks_match_to_string() {
+        for chunk_size in [1, 7, 100, 10_000] {
+            let node = nested_node();
+            let expected = node.to_string();
+
+            let chunks: Vec<u8> = render_to_chunks(node, chunk_size).flatten().collect();
+
+            assert_eq!(String::from_utf8(chunks).unwrap(), expected);
+        }
+    }
+
+    /// Verify that the HTML is split into chunks of at least the chunk size, except for the last.
+    #[test]
+    fn respects_chunk_size() {
+        let chunks: Vec<Vec<u8>> = render_to_chunks(nested_node(), 10).collect();
+
+        assert!(chunks.len() > 1);
+        for chunk in chunks.iter().take(chunks.len() - 1) {
+            assert!(chunk.len() >= 10);
+        }
+    }
+
+    /// Verify that a chunk size of zero still renders the HTML, instead of yielding empty chunks
+    /// forever.
+    #[test]
+    fn zero_chunk_size() {
+        let chunks: Vec<u8> = render_to_chunks(nested_node(), 0).flatten().collect();
+
+        assert_eq!(
+            String::from_utf8(chunks).unwrap(),
+            nested_node().to_string()
+        );
+    }
+
+    /// Verify that the chunks can be polled as a stream.
+    #[cfg(feature = "stream")]
+    #[test]
+    fn stream_chunks() {
+        use futures_core::Stream;
+        use std::pin::Pin;
+        use std::task::{Context, Poll, Waker};
+
+        let mut chunks = render_to_chunks(nested_node(), 7);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut html = vec![];
+        while let Poll::Ready(Some(chunk)) = Pin::new(&mut chunks).poll_next(&mut cx) {
+            html.extend(chunk);
+        }
+
+        assert_eq!(String::from_utf8(html).unwrap(), nested_node().to_string());
+    }
+
+    /// Verify that we can render a text node root.
+    #[test]
+    fn text_root_chunks() {
+        let chunks: Vec<Vec<u8>> = render_to_chunks(VirtualNode::text("a < b"), 100).collect();
+
+        assert_eq!(chunks, vec![b"a &lt; b".to_vec()]);
+    }
+
+    /// Verify that we can render a fragment root, including fragments nested within it.
+    #[test]
+    fn fragment_chunks_match_to_string() {
+        let fragment = || {
+            VirtualNode::Fragment(vec![
+                nested_node(),
+                VirtualNode::text("Between"),
+                VirtualNode::comment("Comment"),
+                VirtualNode::Fragment(vec![VirtualNode::text("Nested"), div_with_em()]),
+            ])
+        };
+        let expected = fragment().to_string();
+
+        for chunk_size in [1, 7, 10_000] {
+            let chunks: Vec<u8> = render_to_chunks(fragment(), chunk_size).flatten().collect();
+
+            assert_eq!(String::from_utf8(chunks).unwrap(), expected);
+        }
+    }
+
+    /// Verify that we can render memos, including a memo root and fragments within a memo's view.
+    #[test]
+    fn memo_chunks_match_to_string() {
+        let memo = || {
+            VirtualNode::memo(1, || {
+                let mut div = VElement::new("div");
+                div.children.push(VirtualNode::memo(2, div_with_em));
+                div.children.push(VirtualNode::Fragment(vec![
+                    VirtualNode::text("Nested"),
+                    nested_node(),
+                ]));
+                div.into()
+            })
+        };
+        let expected = memo().to_string();
+
+        for chunk_size in [1, 7, 10_000] {
+            let chunks: Vec<u8> = render_to_chunks(memo(), chunk_size).flatten().collect();
+
+            assert_eq!(String::from_utf8(chunks).unwrap(), expected);
+        }
+    }
+
+    /// Verify that portals are rendered without their children, since their children get placed
+    /// into the portal's target when the page is hydrated.
+    #[test]
+    fn portal_renders_without_children() {
+        let portal = || {
+            let mut portal = div_with_em();
+            portal
+                .as_velement_mut()
+                .unwrap()
+                .special_attributes
+                .set_portal("body");
+            portal
+        };
+
+        assert_eq!(portal().to_string(), "<div></div>");
+
+        let chunks: Vec<u8> = render_to_chunks(portal(), 1).flatten().collect();
+        assert_eq!(String::from_utf8(chunks).unwrap(), "<div></div>");
+    }
+
+    /// Verify that the text within script and style elements is rendered as is, the same way that
+    /// `.to_string()` renders it.
+    #[test]
+    fn raw_text_chunks_match_to_string() {
+        let script = || {
+            let mut script = VElement::new("script");
+            script.children.push(VirtualNode::text("if (a < b) {}"));
+            script.children.push(VirtualNode::text("'</script>'"));
+            VirtualNode::from(script)
+        };
+        let expected = r"<script>if (a < b) {}'<\/script>'</script>";
+        assert_eq!(script().to_string(), expected);
+
+        let chunks: Vec<u8> = render_to_chunks(script(), 1).flatten().collect();
+        assert_eq!(String::from_utf8(chunks).unwrap(), expected);
+    }
+
+    /// Verify that the writer receives the same HTML that `.to_string()` renders.
+    #[test]
+    fn writer_matches_to_string() {
+        let node = nested_node();
+
+        let mut html = vec![];
+        render_to_writer(&node, &mut html).unwrap();
+
+        assert_eq!(String::from_utf8(html).unwrap(), node.to_string());
+    }
+
+    /// Verify that the document shell surrounds the rendered node, both when writing and when
+    /// rendering chunks.
+    #[test]
+    fn document_shell() {
+        let mut shell = DocumentShell::default();
+        shell.body_attrs.insert("class".to_string(), "dark".into());
+        shell.head = "<title>Title</title>".to_string();
+        shell.body_end = "<script src=/app.js></script>".to_string();
+
+        let expected = r#"<!DOCTYPE html><html><head><title>Title</title></head><body class="dark"><div><em>Hi</em></div><script src=/app.js></script></body></html>"#;
+
+        let mut html = vec![];
+        shell.render_to_writer(&div_with_em(), &mut html).unwrap();
+        assert_eq!(String::from_utf8(html).unwrap(), expected);
+
+        let chunks: Vec<u8> = shell.render_to_chunks(div_with_em(), 3).flatten().collect();
+        assert_eq!(String::from_utf8(chunks).unwrap(), expected);
+    }
+
+    fn div_with_em() -> VirtualNode {
+        let mut em = VElement::new("em");
+        em.children.push(VirtualNode::text("Hi"));
+
+        let mut div = VElement::new("div");
+        div.children.push(em.into());
+
+        div.into()
+    }
+
+    fn nested_node() -> VirtualNode {
+        let mut inner_html = VElement::new("section");
+        inner_html.special_attributes.dangerous_inner_html = Some("<b>Raw</b>".to_string());
+
+        let mut list = VElement::new("ul");
+        for idx in 0..3 {
+            let mut item = VElement::new("li");
+            item.attrs
+                .insert("id".to_string(), format!("item-{}", idx).into());
+            item.children.push(VText::new("First").into());
+            item.children.push(VText::new("Second").into());
+            item.children.push(VirtualNode::element("br"));
+            list.children.push(item.into());
+        }
+
+        let mut root = VElement::new("div");
+        root.attrs.insert("disabled".to_string(), true.into());
+        root.children.push(list.into());
+        root.children.push(VirtualNode::element("span"));
+        root.children.push(inner_html.into());
+        root.children.push(VirtualNode::text("Last"));
+
+        root.into()
+    }
+}
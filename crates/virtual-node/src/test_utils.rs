@@ -1,6 +1,112 @@
 //! A collection of functions that are useful for unit testing your html! views.
 
-use crate::VirtualNode;
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::event::{
+    DragEvent, EventHandler, EventListenerTarget, EventName, FocusEvent, InputEvent, KeyboardEvent,
+    MouseEvent, PointerEvent, SubmitEvent, WheelEvent,
+};
+use crate::{VElement, VirtualNode};
+
+use self::selector::Selector;
+pub use self::tree_diff::TreeDiff;
+
+mod selector;
+mod snapshot;
+mod tree_diff;
+
+/// Assert that two [`VirtualNode`]s are equal.
+///
+/// When they are not equal the panic message shows a [`TreeDiff`] of the two nodes, which is
+/// much easier to read than the `Debug` output that `assert_eq!` would show. The diff is
+/// colorized when printing to a terminal, unless the `NO_COLOR` environment variable is set.
+///
+/// # Examples
+///
+/// ```
+/// # use virtual_node::{assert_vnode_eq, VirtualNode};
+/// assert_vnode_eq!(VirtualNode::element("div"), VirtualNode::element("div"));
+/// assert_vnode_eq!(
+///     VirtualNode::text("a"),
+///     VirtualNode::text("a"),
+///     "Text nodes with the same text are equal"
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_vnode_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::test_utils::__assert_vnode_eq(&$left, &$right, None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::test_utils::__assert_vnode_eq(&$left, &$right, Some(format_args!($($arg)+)))
+    };
+}
+
+/// Assert that a [`VirtualNode`] renders the same HTML as its stored snapshot.
+///
+/// The node is rendered using [`VirtualNode::to_pretty_html`] and compared to
+/// `snapshots/{name}.html` in the directory of the crate that is being tested.
+///
+/// If the snapshot does not exist the assertion fails. If it exists but does not match, the
+/// assertion fails and shows the lines that changed. Run your tests with the
+/// `PERCY_UPDATE_SNAPSHOTS=1` environment variable to write missing snapshots and overwrite the
+/// snapshots that no longer match, then commit them.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use virtual_node::{assert_vnode_snapshot, VirtualNode};
+/// let view = VirtualNode::element("div");
+///
+/// // Compared to `snapshots/empty_div.html`.
+/// assert_vnode_snapshot!("empty_div", view);
+/// ```
+#[macro_export]
+macro_rules! assert_vnode_snapshot {
+    ($name:expr, $node:expr $(,)?) => {
+        $crate::test_utils::__assert_vnode_snapshot(env!("CARGO_MANIFEST_DIR"), $name, &$node)
+    };
+}
+
+// Used by the `assert_vnode_eq!` macro.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_vnode_eq(left: &VirtualNode, right: &VirtualNode, message: Option<fmt::Arguments>) {
+    let diff = match left.tree_diff(right) {
+        Some(diff) => diff,
+        None => return,
+    };
+
+    let diff = if use_colors() {
+        diff.colorized()
+    } else {
+        diff.to_string()
+    };
+    let message = message.map(|m| format!(": {}", m)).unwrap_or_default();
+
+    panic!(
+        "assertion failed: `(left == right)`{}\n\n`-` left, `+` right:\n\n{}",
+        message, diff
+    );
+}
+
+// Used by the `assert_vnode_snapshot!` macro.
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_vnode_snapshot(manifest_dir: &str, name: &str, node: &VirtualNode) {
+    snapshot::assert_snapshot(Path::new(manifest_dir), name, node);
+}
+
+/// Whether or not to colorize diffs in panic messages, which get printed to stderr.
+fn use_colors() -> bool {
+    std::env::var_os("NO_COLOR").is_none() && std::io::stderr().is_terminal()
+}
 
 impl VirtualNode {
     /// Get a vector of all of the VirtualNode children / grandchildren / etc of
@@ -27,35 +133,342 @@ impl VirtualNode {
     /// ```
     pub fn children_recursive<'a>(&'a self) -> Vec<&'a VirtualNode> {
         let mut descendants: Vec<&'a VirtualNode> = vec![];
+        for child in children(self) {
+            get_descendants(&mut descendants, child);
+        }
+
+        descendants.into_iter().collect()
+    }
+
+    /// Get the first descendant element that matches the CSS selector, visiting descendants
+    /// depth first in the same order that they appear in the HTML.
+    ///
+    /// Supported selectors are tags (`li`), `*`, ids (`#main`), classes (`.item`), attributes
+    /// (`[hidden]`, `[data-id='3']`), descendant (`ul li`) and child (`ul > li`) combinators and
+    /// comma separated lists of selectors.
+    ///
+    /// Just like `Element.querySelector`, this node itself is never returned but it can match
+    /// the start of a selector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector is invalid or uses unsupported syntax, such as `li:first-child`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virtual_node::{VElement, VirtualNode};
+    /// let mut item = VElement::new("li");
+    /// item.attrs.insert("data-id".to_string(), "3".into());
+    /// item.children.push(VirtualNode::text("Third"));
+    ///
+    /// let mut list = VElement::new("ul");
+    /// list.attrs.insert("class".to_string(), "items".into());
+    /// list.children.push(item.into());
+    ///
+    /// let mut view = VElement::new("div");
+    /// view.children.push(list.into());
+    /// let view = VirtualNode::from(view);
+    ///
+    /// let item = view.query_selector("ul.items > li[data-id='3']").unwrap();
+    /// assert_eq!(item.text_content(), "Third");
+    /// ```
+    pub fn query_selector(&self, selector: &str) -> Option<&VirtualNode> {
+        let mut matches = vec![];
+        parse_selector(selector).push_matching_descendants(self, &mut matches, true);
+
+        matches.into_iter().next()
+    }
+
+    /// Get every descendant element that matches the CSS selector, in the same order that they
+    /// appear in the HTML.
+    ///
+    /// See [`VirtualNode::query_selector`] for the supported selectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the selector is invalid or uses unsupported syntax.
+    pub fn query_selector_all(&self, selector: &str) -> Vec<&VirtualNode> {
+        let mut matches = vec![];
+        parse_selector(selector).push_matching_descendants(self, &mut matches, false);
+
+        matches
+    }
+
+    /// Get the text of this node and all of its descendants, similar to `Node.textContent`.
+    ///
+    /// Comments within an element or fragment are not included.
+    pub fn text_content(&self) -> String {
         match self {
-            VirtualNode::Text(_) => {}
-            VirtualNode::Element(element_node) => {
-                for child in element_node.children.iter() {
-                    get_descendants(&mut descendants, child);
+            VirtualNode::Text(text_node) => text_node.text.clone(),
+            VirtualNode::Comment(comment_node) => comment_node.text.clone(),
+            VirtualNode::Memo(memo) => memo.view().text_content(),
+            VirtualNode::Element(_) | VirtualNode::Fragment(_) => self
+                .children_recursive()
+                .into_iter()
+                .filter_map(|node| node.as_vtext_ref())
+                .map(|text_node| text_node.text.as_str())
+                .collect(),
+        }
+    }
+
+    /// Get the value of an element's attribute, similar to `Element.getAttribute`.
+    ///
+    /// A `true` boolean attribute has an empty value. A `false` boolean attribute, which does not
+    /// get rendered, returns `None`, as does any node that isn't an element.
+    ///
+    /// The `class` and `style` attributes include the element's `classes` and `styles`, the same
+    /// way that they get rendered.
+    pub fn attr(&self, name: &str) -> Option<Cow<'_, str>> {
+        selector::attribute(self.as_velement_ref()?, name)
+    }
+
+    /// Whether or not this is an element with the given class.
+    pub fn has_class(&self, class: &str) -> bool {
+        match self.as_velement_ref() {
+            Some(element) => selector::has_class(element, class),
+            None => false,
+        }
+    }
+}
+
+impl VirtualNode {
+    /// Dispatch a synthetic event to the first descendant element that matches the CSS selector.
+    ///
+    /// The element's handler for the event gets called, then its parent's, and so on up to and
+    /// including this node, just like when an event bubbles up in the browser. Bubbling stops
+    /// when a handler calls [`MouseEvent::stop_propagation`]. Events that do not bubble, such as
+    /// `onfocus` and `onmouseenter`, only call the element's handler (see
+    /// [`EventName::bubbles`]).
+    ///
+    /// Capturing handlers, such as `onclick.capture`, get called before the others, starting from
+    /// this node and going down to the element.
+    ///
+    /// Handlers with the `.once` modifier only get called the first time that the event is
+    /// dispatched to them.
+    ///
+    /// Handlers that take an event, such as a [`MouseEvent`] or a [`KeyboardEvent`], receive a
+    /// synthetic event of that type, such as a [`MouseEvent::synthetic`] event. On non wasm32
+    /// targets a synthetic event carries no data, such as the mouse position or the event's
+    /// target, and whether or not the default action was prevented is not tracked. Only
+    /// `stop_propagation` has an effect.
+    ///
+    /// This lets you test your views' event handlers with `cargo test`, without a browser.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no element matches the selector, or if the selector is invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::{cell::{Cell, RefCell}, rc::Rc};
+    /// # use virtual_node::{event::EventName, VElement, VirtualNode};
+    /// let clicks = Rc::new(Cell::new(0));
+    /// let clicks_clone = clicks.clone();
+    ///
+    /// let mut button = VElement::new("button");
+    /// button.events.insert_no_args(
+    ///     EventName::ONCLICK,
+    ///     Rc::new(RefCell::new(move || clicks_clone.set(clicks_clone.get() + 1))),
+    /// );
+    /// let mut view = VElement::new("div");
+    /// view.children.push(button.into());
+    /// let view = VirtualNode::from(view);
+    ///
+    /// view.dispatch_event("button", EventName::ONCLICK);
+    /// assert_eq!(clicks.get(), 1);
+    /// ```
+    pub fn dispatch_event(&self, selector: &str, event_name: EventName) {
+        let target = self
+            .query_selector(selector)
+            .unwrap_or_else(|| panic!("No element matches the selector {:?}", selector));
+
+        let mut path = vec![];
+        push_path(self, target, &mut path);
+
+        let should_propagate = Rc::new(Cell::new(true));
+
+        // Events that do not bubble still get captured, but only the target's other handlers get
+        // called.
+        let bubble_path = if event_name.bubbles() {
+            &path[..]
+        } else {
+            &path[path.len() - 1..]
+        };
+        let capture_phase = path.iter().map(|element| (element, true));
+        let bubble_phase = bubble_path.iter().rev().map(|element| (element, false));
+
+        for (element, capture) in capture_phase.chain(bubble_phase) {
+            // An element's handlers for the event can have different modifiers, such as
+            // `onclick` and `onclick.capture`.
+            for (name, handler) in element.events.iter() {
+                if name.with_on_prefix() == event_name.with_on_prefix()
+                    && name.listener_target() == EventListenerTarget::Element
+                    && name.options().capture == capture
+                    && !(name.options().once && was_called_once(handler))
+                {
+                    call_event_handler(handler, &event_name, &should_propagate);
                 }
             }
+
+            if !should_propagate.get() {
+                break;
+            }
         }
+    }
 
-        descendants.into_iter().collect()
+    /// Dispatch a synthetic `onclick` event to the first descendant element that matches the
+    /// CSS selector.
+    ///
+    /// See [`VirtualNode::dispatch_event`].
+    pub fn click(&self, selector: &str) {
+        self.dispatch_event(selector, EventName::ONCLICK);
+    }
+}
+
+/// Push the elements from the node down to the target, returning true if the target was found.
+///
+/// Fragments are skipped since they do not place a node into the DOM.
+fn push_path<'a>(
+    node: &'a VirtualNode,
+    target: &VirtualNode,
+    path: &mut Vec<&'a VElement>,
+) -> bool {
+    if let Some(element) = node.as_velement_ref() {
+        path.push(element);
+    }
+    if std::ptr::eq(node, target) {
+        return true;
+    }
+
+    if children(node)
+        .iter()
+        .any(|child| push_path(child, target, path))
+    {
+        return true;
+    }
+
+    if node.as_velement_ref().is_some() {
+        path.pop();
+    }
+    false
+}
+
+thread_local! {
+    /// The `.once` handlers that `dispatch_event` has already called, by address.
+    ///
+    /// We hold onto a weak reference to each handler so that its memory, and thus its address,
+    /// can't be re-used by a new handler.
+    static CALLED_ONCE_HANDLERS: RefCell<Vec<(*const (), Box<dyn Any>)>> = RefCell::new(vec![]);
+}
+
+/// Whether or not the `.once` handler was already called, remembering that it has been called
+/// if not.
+fn was_called_once(handler: &EventHandler) -> bool {
+    fn identity<T: ?Sized + 'static>(handler: &Rc<T>) -> (*const (), Box<dyn Any>) {
+        (
+            Rc::as_ptr(handler) as *const (),
+            Box::new(Rc::downgrade(handler)),
+        )
     }
+
+    let (address, weak) = match handler {
+        EventHandler::NoArgs(handler) => identity(handler),
+        EventHandler::MouseEvent(handler) => identity(handler),
+        EventHandler::KeyboardEvent(handler) => identity(handler),
+        EventHandler::InputEvent(handler) => identity(handler),
+        EventHandler::FocusEvent(handler) => identity(handler),
+        EventHandler::PointerEvent(handler) => identity(handler),
+        EventHandler::DragEvent(handler) => identity(handler),
+        EventHandler::WheelEvent(handler) => identity(handler),
+        EventHandler::SubmitEvent(handler) => identity(handler),
+        #[cfg(target_arch = "wasm32")]
+        EventHandler::UnsupportedSignature(handler) => identity(&handler.0),
+    };
+
+    CALLED_ONCE_HANDLERS.with(|called| {
+        let mut called = called.borrow_mut();
+        if called
+            .iter()
+            .any(|(called_address, _)| *called_address == address)
+        {
+            return true;
+        }
+
+        called.push((address, weak));
+        false
+    })
+}
+
+/// Call the handler with a synthetic event of the type that it takes.
+fn call_event_handler(
+    handler: &EventHandler,
+    event_name: &EventName,
+    should_propagate: &Rc<Cell<bool>>,
+) {
+    let propagation = || should_propagate.clone();
+
+    match handler {
+        EventHandler::NoArgs(no_args) => (no_args.borrow_mut())(),
+        EventHandler::MouseEvent(mouse) => (mouse.borrow_mut())(
+            MouseEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::KeyboardEvent(keyboard) => (keyboard.borrow_mut())(
+            KeyboardEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::InputEvent(input) => (input.borrow_mut())(
+            InputEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::FocusEvent(focus) => (focus.borrow_mut())(
+            FocusEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::PointerEvent(pointer) => (pointer.borrow_mut())(
+            PointerEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::DragEvent(drag) => (drag.borrow_mut())(
+            DragEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::WheelEvent(wheel) => (wheel.borrow_mut())(
+            WheelEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        EventHandler::SubmitEvent(submit) => (submit.borrow_mut())(
+            SubmitEvent::synthetic_with_propagation(event_name, propagation()),
+        ),
+        #[cfg(target_arch = "wasm32")]
+        EventHandler::UnsupportedSignature(_) => {
+            panic!("Event handlers with unsupported signatures cannot be dispatched.")
+        }
+    };
+}
+
+fn parse_selector(selector: &str) -> Selector {
+    Selector::parse(selector)
+        .unwrap_or_else(|err| panic!("Invalid selector {:?}: {}", selector, err))
 }
 
 fn get_descendants<'a>(descendants: &mut Vec<&'a VirtualNode>, node: &'a VirtualNode) {
+    let node = node.resolve_memos();
     descendants.push(node);
+    for child in children(node) {
+        get_descendants(descendants, child);
+    }
+}
+
+fn children(node: &VirtualNode) -> &[VirtualNode] {
     match node {
-        VirtualNode::Text(_) => {}
-        VirtualNode::Element(element_node) => {
-            for child in element_node.children.iter() {
-                get_descendants(descendants, child);
-            }
-        }
+        VirtualNode::Text(_) | VirtualNode::Comment(_) => &[],
+        VirtualNode::Element(element_node) => &element_node.children,
+        VirtualNode::Fragment(children) => children,
+        VirtualNode::Memo(memo) => children(memo.view()),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::VElement;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     /// Verify that we can return all of a node's descendants.
     #[test]
@@ -72,4 +485,350 @@ mod tests {
 
         assert_eq!(html_node.children_recursive().len(), 2);
     }
+
+    /// Verify that we find the elements that match each kind of selector, in document order.
+    #[test]
+    fn query_selector_all() {
+        let view = view();
+
+        let texts = |selector: &str| -> Vec<String> {
+            view.query_selector_all(selector)
+                .into_iter()
+                .map(|node| node.text_content())
+                .collect()
+        };
+
+        assert_eq!(texts("li"), vec!["One", "Two", "Three", "Nested"]);
+        assert_eq!(texts("ul.items > li"), vec!["One", "Two", "Three"]);
+        assert_eq!(texts(".items li"), vec!["One", "Two", "Three", "Nested"]);
+        assert_eq!(texts("li[data-id='3']"), vec!["Three"]);
+        assert_eq!(texts("li[data-id=\"3\"]"), vec!["Three"]);
+        assert_eq!(texts("li[data-id]"), vec!["One", "Three"]);
+        assert_eq!(texts("#main li.selected"), vec!["Two"]);
+        assert_eq!(texts("div > li"), vec!["Nested"]);
+        assert_eq!(texts("button[disabled], li.selected"), vec!["Two", "Save"]);
+        assert_eq!(texts("section *").len(), 7);
+        assert_eq!(texts("ol li"), Vec::<String>::new());
+    }
+
+    /// Verify that we return the first matching element.
+    #[test]
+    fn query_selector() {
+        let view = view();
+
+        assert_eq!(view.query_selector("li").unwrap().text_content(), "One");
+        assert_eq!(
+            view.query_selector("li.selected")
+                .unwrap()
+                .attr("class")
+                .as_deref(),
+            Some("item selected")
+        );
+        assert!(view.query_selector("table").is_none());
+
+        // The node that we're querying is never matched, only its descendants.
+        assert!(view.query_selector("section").is_none());
+    }
+
+    /// Verify that the children of a fragment are treated as the fragment's parent's children.
+    #[test]
+    fn query_selector_fragment() {
+        let mut list = VElement::new("ul");
+        list.children.push(VirtualNode::Fragment(vec![
+            VirtualNode::element("li"),
+            VirtualNode::element("li"),
+        ]));
+        let list = VirtualNode::Fragment(vec![list.into()]);
+
+        assert_eq!(list.query_selector_all("ul > li").len(), 2);
+    }
+
+    /// Verify that we get an element's attributes and classes.
+    #[test]
+    fn attr_and_has_class() {
+        let view = view();
+        let button = view.query_selector("button").unwrap();
+
+        assert_eq!(button.attr("type").as_deref(), Some("submit"));
+        assert_eq!(button.attr("disabled").as_deref(), Some(""));
+        assert_eq!(button.attr("hidden"), None);
+        assert!(button.has_class("primary"));
+        assert!(!button.has_class("prim"));
+        assert!(!VirtualNode::text("primary").has_class("primary"));
+    }
+
+    /// Verify that we get the text of all of a node's descendants, without comments.
+    #[test]
+    fn text_content() {
+        let node = VirtualNode::from_html("<p>Hello <!-- comment --><b>world</b>!</p>").unwrap();
+
+        assert_eq!(node[0].text_content(), "Hello world!");
+    }
+
+    /// Verify that an event bubbles from the target up through its ancestors, calling both
+    /// `NoArgs` and `MouseEvent` handlers.
+    #[test]
+    fn dispatch_event_bubbles() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut button = element_with_click_handler("button", &calls, false);
+        button.children.push(VirtualNode::text("Save"));
+        let mut form = element_with_click_handler("form", &calls, false);
+        form.children.push(button.into());
+        let mut view = element_with_click_handler("section", &calls, false);
+        view.children.push(VirtualNode::Fragment(vec![form.into()]));
+        let view = VirtualNode::from(view);
+
+        view.click("button");
+        assert_eq!(*calls.borrow(), vec!["button", "form", "section"]);
+
+        calls.borrow_mut().clear();
+        view.click("form");
+        assert_eq!(*calls.borrow(), vec!["form", "section"]);
+    }
+
+    /// Verify that an event stops bubbling when a handler stops its propagation.
+    #[test]
+    fn dispatch_event_stop_propagation() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let button = element_with_click_handler("button", &calls, false);
+        let mut form = element_with_click_handler("form", &calls, true);
+        form.children.push(button.into());
+        let mut view = element_with_click_handler("section", &calls, false);
+        view.children.push(form.into());
+        let view = VirtualNode::from(view);
+
+        view.click("button");
+        assert_eq!(*calls.borrow(), vec!["button", "form"]);
+    }
+
+    /// Verify that we only call the handlers for the dispatched event.
+    #[test]
+    fn dispatch_event_name() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut input = element_with_click_handler("input", &calls, false);
+        let calls_clone = calls.clone();
+        input.events.insert_no_args(
+            EventName::ONINPUT,
+            Rc::new(RefCell::new(move || {
+                calls_clone.borrow_mut().push("oninput")
+            })),
+        );
+        let mut view = VElement::new("div");
+        view.children.push(input.into());
+        let view = VirtualNode::from(view);
+
+        view.dispatch_event("input", EventName::ONINPUT);
+        assert_eq!(*calls.borrow(), vec!["oninput"]);
+    }
+
+    /// Verify that handlers that take different kinds of events share the event's propagation,
+    /// so a typed handler can stop the event from bubbling up to a handler of another type.
+    #[test]
+    fn dispatch_typed_event_stop_propagation() {
+        let calls = Rc::new(RefCell::new(vec![]));
+        let keydown = EventName::from("onkeydown");
+
+        let mut input = VElement::new("input");
+        let calls_clone = calls.clone();
+        input.events.insert_keyboard_event(
+            keydown.clone(),
+            Rc::new(RefCell::new(move |_event: KeyboardEvent| {
+                calls_clone.borrow_mut().push("input");
+            })),
+        );
+
+        let mut form = VElement::new("form");
+        let calls_clone = calls.clone();
+        form.events.insert_keyboard_event(
+            keydown.clone(),
+            Rc::new(RefCell::new(move |event: KeyboardEvent| {
+                calls_clone.borrow_mut().push("form");
+                event.stop_propagation();
+            })),
+        );
+        form.children.push(input.into());
+
+        let mut view = VElement::new("section");
+        let calls_clone = calls.clone();
+        view.events.insert_no_args(
+            keydown.clone(),
+            Rc::new(RefCell::new(move || {
+                calls_clone.borrow_mut().push("section")
+            })),
+        );
+        view.children.push(form.into());
+        let view = VirtualNode::from(view);
+
+        view.dispatch_event("input", keydown);
+        assert_eq!(*calls.borrow(), vec!["input", "form"]);
+    }
+
+    /// Verify that capturing handlers are called on the way down to the target, before the
+    /// handlers that are called while the event bubbles up.
+    #[test]
+    fn dispatch_event_capture() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let button = element_with_click_handler("button", &calls, false);
+        let mut form = element_with_click_handler("form", &calls, false);
+        let calls_clone = calls.clone();
+        form.events.insert_no_args(
+            "onclick.capture".into(),
+            Rc::new(RefCell::new(move || {
+                calls_clone.borrow_mut().push("form capture")
+            })),
+        );
+        form.children.push(button.into());
+        let view = VirtualNode::from(form);
+
+        view.click("button");
+        assert_eq!(*calls.borrow(), vec!["form capture", "button", "form"]);
+    }
+
+    /// Verify that handlers with the `.once` modifier are only called the first time.
+    #[test]
+    fn dispatch_event_once() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut button = element_with_click_handler("button", &calls, false);
+        let calls_clone = calls.clone();
+        button.events.insert_no_args(
+            "onclick.once".into(),
+            Rc::new(RefCell::new(move || calls_clone.borrow_mut().push("once"))),
+        );
+        let view = VirtualNode::from(VElement {
+            children: vec![button.into()],
+            ..VElement::new("div")
+        });
+
+        view.dispatch_event("button", EventName::ONCLICK);
+        view.dispatch_event("button", EventName::ONCLICK);
+
+        calls.borrow_mut().sort();
+        assert_eq!(*calls.borrow(), vec!["button", "button", "once"]);
+    }
+
+    /// Verify that events that do not bubble only call the target's handlers, along with the
+    /// capturing handlers of its ancestors.
+    #[test]
+    fn dispatch_event_without_bubbling() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut form = VElement::new("form");
+        for (name, call) in [("onfocus", "form"), ("onfocus.capture", "form capture")] {
+            let calls = calls.clone();
+            form.events.insert_no_args(
+                name.into(),
+                Rc::new(RefCell::new(move || calls.borrow_mut().push(call))),
+            );
+        }
+        let mut input = VElement::new("input");
+        let calls_clone = calls.clone();
+        input.events.insert_no_args(
+            "onfocus".into(),
+            Rc::new(RefCell::new(move || calls_clone.borrow_mut().push("input"))),
+        );
+        form.children.push(input.into());
+        let view = VirtualNode::from(VElement {
+            children: vec![form.into()],
+            ..VElement::new("div")
+        });
+
+        view.dispatch_event("input", "onfocus".into());
+
+        assert_eq!(*calls.borrow(), vec!["form capture", "input"]);
+    }
+
+    /// Verify that window and document handlers are not called when an event is dispatched to an
+    /// element.
+    #[test]
+    fn dispatch_event_skips_window_and_document_handlers() {
+        let calls = Rc::new(RefCell::new(vec![]));
+
+        let mut button = element_with_click_handler("button", &calls, false);
+        let calls_clone = calls.clone();
+        button.events.insert_no_args(
+            "document:onclick".into(),
+            Rc::new(RefCell::new(move || {
+                calls_clone.borrow_mut().push("document")
+            })),
+        );
+        let mut view = VElement::new("div");
+        view.children.push(button.into());
+        let view = VirtualNode::from(view);
+
+        view.click("button");
+        assert_eq!(*calls.borrow(), vec!["button"]);
+    }
+
+    /// Verify that we panic if there is no element to dispatch the event to.
+    #[test]
+    #[should_panic(expected = "No element matches")]
+    fn dispatch_event_without_target() {
+        VirtualNode::element("div").click("button");
+    }
+
+    /// Create an element whose onclick handler pushes the element's tag to the calls.
+    ///
+    /// Uses a `MouseEvent` handler if it should stop propagation, otherwise a `NoArgs` handler.
+    fn element_with_click_handler(
+        tag: &'static str,
+        calls: &Rc<RefCell<Vec<&'static str>>>,
+        stop_propagation: bool,
+    ) -> VElement {
+        let mut element = VElement::new(tag);
+        let calls = calls.clone();
+
+        if stop_propagation {
+            element.events.insert_mouse_event(
+                EventName::ONCLICK,
+                Rc::new(RefCell::new(move |event: MouseEvent| {
+                    calls.borrow_mut().push(tag);
+                    event.stop_propagation();
+                })),
+            );
+        } else {
+            element.events.insert_no_args(
+                EventName::ONCLICK,
+                Rc::new(RefCell::new(move || calls.borrow_mut().push(tag))),
+            );
+        }
+
+        element
+    }
+
+    /// Verify that assert_vnode_eq! passes for equal nodes and panics with a diff otherwise.
+    #[test]
+    #[should_panic(expected = "`-` left, `+` right:")]
+    fn assert_vnode_eq() {
+        assert_vnode_eq!(VirtualNode::text("a"), VirtualNode::text("a"));
+        assert_vnode_eq!(VirtualNode::text("a"), VirtualNode::text("b"));
+    }
+
+    /// Verify that we panic for an invalid selector instead of silently matching nothing.
+    #[test]
+    #[should_panic(expected = "Invalid selector")]
+    fn invalid_selector() {
+        view().query_selector("li:first-child");
+    }
+
+    fn view() -> VirtualNode {
+        let mut view = VirtualNode::from_html(
+            r#"<section id="main">
+              <ul class="items">
+                <li data-id="1">One</li>
+                <li class="item selected">Two</li>
+                <li data-id="3">Three</li>
+              </ul>
+              <div class="items"><li>Nested</li></div>
+              <button type="submit" class="primary" disabled>Save</button>
+            </section>"#,
+        )
+        .unwrap();
+
+        view.remove(0)
+    }
 }
@@ -0,0 +1,456 @@
+//! A small subset of CSS selectors, used to find nodes when unit testing views.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use super::children;
+use crate::{AttributeValue, VElement, VirtualNode};
+
+/// A parsed selector, such as `ul.items > li[data-id='3'], li.selected`.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Selector {
+    /// The comma separated selectors. An element matches if it matches any of them.
+    alternatives: Vec<ComplexSelector>,
+}
+
+/// Compound selectors joined by combinators, such as `ul.items > li`.
+#[derive(Debug, PartialEq)]
+struct ComplexSelector {
+    compounds: Vec<CompoundSelector>,
+    /// The combinator in between `compounds[idx]` and `compounds[idx + 1]`.
+    combinators: Vec<Combinator>,
+}
+
+/// Conditions that a single element must meet, such as `li#first.item[data-id='3']`.
+#[derive(Debug, Default, PartialEq)]
+struct CompoundSelector {
+    /// `None` for the universal selector `*`, or when there is no tag.
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<AttributeSelector>,
+}
+
+/// `[name]` or `[name=value]`
+#[derive(Debug, PartialEq)]
+struct AttributeSelector {
+    name: String,
+    value: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Combinator {
+    /// `ul li`
+    Descendant,
+    /// `ul > li`
+    Child,
+}
+
+/// An error while parsing a selector.
+#[derive(Debug, PartialEq)]
+pub(crate) struct SelectorError {
+    /// The byte offset into the selector where the problem was found.
+    offset: usize,
+    message: &'static str,
+}
+
+impl Selector {
+    pub(crate) fn parse(selector: &str) -> Result<Selector, SelectorError> {
+        SelectorParser { selector, pos: 0 }.parse()
+    }
+
+    /// Push every descendant of the node that matches this selector, in document order.
+    ///
+    /// The node itself is never pushed, but it can be matched as an ancestor.
+    pub(crate) fn push_matching_descendants<'a>(
+        &self,
+        node: &'a VirtualNode,
+        matches: &mut Vec<&'a VirtualNode>,
+        only_first: bool,
+    ) {
+        let mut ancestors = vec![];
+        if let Some(element) = node.resolve_memos().as_velement_ref() {
+            ancestors.push(element);
+        }
+
+        for child in children(node) {
+            if only_first && !matches.is_empty() {
+                return;
+            }
+            self.push_matching(child, &mut ancestors, matches, only_first);
+        }
+    }
+
+    fn push_matching<'a>(
+        &self,
+        node: &'a VirtualNode,
+        ancestors: &mut Vec<&'a VElement>,
+        matches: &mut Vec<&'a VirtualNode>,
+        only_first: bool,
+    ) {
+        let node = node.resolve_memos();
+
+        // A fragment's children are placed into the fragment's parent, so the fragment is not
+        // one of their ancestors.
+        let element = match node {
+            VirtualNode::Element(element) => element,
+            VirtualNode::Fragment(_) => {
+                for child in children(node) {
+                    self.push_matching(child, ancestors, matches, only_first);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        if self.matches(element, ancestors) {
+            matches.push(node);
+            if only_first {
+                return;
+            }
+        }
+
+        ancestors.push(element);
+        for child in element.children.iter() {
+            if only_first && !matches.is_empty() {
+                break;
+            }
+            self.push_matching(child, ancestors, matches, only_first);
+        }
+        ancestors.pop();
+    }
+
+    /// Whether or not the element matches, given its ancestors with the nearest one last.
+    fn matches(&self, element: &VElement, ancestors: &[&VElement]) -> bool {
+        self.alternatives
+            .iter()
+            .any(|complex| complex.matches(complex.compounds.len() - 1, element, ancestors))
+    }
+}
+
+impl ComplexSelector {
+    /// Whether or not the element matches the compound selector at `idx` along with every
+    /// compound selector and combinator before it.
+    fn matches(&self, idx: usize, element: &VElement, ancestors: &[&VElement]) -> bool {
+        if !self.compounds[idx].matches(element) {
+            return false;
+        }
+        if idx == 0 {
+            return true;
+        }
+
+        match self.combinators[idx - 1] {
+            Combinator::Child => match ancestors.split_last() {
+                Some((parent, ancestors)) => self.matches(idx - 1, parent, ancestors),
+                None => false,
+            },
+            Combinator::Descendant => (0..ancestors.len())
+                .rev()
+                .any(|a| self.matches(idx - 1, ancestors[a], &ancestors[..a])),
+        }
+    }
+}
+
+impl CompoundSelector {
+    fn matches(&self, element: &VElement) -> bool {
+        if let Some(tag) = &self.tag {
+            if !element.tag.eq_ignore_ascii_case(tag) {
+                return false;
+            }
+        }
+
+        let node_attr = |name: &str| attribute(element, name);
+
+        if let Some(id) = &self.id {
+            if node_attr("id").as_deref() != Some(id.as_str()) {
+                return false;
+            }
+        }
+
+        if !self.classes.iter().all(|class| has_class(element, class)) {
+            return false;
+        }
+
+        self.attributes
+            .iter()
+            .all(|attr| match (node_attr(&attr.name), &attr.value) {
+                (Some(_), None) => true,
+                (Some(found), Some(expected)) => found == expected.as_str(),
+                (None, _) => false,
+            })
+    }
+
+    fn is_empty(&self) -> bool {
+        self == &CompoundSelector::default()
+    }
+}
+
+/// The value of an attribute, the same way that `Element.getAttribute` would return it.
+///
+/// A `true` boolean attribute has an empty value, and a `false` one is not present.
+/// The element's classes and styles are included in the `class` and `style` attributes.
+pub(crate) fn attribute<'a>(element: &'a VElement, name: &str) -> Option<Cow<'a, str>> {
+    let merged = match name {
+        "class" => element.class_attribute(),
+        "style" => element.style_attribute(),
+        _ => None,
+    };
+    if let Some(merged) = merged {
+        return Some(Cow::Owned(merged));
+    }
+
+    match element.attrs.get(name)? {
+        AttributeValue::String(value) => Some(Cow::Borrowed(value.as_str())),
+        AttributeValue::Bool(true) => Some(Cow::Borrowed("")),
+        AttributeValue::Bool(false) => None,
+    }
+}
+
+pub(crate) fn has_class(element: &VElement, class: &str) -> bool {
+    element.classes.contains(class)
+        || attribute(element, "class")
+            .map(|classes| classes.split_whitespace().any(|c| c == class))
+            .unwrap_or(false)
+}
+
+struct SelectorParser<'a> {
+    selector: &'a str,
+    pos: usize,
+}
+
+impl<'a> SelectorParser<'a> {
+    fn parse(mut self) -> Result<Selector, SelectorError> {
+        let mut alternatives = vec![self.parse_complex()?];
+
+        while self.eat(',') {
+            alternatives.push(self.parse_complex()?);
+        }
+
+        if self.pos < self.selector.len() {
+            return Err(self.error("Expected a selector"));
+        }
+
+        Ok(Selector { alternatives })
+    }
+
+    fn parse_complex(&mut self) -> Result<ComplexSelector, SelectorError> {
+        self.skip_whitespace();
+
+        let mut compounds = vec![self.parse_compound()?];
+        let mut combinators = vec![];
+
+        loop {
+            let had_whitespace = self.skip_whitespace();
+
+            let combinator = if self.eat('>') {
+                self.skip_whitespace();
+                Combinator::Child
+            } else if had_whitespace && !self.rest().is_empty() && !self.rest().starts_with(',') {
+                Combinator::Descendant
+            } else {
+                break;
+            };
+
+            combinators.push(combinator);
+            compounds.push(self.parse_compound()?);
+        }
+
+        Ok(ComplexSelector {
+            compounds,
+            combinators,
+        })
+    }
+
+    fn parse_compound(&mut self) -> Result<CompoundSelector, SelectorError> {
+        let mut compound = CompoundSelector::default();
+
+        // The universal selector `*` matches every tag.
+        let is_universal = self.eat('*');
+        if !is_universal && !self.peek_name().is_empty() {
+            compound.tag = Some(self.take_name()?.to_string());
+        }
+
+        loop {
+            if self.eat('#') {
+                compound.id = Some(self.take_name()?.to_string());
+            } else if self.eat('.') {
+                compound.classes.push(self.take_name()?.to_string());
+            } else if self.eat('[') {
+                compound.attributes.push(self.parse_attribute()?);
+            } else {
+                break;
+            }
+        }
+
+        if compound.is_empty() && !is_universal {
+            return Err(self.error("Expected a selector"));
+        }
+
+        Ok(compound)
+    }
+
+    /// Parse the rest of an attribute selector, such as `data-id='3']`.
+    fn parse_attribute(&mut self) -> Result<AttributeSelector, SelectorError> {
+        self.skip_whitespace();
+        let name = self.take_name()?.to_string();
+        self.skip_whitespace();
+
+        let value = if self.eat('=') {
+            self.skip_whitespace();
+            let value = self.take_attribute_value()?;
+            self.skip_whitespace();
+            Some(value.to_string())
+        } else {
+            None
+        };
+
+        if !self.eat(']') {
+            return Err(self.error("Expected `]`"));
+        }
+
+        Ok(AttributeSelector { name, value })
+    }
+
+    fn take_attribute_value(&mut self) -> Result<&'a str, SelectorError> {
+        let rest = self.rest();
+
+        match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = rest[1..]
+                    .find(quote)
+                    .ok_or_else(|| self.error("Unterminated attribute value"))?;
+
+                self.pos += end + 2;
+                Ok(&rest[1..end + 1])
+            }
+            _ => self.take_name(),
+        }
+    }
+
+    /// Take a tag, id, class or attribute name.
+    fn take_name(&mut self) -> Result<&'a str, SelectorError> {
+        let name = self.peek_name();
+        if name.is_empty() {
+            return Err(self.error("Expected a name"));
+        }
+
+        self.pos += name.len();
+        Ok(name)
+    }
+
+    fn peek_name(&self) -> &'a str {
+        let rest = self.rest();
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+            .unwrap_or(rest.len());
+
+        &rest[..end]
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.rest().starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if there was any whitespace.
+    fn skip_whitespace(&mut self) -> bool {
+        let rest = self.rest();
+        let whitespace = rest.len() - rest.trim_start().len();
+
+        self.pos += whitespace;
+        whitespace > 0
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.selector[self.pos..]
+    }
+
+    fn error(&self, message: &'static str) -> SelectorError {
+        SelectorError {
+            offset: self.pos,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that we parse every kind of selector and combinator that we support.
+    #[test]
+    fn parses_selectors() {
+        let selector = Selector::parse("ul.items > li[data-id='3'][hidden] a#link, *").unwrap();
+
+        let ul = CompoundSelector {
+            tag: Some("ul".to_string()),
+            classes: vec!["items".to_string()],
+            ..CompoundSelector::default()
+        };
+        let li = CompoundSelector {
+            tag: Some("li".to_string()),
+            attributes: vec![
+                AttributeSelector {
+                    name: "data-id".to_string(),
+                    value: Some("3".to_string()),
+                },
+                AttributeSelector {
+                    name: "hidden".to_string(),
+                    value: None,
+                },
+            ],
+            ..CompoundSelector::default()
+        };
+        let a = CompoundSelector {
+            tag: Some("a".to_string()),
+            id: Some("link".to_string()),
+            ..CompoundSelector::default()
+        };
+
+        assert_eq!(
+            selector,
+            Selector {
+                alternatives: vec![
+                    ComplexSelector {
+                        compounds: vec![ul, li, a],
+                        combinators: vec![Combinator::Child, Combinator::Descendant],
+                    },
+                    ComplexSelector {
+                        compounds: vec![CompoundSelector::default()],
+                        combinators: vec![],
+                    }
+                ]
+            }
+        );
+    }
+
+    /// Verify that we return an error for selectors that we cannot parse.
+    #[test]
+    fn invalid_selectors() {
+        for (selector, offset) in [
+            ("", 0),
+            ("div >", 5),
+            ("div,", 4),
+            ("[data-id='3'", 12),
+            ("[data-id='3]", 9),
+            ("div:hover", 3),
+        ] {
+            assert_eq!(
+                Selector::parse(selector).map_err(|err| err.offset),
+                Err(offset),
+                "{}",
+                selector
+            );
+        }
+    }
+}
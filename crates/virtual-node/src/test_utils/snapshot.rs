@@ -0,0 +1,120 @@
+//! Store the pretty printed HTML of a virtual node and fail when it changes.
+
+use std::path::{Path, PathBuf};
+
+use super::tree_diff::{diff_lines, DiffLines};
+use super::use_colors;
+use crate::VirtualNode;
+
+/// Set this environment variable to write snapshots that are missing and overwrite snapshots that
+/// no longer match.
+pub(crate) const UPDATE_SNAPSHOTS_ENV_VAR: &str = "PERCY_UPDATE_SNAPSHOTS";
+
+/// Compare the node's pretty printed HTML to the snapshot stored in
+/// `{manifest_dir}/snapshots/{name}.html`.
+///
+/// The snapshot only gets written if the [`UPDATE_SNAPSHOTS_ENV_VAR`] environment variable is
+/// set, so a snapshot that was never committed fails the test instead of silently passing.
+#[track_caller]
+pub(crate) fn assert_snapshot(manifest_dir: &Path, name: &str, node: &VirtualNode) {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_ENV_VAR).is_some();
+    compare_snapshot(manifest_dir, name, node, update);
+}
+
+#[track_caller]
+fn compare_snapshot(manifest_dir: &Path, name: &str, node: &VirtualNode, update: bool) {
+    let path = snapshot_path(manifest_dir, name);
+    let html = node.to_pretty_html();
+
+    if update {
+        write_snapshot(&path, &html);
+        return;
+    }
+
+    let stored = match std::fs::read_to_string(&path) {
+        Ok(stored) => stored,
+        Err(_) => panic!(
+            r#"Snapshot {:?} does not exist.
+
+Rendered:
+
+{}
+Run the test with {}=1 to write the snapshot."#,
+            path, html, UPDATE_SNAPSHOTS_ENV_VAR
+        ),
+    };
+
+    if stored == html {
+        return;
+    }
+
+    let stored_lines: Vec<&str> = stored.lines().collect();
+    let html_lines: Vec<&str> = html.lines().collect();
+    let lines = diff_lines(&stored_lines, &html_lines);
+
+    let lines = DiffLines(&lines);
+    let diff = if use_colors() {
+        lines.colorized()
+    } else {
+        lines.to_string()
+    };
+
+    panic!(
+        r#"Snapshot {:?} does not match.
+
+`-` snapshot, `+` rendered:
+
+{}
+Run the test with {}=1 to update the snapshot."#,
+        path, diff, UPDATE_SNAPSHOTS_ENV_VAR
+    );
+}
+
+fn snapshot_path(manifest_dir: &Path, name: &str) -> PathBuf {
+    manifest_dir
+        .join("snapshots")
+        .join(format!("{}.html", name))
+}
+
+fn write_snapshot(path: &Path, html: &str) {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).unwrap();
+    }
+    std::fs::write(path, html).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that a missing snapshot fails unless we are updating snapshots, and that a written
+    /// snapshot passes when it matches and fails when it changes.
+    #[test]
+    fn snapshot_lifecycle() {
+        let manifest_dir =
+            std::env::temp_dir().join(format!("virtual-node-snapshot-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&manifest_dir);
+
+        let node = VirtualNode::element("div");
+        let missing = std::panic::catch_unwind(|| {
+            compare_snapshot(&manifest_dir, "view", &VirtualNode::element("div"), false);
+        });
+        assert!(missing.is_err());
+        assert!(!manifest_dir.join("snapshots/view.html").exists());
+
+        compare_snapshot(&manifest_dir, "view", &node, true);
+        assert_eq!(
+            std::fs::read_to_string(manifest_dir.join("snapshots/view.html")).unwrap(),
+            "<div></div>\n"
+        );
+
+        compare_snapshot(&manifest_dir, "view", &node, false);
+
+        let changed = std::panic::catch_unwind(|| {
+            compare_snapshot(&manifest_dir, "view", &VirtualNode::element("span"), false);
+        });
+        assert!(changed.is_err());
+
+        std::fs::remove_dir_all(&manifest_dir).unwrap();
+    }
+}
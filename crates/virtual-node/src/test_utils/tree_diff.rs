@@ -0,0 +1,769 @@
+//! A tree shaped diff between two virtual nodes, used to explain why they are not equal.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::escape_html::EscapeHtml;
+use crate::event::{EventListenerTarget, EventName};
+use crate::{AttributeValue, PropertyValue, VElement, VirtualNode};
+
+/// Unchanged nodes whose HTML is longer than this get collapsed into `<tag>…</tag>`.
+const MAX_UNCHANGED_LINE_LEN: usize = 80;
+
+/// Above this many children pairs we stop looking for the longest common subsequence and
+/// compare children by position, so that diffing huge lists stays fast.
+const MAX_LCS_CELLS: usize = 1_000_000;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+/// The differences between two virtual nodes, shown as a tree.
+///
+/// Lines that only exist in the left node start with `-`, lines that only exist in the right
+/// node start with `+`. Unchanged nodes are collapsed onto a single line.
+///
+/// ```text
+///   <div>
+/// -   <span class="old">
+/// +   <span class="new">
+/// -     "Hello"
+/// +     "Goodbye"
+///     </span>
+/// +   <br>
+///   </div>
+/// ```
+///
+/// Created using [`VirtualNode::tree_diff`].
+#[derive(Debug, PartialEq)]
+pub struct TreeDiff {
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct DiffLine {
+    pub(crate) change: Change,
+    pub(crate) depth: usize,
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Change {
+    Unchanged,
+    Removed,
+    Added,
+}
+
+impl VirtualNode {
+    /// Compare this node to another one, returning the differences between them or `None` if
+    /// they are equal.
+    ///
+    /// Nodes are compared using what the diff shows, so event handlers are compared by their
+    /// names, not by their closures.
+    ///
+    /// This node is treated as the left side (`-`) of the diff.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virtual_node::{VElement, VirtualNode};
+    /// let mut left = VElement::new("div");
+    /// left.children.push(VirtualNode::text("Hello"));
+    /// let mut right = VElement::new("div");
+    /// right.children.push(VirtualNode::text("Goodbye"));
+    ///
+    /// let diff = VirtualNode::from(left).tree_diff(&right.into()).unwrap();
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     r#"  <div>
+    /// -   "Hello"
+    /// +   "Goodbye"
+    ///   </div>
+    /// "#
+    /// );
+    /// ```
+    pub fn tree_diff(&self, other: &VirtualNode) -> Option<TreeDiff> {
+        if same(self, other) {
+            return None;
+        }
+
+        let mut lines = vec![];
+        diff_nodes(self, other, 0, &mut lines);
+        Some(TreeDiff { lines })
+    }
+
+    /// Render the node as HTML with every node on its own line, indented by its depth.
+    ///
+    /// Attributes are sorted by name so that the output is the same every time. Fragments are
+    /// transparent, just like when they are rendered into the DOM.
+    ///
+    /// This is what gets stored by `assert_vnode_snapshot!`.
+    pub fn to_pretty_html(&self) -> String {
+        let mut lines = vec![];
+        push_pretty_html_lines(self, 0, &mut lines);
+
+        let mut html = String::new();
+        for line in lines {
+            html += &"  ".repeat(line.depth);
+            html += &line.text;
+            html += "\n";
+        }
+        html
+    }
+}
+
+impl TreeDiff {
+    /// The diff with removed lines colored red and added lines colored green using ANSI escape
+    /// codes, for printing to a terminal.
+    pub fn colorized(&self) -> String {
+        DiffLines(&self.lines).colorized()
+    }
+}
+
+impl fmt::Display for TreeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        DiffLines(&self.lines).fmt(f)
+    }
+}
+
+/// Lines of a diff, which can either come from a [`TreeDiff`] or from diffing two strings.
+pub(crate) struct DiffLines<'a>(pub(crate) &'a [DiffLine]);
+
+impl fmt::Display for DiffLines<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl DiffLines<'_> {
+    pub(crate) fn colorized(&self) -> String {
+        let mut colorized = String::new();
+        self.write(&mut colorized, true).unwrap();
+        colorized
+    }
+
+    fn write<W: fmt::Write>(&self, w: &mut W, colorize: bool) -> fmt::Result {
+        for line in self.0 {
+            let (prefix, color) = match line.change {
+                Change::Unchanged => (' ', None),
+                Change::Removed => ('-', Some(RED)),
+                Change::Added => ('+', Some(GREEN)),
+            };
+            let color = color.filter(|_| colorize);
+
+            if let Some(color) = color {
+                w.write_str(color)?;
+            }
+            write!(w, "{} {}{}", prefix, "  ".repeat(line.depth), line.text)?;
+            if color.is_some() {
+                w.write_str(RESET)?;
+            }
+            writeln!(w)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Diff two lists of lines, such as a stored snapshot and a freshly rendered one.
+pub(crate) fn diff_lines(left: &[&str], right: &[&str]) -> Vec<DiffLine> {
+    let mut lines = vec![];
+
+    let line = |change, text: &str| DiffLine {
+        change,
+        depth: 0,
+        text: text.to_string(),
+    };
+
+    diff_sequences(
+        left,
+        right,
+        |l, r| l == r,
+        |l, r| match (l, r) {
+            (Some(l), Some(r)) if l == r => lines.push(line(Change::Unchanged, l)),
+            (l, r) => {
+                if let Some(l) = l {
+                    lines.push(line(Change::Removed, l));
+                }
+                if let Some(r) = r {
+                    lines.push(line(Change::Added, r));
+                }
+            }
+        },
+    );
+
+    lines
+}
+
+fn diff_nodes(left: &VirtualNode, right: &VirtualNode, depth: usize, lines: &mut Vec<DiffLine>) {
+    let (left, right) = (left.resolve_memos(), right.resolve_memos());
+
+    if same(left, right) {
+        lines.push(DiffLine {
+            change: Change::Unchanged,
+            depth,
+            text: collapsed(left),
+        });
+        return;
+    }
+
+    match (left, right) {
+        (VirtualNode::Element(l), VirtualNode::Element(r)) if l.tag == r.tag => {
+            let (l_open, r_open) = (open_tag(l), open_tag(r));
+            if l_open == r_open {
+                push_line(lines, Change::Unchanged, depth, l_open);
+            } else {
+                push_line(lines, Change::Removed, depth, l_open);
+                push_line(lines, Change::Added, depth, r_open);
+            }
+
+            let l_inner = &l.special_attributes.dangerous_inner_html;
+            let r_inner = &r.special_attributes.dangerous_inner_html;
+            if l_inner == r_inner {
+                diff_children(&l.children, &r.children, depth + 1, lines);
+            } else {
+                push_inner_html_or_children(l, Change::Removed, depth + 1, lines);
+                push_inner_html_or_children(r, Change::Added, depth + 1, lines);
+            }
+
+            if let Some(close) = close_tag(l) {
+                push_line(lines, Change::Unchanged, depth, close);
+            }
+        }
+        (VirtualNode::Fragment(l), VirtualNode::Fragment(r)) => {
+            push_line(lines, Change::Unchanged, depth, "<>".to_string());
+            diff_children(l, r, depth + 1, lines);
+            push_line(lines, Change::Unchanged, depth, "</>".to_string());
+        }
+        _ => {
+            push_node_lines(left, Change::Removed, depth, lines);
+            push_node_lines(right, Change::Added, depth, lines);
+        }
+    }
+}
+
+/// Children that are equal are matched up with each other. The children in between them are
+/// compared by their position.
+fn diff_children(
+    left: &[VirtualNode],
+    right: &[VirtualNode],
+    depth: usize,
+    lines: &mut Vec<DiffLine>,
+) {
+    diff_sequences(left, right, same, |l, r| match (l, r) {
+        (Some(l), Some(r)) => diff_nodes(l, r, depth, lines),
+        (Some(l), None) => push_node_lines(l, Change::Removed, depth, lines),
+        (None, Some(r)) => push_node_lines(r, Change::Added, depth, lines),
+        (None, None) => {}
+    });
+}
+
+/// Whether or not the nodes are the same, comparing the same things that the diff shows.
+fn same(left: &VirtualNode, right: &VirtualNode) -> bool {
+    match (left.resolve_memos(), right.resolve_memos()) {
+        (VirtualNode::Element(l), VirtualNode::Element(r)) => {
+            open_tag(l) == open_tag(r)
+                && l.special_attributes.dangerous_inner_html
+                    == r.special_attributes.dangerous_inner_html
+                && all_same(&l.children, &r.children)
+        }
+        (VirtualNode::Fragment(l), VirtualNode::Fragment(r)) => all_same(l, r),
+        (VirtualNode::Text(l), VirtualNode::Text(r)) => l.text == r.text,
+        (VirtualNode::Comment(l), VirtualNode::Comment(r)) => l.text == r.text,
+        _ => false,
+    }
+}
+
+fn all_same(left: &[VirtualNode], right: &[VirtualNode]) -> bool {
+    left.len() == right.len() && left.iter().zip(right).all(|(l, r)| same(l, r))
+}
+
+/// Walk two sequences in order, calling `on_pair` with the items that line up with each other.
+///
+/// Items that are equal according to the longest common subsequence are paired up. Any items in
+/// between those are paired up by position, with `None` for the side that ran out of items.
+fn diff_sequences<T>(
+    left: &[T],
+    right: &[T],
+    eq: impl Fn(&T, &T) -> bool,
+    mut on_pair: impl FnMut(Option<&T>, Option<&T>),
+) {
+    let common = longest_common_subsequence(left, right, &eq);
+
+    let (mut l_idx, mut r_idx) = (0, 0);
+    for (l_common, r_common) in common
+        .into_iter()
+        .chain(std::iter::once((left.len(), right.len())))
+    {
+        let gap = (l_common - l_idx).max(r_common - r_idx);
+        for offset in 0..gap {
+            let l = left[l_idx..l_common].get(offset);
+            let r = right[r_idx..r_common].get(offset);
+            on_pair(l, r);
+        }
+
+        if l_common < left.len() {
+            on_pair(Some(&left[l_common]), Some(&right[r_common]));
+        }
+        l_idx = l_common + 1;
+        r_idx = r_common + 1;
+    }
+}
+
+/// The indices of the items that make up the longest common subsequence of the two sequences.
+fn longest_common_subsequence<T>(
+    left: &[T],
+    right: &[T],
+    eq: impl Fn(&T, &T) -> bool,
+) -> Vec<(usize, usize)> {
+    let prefix = left.iter().zip(right).take_while(|(l, r)| eq(l, r)).count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| eq(l, r))
+        .count();
+
+    let mut common: Vec<(usize, usize)> = (0..prefix).map(|idx| (idx, idx)).collect();
+
+    let left_middle = &left[prefix..left.len() - suffix];
+    let right_middle = &right[prefix..right.len() - suffix];
+
+    if left_middle.len() * right_middle.len() <= MAX_LCS_CELLS {
+        // lengths[l][r] is the length of the longest common subsequence of left_middle[l..] and
+        // right_middle[r..].
+        let mut lengths = vec![vec![0; right_middle.len() + 1]; left_middle.len() + 1];
+        for l in (0..left_middle.len()).rev() {
+            for r in (0..right_middle.len()).rev() {
+                lengths[l][r] = if eq(&left_middle[l], &right_middle[r]) {
+                    lengths[l + 1][r + 1] + 1
+                } else {
+                    lengths[l + 1][r].max(lengths[l][r + 1])
+                };
+            }
+        }
+
+        let (mut l, mut r) = (0, 0);
+        while l < left_middle.len() && r < right_middle.len() {
+            if eq(&left_middle[l], &right_middle[r]) {
+                common.push((prefix + l, prefix + r));
+                l += 1;
+                r += 1;
+            } else if lengths[l + 1][r] >= lengths[l][r + 1] {
+                l += 1;
+            } else {
+                r += 1;
+            }
+        }
+    }
+
+    let left_suffix_start = left.len() - suffix;
+    let right_suffix_start = right.len() - suffix;
+    common.extend((0..suffix).map(|idx| (left_suffix_start + idx, right_suffix_start + idx)));
+
+    common
+}
+
+/// Push every line of the node, such as when it was added or removed.
+fn push_node_lines(node: &VirtualNode, change: Change, depth: usize, lines: &mut Vec<DiffLine>) {
+    match node.resolve_memos() {
+        VirtualNode::Element(element) => {
+            push_line(lines, change, depth, open_tag(element));
+            push_inner_html_or_children(element, change, depth + 1, lines);
+            if let Some(close) = close_tag(element) {
+                push_line(lines, change, depth, close);
+            }
+        }
+        VirtualNode::Fragment(children) => {
+            push_line(lines, change, depth, "<>".to_string());
+            for child in children {
+                push_node_lines(child, change, depth + 1, lines);
+            }
+            push_line(lines, change, depth, "</>".to_string());
+        }
+        node => {
+            push_line(lines, change, depth, collapsed(node));
+        }
+    }
+}
+
+fn push_inner_html_or_children(
+    element: &VElement,
+    change: Change,
+    depth: usize,
+    lines: &mut Vec<DiffLine>,
+) {
+    match &element.special_attributes.dangerous_inner_html {
+        Some(inner_html) => push_line(
+            lines,
+            change,
+            depth,
+            format!("{{inner html: {:?}}}", inner_html),
+        ),
+        None => {
+            for child in element.children.iter() {
+                push_node_lines(child, change, depth, lines);
+            }
+        }
+    }
+}
+
+fn push_pretty_html_lines(node: &VirtualNode, depth: usize, lines: &mut Vec<DiffLine>) {
+    match node.resolve_memos() {
+        VirtualNode::Element(element) => {
+            let open = html_open_tag(element);
+            let close = close_tag(element);
+            let inner_html = element.special_attributes.dangerous_inner_html.as_ref();
+
+            if element.children.is_empty() && inner_html.is_none() {
+                let text = open + close.as_deref().unwrap_or("");
+                push_line(lines, Change::Unchanged, depth, text);
+                return;
+            }
+
+            push_line(lines, Change::Unchanged, depth, open);
+            match inner_html {
+                Some(inner_html) => {
+                    push_line(lines, Change::Unchanged, depth + 1, inner_html.clone());
+                }
+                None => {
+                    for child in element.children.iter() {
+                        push_pretty_html_lines(child, depth + 1, lines);
+                    }
+                }
+            }
+            if let Some(close) = close {
+                push_line(lines, Change::Unchanged, depth, close);
+            }
+        }
+        VirtualNode::Fragment(children) => {
+            for child in children {
+                push_pretty_html_lines(child, depth, lines);
+            }
+        }
+        node => {
+            push_line(lines, Change::Unchanged, depth, node.to_string());
+        }
+    }
+}
+
+fn push_line(lines: &mut Vec<DiffLine>, change: Change, depth: usize, text: String) {
+    lines.push(DiffLine {
+        change,
+        depth,
+        text,
+    });
+}
+
+/// A single line that represents a node, such as `<li class="item">…</li>` or `"Hello"`.
+fn collapsed(node: &VirtualNode) -> String {
+    match node {
+        VirtualNode::Text(text) => format!("{:?}", text.text),
+        VirtualNode::Comment(comment) => format!("<!--{}-->", comment.text),
+        VirtualNode::Element(element) => {
+            let open = open_tag(element);
+            let close = close_tag(element).unwrap_or_default();
+
+            let has_content = !element.children.is_empty()
+                || element.special_attributes.dangerous_inner_html.is_some();
+            if !has_content {
+                return open + &close;
+            }
+
+            let html = node.to_string();
+            if !element.events.has_events() && html.len() <= MAX_UNCHANGED_LINE_LEN {
+                html
+            } else {
+                format!("{}…{}", open, close)
+            }
+        }
+        VirtualNode::Fragment(_) => "<>…</>".to_string(),
+        VirtualNode::Memo(memo) => collapsed(memo.view()),
+    }
+}
+
+/// The opening tag as HTML, with the attributes sorted by name.
+fn html_open_tag(element: &VElement) -> String {
+    let mut open = format!("<{}", element.tag);
+    for (name, value) in sorted_attributes(element) {
+        match value.as_ref() {
+            AttributeValue::String(value) => {
+                open += &format!(r#" {}="{}""#, name, EscapeHtml(value));
+            }
+            AttributeValue::Bool(true) => open += &format!(" {}", name),
+            AttributeValue::Bool(false) => {}
+        }
+    }
+    open + ">"
+}
+
+/// The opening tag along with everything else about the element that can make it unequal to
+/// another element with the same children, such as
+/// `<input class="todo" prop:indeterminate=true onclick=…>`.
+///
+/// Attributes, properties and events are sorted by name so that the same element always has
+/// the same opening tag.
+fn open_tag(element: &VElement) -> String {
+    let mut open = format!("<{}", element.tag);
+    for (name, value) in sorted_attributes(element) {
+        match value.as_ref() {
+            AttributeValue::String(value) => {
+                open += &format!(r#" {}="{}""#, name, EscapeHtml(value));
+            }
+            AttributeValue::Bool(value) => open += &format!(" {}={}", name, value),
+        }
+    }
+
+    let mut properties: Vec<_> = element.properties.iter().collect();
+    properties.sort_by(|a, b| a.0.cmp(b.0));
+    for (name, value) in properties {
+        match value {
+            PropertyValue::String(value) => open += &format!(" prop:{}={:?}", name, value),
+            PropertyValue::Bool(value) => open += &format!(" prop:{}={}", name, value),
+            PropertyValue::Number(value) => open += &format!(" prop:{}={}", name, value),
+            #[cfg(target_arch = "wasm32")]
+            PropertyValue::JsValue(value) => open += &format!(" prop:{}={:?}", name, value),
+        }
+    }
+
+    let mut events: Vec<_> = element
+        .events
+        .events()
+        .keys()
+        .map(event_attribute)
+        .collect();
+    events.sort_unstable();
+    for event in events {
+        open += &format!(" {}=…", event);
+    }
+
+    let special = &element.special_attributes;
+    if let Some(key) = special.on_create_element_key() {
+        open += &format!(" on_create_element={:?}", key);
+    }
+    if let Some(key) = special.on_remove_element_key() {
+        open += &format!(" on_remove_element={:?}", key);
+    }
+
+    open + ">"
+}
+
+/// The event's name as it is written in the `html!` macro, such as `document:onkeydown.prevent`.
+fn event_attribute(name: &EventName) -> String {
+    let mut attribute = match name.listener_target() {
+        EventListenerTarget::Element => String::new(),
+        EventListenerTarget::Window => "window:".to_string(),
+        EventListenerTarget::Document => "document:".to_string(),
+    };
+    attribute += name.with_on_prefix();
+
+    let options = name.options();
+    for (modifier, is_set) in [
+        ("prevent", options.prevent_default),
+        ("passive", options.passive),
+        ("capture", options.capture),
+        ("once", options.once),
+    ] {
+        if is_set {
+            attribute += ".";
+            attribute += modifier;
+        }
+    }
+
+    attribute
+}
+
+/// The attributes sorted by name, with the element's [`VElement.classes`] and
+/// [`VElement.styles`] merged into the `class` and `style` attributes.
+fn sorted_attributes(element: &VElement) -> Vec<(&str, Cow<'_, AttributeValue>)> {
+    let mut attrs = element.rendered_attributes();
+    attrs.sort_by(|a, b| a.0.cmp(b.0));
+    attrs
+}
+
+fn close_tag(element: &VElement) -> Option<String> {
+    let mut close = String::new();
+    element.write_close_tag(&mut close).unwrap();
+    Some(close).filter(|close| !close.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VText;
+
+    /// Verify that equal nodes do not have a diff.
+    #[test]
+    fn equal_nodes() {
+        assert_eq!(view("a", "Hello").tree_diff(&view("a", "Hello")), None);
+    }
+
+    /// Verify that event handlers are compared by their names, the same way that the diff shows
+    /// them.
+    #[test]
+    fn events_compared_by_name() {
+        let button = |event: &'static str| {
+            let mut button = VElement::new("button");
+            button.events.insert_no_args(
+                event.into(),
+                std::rc::Rc::new(std::cell::RefCell::new(|| {})),
+            );
+            VirtualNode::from(button)
+        };
+
+        assert_eq!(button("onclick").tree_diff(&button("onclick")), None);
+        assert_eq!(
+            button("onclick")
+                .tree_diff(&button("onclick.once"))
+                .unwrap()
+                .to_string(),
+            "- <button onclick=…>\n+ <button onclick.once=…>\n  </button>\n"
+        );
+    }
+
+    /// Verify that we show which attributes and text changed, and collapse unchanged children.
+    #[test]
+    fn changed_attributes_and_text() {
+        let diff = view("a", "Hello").tree_diff(&view("b", "Goodbye")).unwrap();
+
+        assert_eq!(
+            diff.to_string(),
+            r#"  <div>
+    <h1>Title</h1>
+-   <span class="a" hidden=true>
++   <span class="b" hidden=true>
+-     "Hello"
++     "Goodbye"
+    </span>
+  </div>
+"#
+        );
+    }
+
+    /// Verify that we show which children were added and removed.
+    #[test]
+    fn added_and_removed_children() {
+        let left = list(&["a", "b", "c"]);
+        let right = list(&["b", "c", "d"]);
+
+        assert_eq!(
+            left.tree_diff(&right).unwrap().to_string(),
+            r#"  <ul>
+-   <li>
+-     "a"
+-   </li>
+    <li>b</li>
+    <li>c</li>
++   <li>
++     "d"
++   </li>
+  </ul>
+"#
+        );
+    }
+
+    /// Verify that we show when two nodes are different kinds of nodes.
+    #[test]
+    fn replaced_node() {
+        let left = VirtualNode::text("Hello");
+        let right = VirtualNode::comment("Hello");
+
+        assert_eq!(
+            left.tree_diff(&right).unwrap().to_string(),
+            "- \"Hello\"\n+ <!--Hello-->\n"
+        );
+    }
+
+    /// Verify that elements that only differ in their events are shown as different.
+    #[test]
+    fn changed_events() {
+        let left = VirtualNode::element("button");
+        let mut right = VElement::new("button");
+        right.events.insert_no_args(
+            crate::event::EventName::ONCLICK,
+            std::rc::Rc::new(std::cell::RefCell::new(|| {})),
+        );
+
+        assert_eq!(
+            left.tree_diff(&right.into()).unwrap().to_string(),
+            "- <button>\n+ <button onclick=…>\n  </button>\n"
+        );
+    }
+
+    /// Verify that the colorized diff colors the removed and added lines.
+    #[test]
+    fn colorized() {
+        let diff = VirtualNode::text("a").tree_diff(&VirtualNode::text("b"));
+
+        assert_eq!(
+            diff.unwrap().colorized(),
+            "\x1b[31m- \"a\"\x1b[0m\n\x1b[32m+ \"b\"\x1b[0m\n"
+        );
+    }
+
+    /// Verify that we render nodes on their own lines, indented by their depth.
+    #[test]
+    fn pretty_html() {
+        let mut node = list(&["a", "b"]);
+        let ul = node.as_velement_mut().unwrap();
+        ul.attrs.insert("id".into(), "list".into());
+        ul.attrs.insert("class".into(), "items".into());
+        ul.children.push(VirtualNode::element("br"));
+        ul.children
+            .push(VirtualNode::Fragment(vec![VirtualNode::comment("end")]));
+
+        assert_eq!(
+            node.to_pretty_html(),
+            r#"<ul class="items" id="list">
+  <li>
+    a
+  </li>
+  <li>
+    b
+  </li>
+  <br>
+  <!--end-->
+</ul>
+"#
+        );
+    }
+
+    /// Verify that we diff lines using their longest common subsequence.
+    #[test]
+    fn lines() {
+        let lines = diff_lines(&["a", "b", "c", "d"], &["a", "c", "e", "d"]);
+
+        assert_eq!(
+            DiffLines(&lines).colorized(),
+            format!(
+                "  a\n{}- b{}\n  c\n{}+ e{}\n  d\n",
+                RED, RESET, GREEN, RESET
+            )
+        );
+    }
+
+    fn view(class: &str, text: &str) -> VirtualNode {
+        let mut span = VElement::new("span");
+        span.attrs.insert("class".into(), class.into());
+        span.attrs.insert("hidden".into(), true.into());
+        span.children.push(VText::new(text).into());
+
+        let mut h1 = VElement::new("h1");
+        h1.children.push(VirtualNode::text("Title"));
+
+        let mut div = VElement::new("div");
+        div.children.push(h1.into());
+        div.children.push(span.into());
+        div.into()
+    }
+
+    fn list(items: &[&str]) -> VirtualNode {
+        let mut ul = VElement::new("ul");
+        for item in items {
+            let mut li = VElement::new("li");
+            li.children.push(VirtualNode::text(*item));
+            ul.children.push(li.into());
+        }
+        ul.into()
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+#[cfg(target_arch = "wasm32")]
+use crate::create_element::set_virtual_node_marker;
+#[cfg(target_arch = "wasm32")]
+use web_sys::Comment;
+
+/// Represents a comment node, such as `<!-- some comment -->`
+#[derive(PartialEq)]
+pub struct VComment {
+    pub text: String,
+}
+
+impl VComment {
+    /// Create an new `VComment` instance with the specified text.
+    pub fn new<S>(text: S) -> Self
+    where
+        S: Into<String>,
+    {
+        VComment { text: text.into() }
+    }
+
+    /// Return a `Comment` node from a `VirtualNode`, typically right before adding it
+    /// into the DOM.
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn create_comment_node(&self) -> Comment {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let comment = document.create_comment(&self.text);
+
+        // The marker is what tells user comments apart from the `TEXT_SEPARATOR` comments that we insert
+        // in between neighboring text nodes, which are never marked.
+        set_virtual_node_marker(&comment);
+
+        comment
+    }
+}
+
+impl From<&str> for VComment {
+    fn from(text: &str) -> Self {
+        VComment {
+            text: text.to_string(),
+        }
+    }
+}
+
+impl From<String> for VComment {
+    fn from(text: String) -> Self {
+        VComment { text }
+    }
+}
+
+impl fmt::Debug for VComment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Comment({})", self.text)
+    }
+}
+
+// Turn a VComment into an HTML string.
+//
+// Character references are not decoded within comments, so we leave the text as is, other than
+// replacing the `>` of anything that would end the comment early. This keeps conditional comments
+// such as `<!--[if IE]><p>Old browser</p><![endif]-->` the same as the comment that the browser
+// creates.
+impl fmt::Display for VComment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut text = self
+            .text
+            .replace("-->", "--&gt;")
+            .replace("--!>", "--!&gt;");
+
+        if text.starts_with('>') {
+            text.replace_range(..1, "&gt;");
+        } else if text.starts_with("->") {
+            text.replace_range(..2, "-&gt;");
+        }
+
+        write!(f, "<!--{}-->", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VirtualNode;
+
+    /// Verify that a comment's text cannot end the comment and create elements.
+    #[test]
+    fn comment_cannot_be_closed_early() {
+        let comment = VComment::new(" a --><script></script>--!><!-- ");
+
+        assert_eq!(
+            comment.to_string(),
+            "<!-- a --&gt;<script></script>--!&gt;<!-- -->"
+        );
+
+        assert_eq!(VComment::new(">a").to_string(), "<!--&gt;a-->");
+        assert_eq!(VComment::new("->a").to_string(), "<!---&gt;a-->");
+    }
+
+    /// Verify that conditional comments are rendered as is, so that they can be parsed back into
+    /// the same comment.
+    #[test]
+    fn conditional_comment_round_trips() {
+        let comment = VirtualNode::comment("[if IE]><p>Old browser</p><![endif]");
+
+        let html = comment.to_string();
+        assert_eq!(html, "<!--[if IE]><p>Old browser</p><![endif]-->");
+
+        assert_eq!(VirtualNode::from_html(&html).unwrap(), vec![comment]);
+    }
+}
@@ -1,14 +1,26 @@
+use std::borrow::{Borrow, Cow};
 use std::collections::HashMap;
 use std::fmt;
 
+use crate::escape_html::{is_raw_text_element, EscapeHtml, RawText};
 use crate::event::Events;
-use crate::VirtualNode;
+use crate::{VirtualNode, TEXT_SEPARATOR};
 
 pub use self::attribute_value::*;
+pub use self::classes::*;
+pub use self::node_ref::*;
+pub use self::portal::*;
+pub use self::property_value::*;
 pub use self::special_attributes::*;
+pub use self::styles::*;
 
 mod attribute_value;
+mod classes;
+mod node_ref;
+mod portal;
+mod property_value;
 mod special_attributes;
+mod styles;
 
 #[derive(PartialEq)]
 pub struct VElement {
@@ -16,6 +28,20 @@ pub struct VElement {
     pub tag: String,
     /// HTML attributes such as id, class, style, etc
     pub attrs: HashMap<String, AttributeValue>,
+    /// DOM properties such as indeterminate, scrollTop, etc, that get set directly on your real
+    /// DOM element instead of being set as attributes.
+    ///
+    /// Properties only exist in the DOM, so they are not rendered when turning the element into
+    /// an HTML string.
+    pub properties: HashMap<String, PropertyValue>,
+    /// Classes such as `btn primary`, which get patched one class at a time.
+    ///
+    /// They are rendered into the `class` attribute when turning the element into an HTML string.
+    pub classes: Classes,
+    /// Inline styles such as `color: red`, which get patched one property at a time.
+    ///
+    /// They are rendered into the `style` attribute when turning the element into an HTML string.
+    pub styles: Styles,
     /// Events that will get added to your real DOM element via `.addEventListener`
     ///
     /// Events natively handled in HTML such as onclick, onchange, oninput and others
@@ -36,11 +62,35 @@ impl VElement {
         VElement {
             tag: tag.into(),
             attrs: HashMap::new(),
+            properties: HashMap::new(),
+            classes: Classes::new(),
+            styles: Styles::new(),
             events: Events::new(),
             children: vec![],
             special_attributes: SpecialAttributes::default(),
         }
     }
+
+    /// The custom element that a customized built-in element is, such as the "fancy-button" in
+    /// `<button is="fancy-button"></button>`.
+    ///
+    /// The browser only reads `is` when the element gets created, so changing it replaces the
+    /// element.
+    pub fn custom_element_is(&self) -> Option<&str> {
+        match self.attrs.get("is") {
+            Some(AttributeValue::String(is)) => Some(is.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Whether or not this is a declarative shadow root, such as
+    /// `<template shadowrootmode="open"></template>`.
+    ///
+    /// When it gets turned into a DOM node its children get placed into a shadow root that is
+    /// attached to its parent element.
+    pub fn is_declarative_shadow_root(&self) -> bool {
+        self.tag == "template" && self.attrs.contains_key("shadowrootmode")
+    }
 }
 
 impl fmt::Debug for VElement {
@@ -56,31 +106,184 @@ impl fmt::Debug for VElement {
 impl fmt::Display for VElement {
     // Turn a VElement and all of it's children (recursively) into an HTML string
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "<{}", self.tag).unwrap();
+        self.write_open_tag(f)?;
 
-        for (attr, value) in self.attrs.iter() {
-            match value {
-                AttributeValue::String(value_str) => {
-                    write!(f, r#" {}="{}""#, attr, value_str)?;
-                }
-                AttributeValue::Bool(value_bool) => {
-                    if *value_bool {
-                        write!(f, " {}", attr)?;
-                    }
+        // Just like when we create a DOM element, the inner HTML takes the place of any children.
+        //
+        // A portal's children get placed into a different element, so they are only rendered in
+        // the browser.
+        match &self.special_attributes.dangerous_inner_html {
+            Some(inner_html) => {
+                write!(f, "{}", inner_html)?;
+            }
+            None if self.special_attributes.portal().is_some() => {}
+            None if is_raw_text_element(&self.tag) => {
+                write_raw_text_children(self.dom_children(), &self.tag, f)?;
+            }
+            None => {
+                write_children(self.dom_children(), f)?;
+            }
+        }
+
+        self.write_close_tag(f)
+    }
+}
+
+/// Write the HTML for the nodes that get placed into an element or fragment.
+pub(crate) fn write_children<W: fmt::Write>(children: Vec<&VirtualNode>, w: &mut W) -> fmt::Result {
+    let mut previous_node_was_text = false;
+
+    for child in children {
+        let child = child.resolve_memos();
+
+        // Just like when we create a DOM element, we separate neighboring text nodes
+        // so that the browser does not merge them into one text node when it parses
+        // our HTML. This lets us hydrate server side rendered text nodes.
+        let is_text = child.as_vtext_ref().is_some();
+        if is_text && previous_node_was_text {
+            write!(w, "<!--{}-->", TEXT_SEPARATOR)?;
+        }
+        previous_node_was_text = is_text;
+
+        write!(w, "{}", child)?;
+    }
+
+    Ok(())
+}
+
+/// Write the children of a raw text element, such as a `<script>`, whose text the browser does not
+/// parse as HTML.
+///
+/// A separator in between neighboring text nodes would become part of the text, so we leave it
+/// out.
+fn write_raw_text_children<W: fmt::Write>(
+    children: Vec<&VirtualNode>,
+    tag: &str,
+    w: &mut W,
+) -> fmt::Result {
+    for child in children {
+        match child.resolve_memos() {
+            VirtualNode::Text(text) => {
+                let text = RawText {
+                    text: &text.text,
+                    tag,
+                };
+                write!(w, "{}", text)?;
+            }
+            child => write!(w, "{}", child)?,
+        };
+    }
+
+    Ok(())
+}
+
+impl VElement {
+    /// Write the opening tag, such as `<div id="foo">`.
+    pub(crate) fn write_open_tag<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        write!(w, "<{}", self.tag)?;
+        write_attributes(self.rendered_attributes(), w)?;
+        write!(w, ">")
+    }
+
+    /// The attributes that the element gets rendered with, where the [`VElement.classes`] and
+    /// [`VElement.styles`] are merged into the `class` and `style` attributes.
+    pub(crate) fn rendered_attributes(&self) -> Vec<(&str, Cow<'_, AttributeValue>)> {
+        let class = self.class_attribute();
+        let style = self.style_attribute();
+
+        let mut attrs: Vec<_> = self
+            .attrs
+            .iter()
+            .filter(|(name, _)| {
+                !(class.is_some() && *name == "class" || style.is_some() && *name == "style")
+            })
+            .map(|(name, value)| (name.as_str(), Cow::Borrowed(value)))
+            .collect();
+
+        if let Some(class) = class {
+            attrs.push(("class", Cow::Owned(AttributeValue::String(class))));
+        }
+        if let Some(style) = style {
+            attrs.push(("style", Cow::Owned(AttributeValue::String(style))));
+        }
+
+        attrs
+    }
+
+    /// The value of the `class` attribute when the element has [`VElement.classes`], including
+    /// the classes in a `class` attribute in the `attrs`, if there is one.
+    ///
+    /// `None` if there are no classes, in which case any `class` attribute in the `attrs` is used
+    /// as is.
+    pub(crate) fn class_attribute(&self) -> Option<String> {
+        if self.classes.is_empty() {
+            return None;
+        }
+
+        match self.attrs.get("class").and_then(|class| class.as_string()) {
+            Some(attr) => {
+                let mut classes = Classes::from(attr);
+                for class in self.classes.iter() {
+                    classes.insert(class);
                 }
+                Some(classes.to_string())
             }
+            None => Some(self.classes.to_string()),
         }
+    }
 
-        write!(f, ">")?;
+    /// The value of the `style` attribute when the element has [`VElement.styles`], including
+    /// the value of a `style` attribute in the `attrs`, if there is one.
+    ///
+    /// `None` if there are no styles, in which case any `style` attribute in the `attrs` is used
+    /// as is.
+    pub(crate) fn style_attribute(&self) -> Option<String> {
+        if self.styles.is_empty() {
+            return None;
+        }
 
-        for child in self.children.iter() {
-            write!(f, "{}", child.to_string())?;
+        match self.attrs.get("style").and_then(|style| style.as_string()) {
+            Some(attr) if !attr.trim().is_empty() => Some(format!(
+                "{}; {}",
+                attr.trim().trim_end_matches(';'),
+                self.styles
+            )),
+            _ => Some(self.styles.to_string()),
         }
+    }
 
+    /// Write the closing tag, such as `</div>`. Self closing tags such as `<br>` do not have one.
+    pub(crate) fn write_close_tag<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
         if !html_validation::is_self_closing(&self.tag) {
-            write!(f, "</{}>", self.tag)?;
+            write!(w, "</{}>", self.tag)?;
         }
 
         Ok(())
     }
 }
+
+/// Write attributes, such as ` id="foo" disabled`, escaping their values.
+pub(crate) fn write_attributes<W, N, V>(
+    attrs: impl IntoIterator<Item = (N, V)>,
+    w: &mut W,
+) -> fmt::Result
+where
+    W: fmt::Write,
+    N: fmt::Display,
+    V: Borrow<AttributeValue>,
+{
+    for (attr, value) in attrs {
+        match value.borrow() {
+            AttributeValue::String(value_str) => {
+                write!(w, r#" {}="{}""#, attr, EscapeHtml(value_str))?;
+            }
+            AttributeValue::Bool(value_bool) => {
+                if *value_bool {
+                    write!(w, " {}", attr)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use std::fmt::{Display, Formatter};
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
 /// The value associated with an element's attribute.
@@ -55,6 +56,7 @@ macro_rules! to_string_impls {
 }
 to_string_impls!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
 
+#[cfg(target_arch = "wasm32")]
 impl Into<JsValue> for AttributeValue {
     fn into(self) -> JsValue {
         match self {
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+/// An element's classes, such as the `btn primary` in `<button class="btn primary"></button>`.
+///
+/// The classes are a set. Adding a class that is already present does nothing, and two sets of
+/// classes are equal if they contain the same classes in any order.
+///
+/// Each class is patched on its own using `classList.add` and `classList.remove`, so classes
+/// that were added to the DOM element by other code are left alone.
+///
+/// The `html!` macro accepts anything that implements `Into<Classes>` as the `class` value.
+/// Every string is split on whitespace, so `["btn primary", "large"]` has three classes.
+///
+/// ```
+/// # use virtual_node::Classes;
+/// let from_str = Classes::from("btn primary");
+/// let from_array = Classes::from(["primary", "btn", ""]);
+///
+/// assert_eq!(from_str, from_array);
+/// assert_eq!(from_array.to_string(), "primary btn");
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Classes {
+    classes: Vec<String>,
+}
+
+impl Classes {
+    /// Create an empty set of classes.
+    pub fn new() -> Self {
+        Classes::default()
+    }
+
+    /// Add every whitespace separated class in `classes`, such as `"btn primary"`.
+    pub fn insert<S: AsRef<str>>(&mut self, classes: S) {
+        for class in classes.as_ref().split_whitespace() {
+            if !self.contains(class) {
+                self.classes.push(class.to_string());
+            }
+        }
+    }
+
+    /// Remove a class, returning whether or not it was present.
+    pub fn remove(&mut self, class: &str) -> bool {
+        match self.classes.iter().position(|c| c == class) {
+            Some(idx) => {
+                self.classes.remove(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether or not the class is present.
+    pub fn contains(&self, class: &str) -> bool {
+        self.classes.iter().any(|c| c == class)
+    }
+
+    /// Iterate over the classes in the order that they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.classes.iter().map(|class| class.as_str())
+    }
+
+    /// The number of classes.
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Whether or not there are no classes.
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+}
+
+impl PartialEq for Classes {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|class| other.contains(class))
+    }
+}
+
+/// Serializes the classes the same way that they are rendered into a `class` attribute, such as
+/// `btn primary`.
+impl fmt::Display for Classes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, class) in self.classes.iter().enumerate() {
+            if idx != 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", class)?;
+        }
+
+        Ok(())
+    }
+}
+
+mod from_impls {
+    //! These `From` implementations are used by the `html-macro` to convert the expression in
+    //! `class=expression` into [`Classes`].
+    //! To find this use case, search for `#value.into()` within the `crates/html-macro` crate.
+
+    use super::*;
+
+    impl From<&str> for Classes {
+        fn from(classes: &str) -> Self {
+            std::iter::once(classes).collect()
+        }
+    }
+
+    impl From<String> for Classes {
+        fn from(classes: String) -> Self {
+            std::iter::once(classes).collect()
+        }
+    }
+
+    impl From<&String> for Classes {
+        fn from(classes: &String) -> Self {
+            std::iter::once(classes).collect()
+        }
+    }
+
+    impl From<&Classes> for Classes {
+        fn from(classes: &Classes) -> Self {
+            classes.clone()
+        }
+    }
+
+    impl<S: AsRef<str>, const N: usize> From<[S; N]> for Classes {
+        fn from(classes: [S; N]) -> Self {
+            classes.iter().collect()
+        }
+    }
+
+    impl<S: AsRef<str>> From<Vec<S>> for Classes {
+        fn from(classes: Vec<S>) -> Self {
+            classes.iter().collect()
+        }
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for Classes {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut classes = Classes::new();
+        for class in iter {
+            classes.insert(class);
+        }
+        classes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that classes are split on whitespace and deduplicated.
+    #[test]
+    fn split_and_deduplicate() {
+        let classes = Classes::from(vec!["  btn  primary ", "", "btn", "large"]);
+
+        assert_eq!(
+            classes.iter().collect::<Vec<_>>(),
+            vec!["btn", "primary", "large"]
+        );
+    }
+
+    /// Verify that the order of the classes does not affect equality.
+    #[test]
+    fn equality_ignores_order() {
+        assert_eq!(Classes::from("a b"), Classes::from("b a"));
+        assert_ne!(Classes::from("a b"), Classes::from("a"));
+        assert_ne!(Classes::from("a"), Classes::from("a b"));
+    }
+}
@@ -0,0 +1,98 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsCast;
+
+#[cfg(target_arch = "wasm32")]
+type RefElement = web_sys::Element;
+// Elements are only created in the browser, so on other targets a `NodeRef` is always empty.
+#[cfg(not(target_arch = "wasm32"))]
+type RefElement = std::convert::Infallible;
+
+/// A handle to the real DOM element that a virtual node was turned into.
+///
+/// Pass a `NodeRef` to an element using the `ref` attribute and Percy will point it at the
+/// element whenever the element is created, including when it replaces a different element, and
+/// will clear it when the element is removed.
+///
+/// Cloning a `NodeRef` gives you another handle to the same element.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use virtual_node::{NodeRef, VirtualNode};
+/// let input_ref = NodeRef::new();
+///
+/// let mut node = VirtualNode::element("input");
+/// node.as_velement_mut()
+///     .unwrap()
+///     .special_attributes
+///     .set_node_ref(input_ref.clone());
+///
+/// // ... After the node gets turned into a real DOM element ...
+///
+/// # #[cfg(target_arch = "wasm32")]
+/// if let Some(input) = input_ref.cast::<web_sys::HtmlElement>() {
+///     input.focus().unwrap();
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct NodeRef(Rc<RefCell<Option<RefElement>>>);
+
+impl NodeRef {
+    /// Create an empty `NodeRef`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether or not the `NodeRef` currently points at an element.
+    pub fn is_set(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl NodeRef {
+    /// The element that the `NodeRef` points at.
+    pub fn get(&self) -> Option<web_sys::Element> {
+        self.0.borrow().clone()
+    }
+
+    /// The element that the `NodeRef` points at, cast to a more specific type such as
+    /// `web_sys::HtmlInputElement`.
+    ///
+    /// Returns `None` if the `NodeRef` is empty or the element is not a `T`.
+    pub fn cast<T: JsCast>(&self) -> Option<T> {
+        self.get()?.dyn_into().ok()
+    }
+
+    pub(crate) fn set(&self, element: &web_sys::Element) {
+        *self.0.borrow_mut() = Some(element.clone());
+    }
+
+    /// Only clears the `NodeRef` if it still points at the given element, since the `NodeRef`
+    /// might have already been moved to a different element during the same patch.
+    pub(crate) fn clear_if_pointing_at(&self, element: &web_sys::Element) {
+        let mut current = self.0.borrow_mut();
+        if current.as_ref() == Some(element) {
+            *current = None;
+        }
+    }
+}
+
+/// Two `NodeRef`s are equal if one is a clone of the other.
+impl PartialEq for NodeRef {
+    fn eq(&self, rhs: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &rhs.0)
+    }
+}
+
+impl fmt::Debug for NodeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeRef")
+            .field("is_set", &self.is_set())
+            .finish()
+    }
+}
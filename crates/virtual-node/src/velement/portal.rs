@@ -0,0 +1,77 @@
+/// The element that a portal places its children into.
+///
+/// An element with a portal stays where it is, but its children get placed into a container that
+/// gets appended to the target, such as the end of the `<body>`. This lets modals, tooltips and
+/// toasts escape their ancestors' `overflow: hidden` and stacking contexts.
+///
+/// The children are still patched along with the rest of your application, their events still
+/// bubble up to the portal's ancestors and the container is removed along with the portal.
+///
+/// # Examples
+///
+/// ```
+/// # use virtual_node::{PortalTarget, VirtualNode};
+/// let mut portal = VirtualNode::element("div");
+/// portal
+///     .as_velement_mut()
+///     .unwrap()
+///     .special_attributes
+///     .set_portal("body");
+///
+/// assert_eq!(
+///     portal.as_velement_ref().unwrap().special_attributes.portal(),
+///     Some(&PortalTarget::Selector("body".to_string()))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalTarget {
+    /// The first element that matches a CSS selector, such as `body` or `#modals`.
+    Selector(String),
+    /// A DOM element.
+    #[cfg(target_arch = "wasm32")]
+    Element(web_sys::Element),
+}
+
+#[cfg(target_arch = "wasm32")]
+impl PortalTarget {
+    /// The target element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no element matches the selector.
+    pub(crate) fn element(&self, document: &web_sys::Document) -> web_sys::Element {
+        match self {
+            PortalTarget::Selector(selector) => document
+                .query_selector(selector)
+                .unwrap()
+                .unwrap_or_else(|| panic!("No element matches the portal target {:?}", selector)),
+            PortalTarget::Element(element) => element.clone(),
+        }
+    }
+}
+
+impl From<&str> for PortalTarget {
+    fn from(selector: &str) -> Self {
+        PortalTarget::Selector(selector.to_string())
+    }
+}
+
+impl From<String> for PortalTarget {
+    fn from(selector: String) -> Self {
+        PortalTarget::Selector(selector)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<web_sys::Element> for PortalTarget {
+    fn from(element: web_sys::Element) -> Self {
+        PortalTarget::Element(element)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl From<&web_sys::Element> for PortalTarget {
+    fn from(element: &web_sys::Element) -> Self {
+        PortalTarget::Element(element.clone())
+    }
+}
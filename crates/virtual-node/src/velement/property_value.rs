@@ -0,0 +1,148 @@
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::JsValue;
+
+/// The value of an element's DOM property.
+///
+/// Unlike attributes, properties are set directly on the DOM element, such as
+/// `element.indeterminate = true`. This lets you set things that can't be expressed as an
+/// attribute, such as `scrollTop`, `muted` or the object properties of custom elements.
+///
+/// For `<input prop:indeterminate=true />` the property value would be
+/// `PropertyValue::Bool(true)`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PropertyValue {
+    /// A string property such as `prop:title="Save"`
+    String(String),
+    /// A boolean property such as `prop:muted=true`
+    Bool(bool),
+    /// A number property such as `prop:scrollTop=100`
+    Number(f64),
+    /// Any other JavaScript value, such as an object for a custom element's property.
+    #[cfg(target_arch = "wasm32")]
+    JsValue(JsValue),
+}
+
+impl PropertyValue {
+    /// If the property is a string, return it. Otherwise return None.
+    pub fn as_string(&self) -> Option<&String> {
+        match self {
+            PropertyValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// If the property is a bool, return it. Otherwise return None.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PropertyValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// If the property is a number, return it. Otherwise return None.
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            PropertyValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+// Implements
+//   From<T> and From<&T> -> PropertyValue::Number(T as f64)
+macro_rules! to_number_impls {
+    ($ty:ty) => {
+        impl From<$ty> for PropertyValue {
+            fn from(val: $ty) -> Self {
+                PropertyValue::Number(val as f64)
+            }
+        }
+
+        impl From<&$ty> for PropertyValue {
+            fn from(val: &$ty) -> Self {
+                PropertyValue::Number(*val as f64)
+            }
+        }
+    };
+
+    ($ty:ty, $($tys:ty),*) => {
+        to_number_impls!( $ty );
+        to_number_impls! ( $($tys),* );
+    }
+}
+to_number_impls!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+#[cfg(target_arch = "wasm32")]
+impl From<PropertyValue> for JsValue {
+    fn from(value: PropertyValue) -> JsValue {
+        match value {
+            PropertyValue::String(s) => s.into(),
+            PropertyValue::Bool(b) => b.into(),
+            PropertyValue::Number(n) => n.into(),
+            PropertyValue::JsValue(v) => v,
+        }
+    }
+}
+
+mod from_impls {
+    //! These `From` implementations are used by the `html-macro` to convert the expression in
+    //! `prop:name=expression` into a [`PropertyValue`].
+    //! To find this use case, search for `#value.into()` within the `crates/html-macro` crate.
+
+    use super::*;
+
+    impl From<String> for PropertyValue {
+        fn from(s: String) -> Self {
+            PropertyValue::String(s)
+        }
+    }
+
+    impl From<&String> for PropertyValue {
+        fn from(s: &String) -> Self {
+            PropertyValue::String(s.to_string())
+        }
+    }
+
+    impl From<&str> for PropertyValue {
+        fn from(s: &str) -> Self {
+            PropertyValue::String(s.to_string())
+        }
+    }
+
+    impl From<bool> for PropertyValue {
+        fn from(b: bool) -> Self {
+            PropertyValue::Bool(b)
+        }
+    }
+
+    impl From<&bool> for PropertyValue {
+        fn from(b: &bool) -> Self {
+            PropertyValue::Bool(*b)
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    impl From<JsValue> for PropertyValue {
+        fn from(v: JsValue) -> Self {
+            PropertyValue::JsValue(v)
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    impl From<&JsValue> for PropertyValue {
+        fn from(v: &JsValue) -> Self {
+            PropertyValue::JsValue(v.clone())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_f64() {
+        assert_eq!(PropertyValue::from(100u32), PropertyValue::Number(100.));
+        assert_eq!(PropertyValue::from(&-2.5f32), PropertyValue::Number(-2.5));
+    }
+}
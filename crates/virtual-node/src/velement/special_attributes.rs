@@ -1,5 +1,9 @@
 use std::borrow::Cow;
+
+use crate::{NodeRef, PortalTarget};
+#[cfg(target_arch = "wasm32")]
 use std::cell::RefCell;
+#[cfg(target_arch = "wasm32")]
 use std::ops::DerefMut;
 
 /// A specially supported attributes.
@@ -13,6 +17,19 @@ pub struct SpecialAttributes {
     ///
     /// See [`SpecialAttributes.set_on_remove_element`] for more documentation.
     on_remove_element: Option<KeyAndElementFn>,
+    /// A function that gets called when the virtual node is patched over an element that has the
+    /// same key, which re-renders the component that was mounted into the element.
+    ///
+    /// See [`SpecialAttributes.set_on_render_component`] for more documentation.
+    on_render_component: Option<KeyAndElementFn>,
+    /// Points at the real DOM element while it exists.
+    ///
+    /// See [`SpecialAttributes.set_node_ref`] for more documentation.
+    node_ref: Option<NodeRef>,
+    /// The element that this element's children get placed into.
+    ///
+    /// See [`SpecialAttributes.set_portal`] for more documentation.
+    portal: Option<PortalTarget>,
     /// Allows setting the innerHTML of an element.
     ///
     /// # Danger
@@ -59,6 +76,7 @@ impl SpecialAttributes {
     ///     .special_attributes
     ///     .set_on_create_element(key, on_create_elem);
     /// ```
+    #[cfg(target_arch = "wasm32")]
     pub fn set_on_create_element<Key, Func>(&mut self, key: Key, func: Func)
     where
         Key: Into<Cow<'static, str>>,
@@ -79,11 +97,17 @@ impl SpecialAttributes {
     {
         self.on_create_element = Some(KeyAndElementFn {
             key: key.into(),
+            #[cfg(target_arch = "wasm32")]
             func: RefCell::new(ElementFunc::NoArgs(Box::new(func))),
         });
+
+        // Elements are only created in the browser, so on other targets we only need the key.
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = func;
     }
 
     /// If an `on_create_element` function was set, call it.
+    #[cfg(target_arch = "wasm32")]
     pub fn maybe_call_on_create_element(&self, element: &web_sys::Element) {
         if let Some(on_create_elem) = &self.on_create_element {
             on_create_elem.call(element.clone());
@@ -131,6 +155,7 @@ impl SpecialAttributes {
     ///     .special_attributes
     ///     .set_on_remove_element(key, on_remove_elem);
     /// ```
+    #[cfg(target_arch = "wasm32")]
     pub fn set_on_remove_element<Key, Func>(&mut self, key: Key, func: Func)
     where
         Key: Into<Cow<'static, str>>,
@@ -151,11 +176,17 @@ impl SpecialAttributes {
     {
         self.on_remove_element = Some(KeyAndElementFn {
             key: key.into(),
+            #[cfg(target_arch = "wasm32")]
             func: RefCell::new(ElementFunc::NoArgs(Box::new(func))),
         });
+
+        // Elements are only created in the browser, so on other targets we only need the key.
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = func;
     }
 
     /// If an `on_remove_element` function was set, call it.
+    #[cfg(target_arch = "wasm32")]
     pub fn maybe_call_on_remove_element(&self, element: &web_sys::Element) {
         if let Some(on_remove_elem) = &self.on_remove_element {
             on_remove_elem.call(element.clone());
@@ -165,16 +196,105 @@ impl SpecialAttributes {
     }
 }
 
+impl SpecialAttributes {
+    /// The key for the on render component function
+    pub fn on_render_component_key(&self) -> Option<&Cow<'static, str>> {
+        self.on_render_component.as_ref().map(|k| &k.key)
+    }
+
+    /// Set the [`SpecialAttributes.on_render_component`] function.
+    ///
+    /// Used by components, whose views get rendered into their own host element. When the
+    /// component's parent gets patched, the function gets called with the existing host element
+    /// if the old node had the same key, so that the component gets re-rendered while patching
+    /// instead of while its parent's view is being created.
+    #[cfg(target_arch = "wasm32")]
+    #[doc(hidden)]
+    pub fn set_on_render_component<Key, Func>(&mut self, key: Key, func: Func)
+    where
+        Key: Into<Cow<'static, str>>,
+        Func: FnMut(web_sys::Element) + 'static,
+    {
+        self.on_render_component = Some(KeyAndElementFn {
+            key: key.into(),
+            func: RefCell::new(ElementFunc::OneArg(Box::new(func))),
+        });
+    }
+
+    /// If an `on_render_component` function was set, call it.
+    #[cfg(target_arch = "wasm32")]
+    pub fn maybe_call_on_render_component(&self, element: &web_sys::Element) {
+        if let Some(on_render_component) = &self.on_render_component {
+            on_render_component.call(element.clone());
+        }
+    }
+}
+
+impl SpecialAttributes {
+    /// The [`NodeRef`] that points at this node's real DOM element.
+    pub fn node_ref(&self) -> Option<&NodeRef> {
+        self.node_ref.as_ref()
+    }
+
+    /// Set the [`SpecialAttributes.node_ref`].
+    ///
+    /// The `NodeRef` gets pointed at the real DOM element when the element is created, and gets
+    /// cleared when the element is removed or replaced.
+    ///
+    /// Unlike `on_create_element`, a `NodeRef` does not need a key. When one virtual node is
+    /// patched over another the existing element is kept, so a `NodeRef` that was already
+    /// pointing at it is left alone.
+    pub fn set_node_ref(&mut self, node_ref: NodeRef) {
+        self.node_ref = Some(node_ref);
+    }
+
+    /// If a `NodeRef` was set, point it at the element.
+    #[cfg(target_arch = "wasm32")]
+    pub fn maybe_set_node_ref(&self, element: &web_sys::Element) {
+        if let Some(node_ref) = &self.node_ref {
+            node_ref.set(element);
+        }
+    }
+
+    /// If a `NodeRef` was set and it still points at the element, clear it.
+    #[cfg(target_arch = "wasm32")]
+    pub fn maybe_clear_node_ref(&self, element: &web_sys::Element) {
+        if let Some(node_ref) = &self.node_ref {
+            node_ref.clear_if_pointing_at(element);
+        }
+    }
+}
+
+impl SpecialAttributes {
+    /// The element that this element's children get placed into, if this element is a portal.
+    pub fn portal(&self) -> Option<&PortalTarget> {
+        self.portal.as_ref()
+    }
+
+    /// Make this element a portal, so that its children get placed into a container that gets
+    /// appended to the `target` instead of being placed into this element.
+    ///
+    /// Changing an element's portal target replaces the element.
+    ///
+    /// See [`PortalTarget`].
+    pub fn set_portal(&mut self, target: impl Into<PortalTarget>) {
+        self.portal = Some(target.into());
+    }
+}
+
 struct KeyAndElementFn {
     key: Cow<'static, str>,
+    #[cfg(target_arch = "wasm32")]
     func: RefCell<ElementFunc>,
 }
 
+#[cfg(target_arch = "wasm32")]
 enum ElementFunc {
     NoArgs(Box<dyn FnMut()>),
     OneArg(Box<dyn FnMut(web_sys::Element)>),
 }
 
+#[cfg(target_arch = "wasm32")]
 impl KeyAndElementFn {
     fn call(&self, element: web_sys::Element) {
         match self.func.borrow_mut().deref_mut() {
@@ -0,0 +1,258 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+/// An element's inline styles, such as the `color: red; width: 10px` in
+/// `<div style="color: red; width: 10px"></div>`.
+///
+/// Styles are kept in the order that they were inserted in, since later declarations can
+/// override earlier ones (such as `margin-top` after `margin`).
+///
+/// Each property is patched on its own using `style.setProperty` and `style.removeProperty`, so
+/// styles that were set on the DOM element by other code are left alone.
+///
+/// The `html!` macro accepts anything that implements `Into<Styles>` as the `style` value.
+///
+/// ```
+/// # use virtual_node::Styles;
+/// let from_str = Styles::from("color: red; width: 10px");
+/// let from_pairs = Styles::from([("color", "red"), ("width", "10px")]);
+///
+/// assert_eq!(from_str, from_pairs);
+/// assert_eq!(from_str.to_string(), "color: red; width: 10px");
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Styles {
+    properties: Vec<(String, String)>,
+}
+
+impl Styles {
+    /// Create an empty set of styles.
+    pub fn new() -> Self {
+        Styles::default()
+    }
+
+    /// Set a property's value, such as `("color", "red")` or
+    /// `("color", "red !important")`.
+    ///
+    /// If the property is already set its value is replaced without changing its position.
+    pub fn insert<N: Into<String>, V: Into<String>>(&mut self, name: N, value: V) {
+        let name = name.into();
+        let value = value.into();
+
+        match self.properties.iter_mut().find(|(n, _)| *n == name) {
+            Some(existing) => existing.1 = value,
+            None => self.properties.push((name, value)),
+        }
+    }
+
+    /// Remove a property, returning its value if it was set.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let idx = self.properties.iter().position(|(n, _)| n == name)?;
+        Some(self.properties.remove(idx).1)
+    }
+
+    /// Get a property's value.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterate over the properties and their values in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.properties
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// The number of properties.
+    pub fn len(&self) -> usize {
+        self.properties.len()
+    }
+
+    /// Whether or not there are no properties.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+
+    /// Split a style's value into the value and the priority that `style.setProperty` expects,
+    /// such as `red !important` into `("red", "important")`.
+    ///
+    /// The priority is empty when the value does not end with `!important`.
+    pub fn split_priority(value: &str) -> (&str, &str) {
+        let trimmed = value.trim_end();
+
+        match trimmed.len().checked_sub("!important".len()) {
+            Some(idx)
+                if trimmed.is_char_boundary(idx)
+                    && trimmed[idx..].eq_ignore_ascii_case("!important") =>
+            {
+                (trimmed[..idx].trim_end(), "important")
+            }
+            _ => (value, ""),
+        }
+    }
+}
+
+/// Serializes the styles the same way that they are rendered into a `style` attribute, such as
+/// `color: red; width: 10px`.
+impl fmt::Display for Styles {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (idx, (name, value)) in self.properties.iter().enumerate() {
+            if idx != 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}: {}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a style attribute's declarations, such as `color: red; width: 10px`.
+///
+/// Semicolons and colons that are inside of quotes or parentheses, such as in
+/// `background: url("a;b.png")`, do not split declarations.
+fn parse_declarations(css: &str) -> Styles {
+    let mut styles = Styles::new();
+    let mut rest = css;
+
+    loop {
+        let end = find_outside_of_quotes_and_parens(rest, ';').unwrap_or(rest.len());
+        let declaration = &rest[..end];
+
+        if let Some(colon) = find_outside_of_quotes_and_parens(declaration, ':') {
+            let name = declaration[..colon].trim();
+            let value = declaration[colon + 1..].trim();
+
+            if !name.is_empty() {
+                styles.insert(name, value);
+            }
+        }
+
+        if end == rest.len() {
+            break;
+        }
+        rest = &rest[end + 1..];
+    }
+
+    styles
+}
+
+fn find_outside_of_quotes_and_parens(text: &str, separator: char) -> Option<usize> {
+    let mut quote = None;
+    let mut paren_depth = 0usize;
+
+    for (idx, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => paren_depth += 1,
+            (None, ')') => paren_depth = paren_depth.saturating_sub(1),
+            (None, c) if c == separator && paren_depth == 0 => return Some(idx),
+            (None, _) => {}
+        };
+    }
+
+    None
+}
+
+mod from_impls {
+    //! These `From` implementations are used by the `html-macro` to convert the expression in
+    //! `style=expression` into [`Styles`].
+    //! To find this use case, search for `#value.into()` within the `crates/html-macro` crate.
+
+    use super::*;
+
+    impl From<&str> for Styles {
+        fn from(css: &str) -> Self {
+            parse_declarations(css)
+        }
+    }
+
+    impl From<String> for Styles {
+        fn from(css: String) -> Self {
+            parse_declarations(&css)
+        }
+    }
+
+    impl From<&String> for Styles {
+        fn from(css: &String) -> Self {
+            parse_declarations(css)
+        }
+    }
+
+    impl From<&Styles> for Styles {
+        fn from(styles: &Styles) -> Self {
+            styles.clone()
+        }
+    }
+
+    impl<N: Into<String>, V: Into<String>, const LEN: usize> From<[(N, V); LEN]> for Styles {
+        fn from(properties: [(N, V); LEN]) -> Self {
+            IntoIterator::into_iter(properties).collect()
+        }
+    }
+
+    impl<N: Into<String>, V: Into<String>> From<Vec<(N, V)>> for Styles {
+        fn from(properties: Vec<(N, V)>) -> Self {
+            properties.into_iter().collect()
+        }
+    }
+}
+
+impl<N: Into<String>, V: Into<String>> FromIterator<(N, V)> for Styles {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut styles = Styles::new();
+        for (name, value) in iter {
+            styles.insert(name, value);
+        }
+        styles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that we parse declarations, ignoring separators inside of quotes and parentheses.
+    #[test]
+    fn parse_style_attribute() {
+        let styles = Styles::from(
+            r#" color : red;; background: url("a;b:c.png") no-repeat; content: ';'; invalid "#,
+        );
+
+        assert_eq!(
+            styles.iter().collect::<Vec<_>>(),
+            vec![
+                ("color", "red"),
+                ("background", r#"url("a;b:c.png") no-repeat"#),
+                ("content", "';'"),
+            ]
+        );
+    }
+
+    /// Verify that inserting an existing property replaces its value in place.
+    #[test]
+    fn insert_existing_property() {
+        let mut styles = Styles::from([("margin", "0"), ("color", "red")]);
+        styles.insert("margin", "4px");
+
+        assert_eq!(styles.to_string(), "margin: 4px; color: red");
+    }
+
+    #[test]
+    fn style_priority() {
+        assert_eq!(
+            Styles::split_priority("red !important"),
+            ("red", "important")
+        );
+        assert_eq!(
+            Styles::split_priority("red!IMPORTANT "),
+            ("red", "important")
+        );
+        assert_eq!(Styles::split_priority("red"), ("red", ""));
+    }
+}
@@ -0,0 +1,184 @@
+use std::cell::{Cell, OnceCell};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::VirtualNode;
+
+/// A node whose view is only rendered and diffed when its hash changes.
+///
+/// When the memo's hash is the same as the hash of the memo that it is being diffed against, the
+/// memo's view is not rendered and the old memo's DOM node and events are kept as they are.
+///
+/// A memo's view places a single node into the DOM, so it cannot be a fragment.
+///
+/// Created using [`VirtualNode::memo`].
+pub struct VMemo {
+    hash: u64,
+    view: OnceCell<Rc<VirtualNode>>,
+    render: Cell<Option<Box<dyn FnOnce() -> VirtualNode>>>,
+    reused: Cell<bool>,
+}
+
+impl VMemo {
+    /// Create a memo that renders its view using the `render` function, but only once the view is
+    /// needed.
+    ///
+    /// The `key` should hash the same way whenever the view would render the same nodes, such as
+    /// a version number of the data that the view is rendered from.
+    pub fn new<K, F>(key: K, render: F) -> Self
+    where
+        K: Hash,
+        F: FnOnce() -> VirtualNode + 'static,
+    {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+
+        VMemo {
+            hash: hasher.finish(),
+            view: OnceCell::new(),
+            render: Cell::new(Some(Box::new(render))),
+            reused: Cell::new(false),
+        }
+    }
+
+    /// The hash of the key that the memo was created with.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The memo's view, which gets rendered the first time that it is needed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the view is a fragment.
+    pub fn view(&self) -> &VirtualNode {
+        self.view.get_or_init(|| {
+            let render = self.render.take().unwrap();
+            let view = render();
+
+            assert!(
+                view.as_fragment_ref().is_none(),
+                "A memo's view must be a single node, not a fragment."
+            );
+
+            Rc::new(view)
+        })
+    }
+
+    /// A mutable reference to the memo's view, which gets rendered if it has not been rendered
+    /// yet.
+    ///
+    /// Returns `None` if the view was reused by another memo, since they share it.
+    pub(crate) fn view_mut(&mut self) -> Option<&mut VirtualNode> {
+        self.view();
+        Rc::get_mut(self.view.get_mut().unwrap())
+    }
+
+    /// Whether or not the memo's view has been rendered or reused.
+    pub fn is_rendered(&self) -> bool {
+        self.view.get().is_some()
+    }
+
+    /// Use the `old` memo's view instead of rendering this memo's view, if both memos have the
+    /// same hash and the `old` memo's view was already rendered.
+    ///
+    /// Returns true if the view was reused.
+    pub fn reuse_view_of(&self, old: &VMemo) -> bool {
+        if self.hash != old.hash || self.is_rendered() {
+            return false;
+        }
+        let Some(old_view) = old.view.get() else {
+            return false;
+        };
+
+        self.view.set(old_view.clone()).unwrap();
+        self.render.take();
+        self.reused.set(true);
+
+        true
+    }
+
+    /// Whether or not the memo's view was reused from the memo that it was diffed against, in
+    /// which case its DOM node and events were kept as they are.
+    pub fn is_reused(&self) -> bool {
+        self.reused.get()
+    }
+}
+
+/// Two memos are equal if they have the same hash, since they would render the same view.
+impl PartialEq for VMemo {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.hash == rhs.hash
+    }
+}
+
+impl fmt::Debug for VMemo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VMemo")
+            .field("hash", &self.hash)
+            .field("view", &self.view.get())
+            .finish()
+    }
+}
+
+// Turn a VMemo into an HTML string.
+impl fmt::Display for VMemo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.view())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_lazily() {
+        let renders = Rc::new(Cell::new(0));
+
+        let memo = {
+            let renders = renders.clone();
+            VMemo::new(1, move || {
+                renders.set(renders.get() + 1);
+                VirtualNode::element("div")
+            })
+        };
+        assert_eq!(renders.get(), 0);
+
+        assert_eq!(memo.to_string(), "<div></div>");
+        assert_eq!(memo.to_string(), "<div></div>");
+        assert_eq!(renders.get(), 1);
+    }
+
+    #[test]
+    fn reuses_view_with_same_hash() {
+        let old = VMemo::new("rows-v1", || VirtualNode::element("table"));
+        old.view();
+
+        let new = VMemo::new("rows-v1", || unreachable!());
+        assert!(new.reuse_view_of(&old));
+
+        assert!(new.is_reused());
+        assert_eq!(new.to_string(), "<table></table>");
+    }
+
+    #[test]
+    fn does_not_reuse_view_with_different_hash() {
+        let old = VMemo::new("rows-v1", || VirtualNode::element("table"));
+        old.view();
+
+        let new = VMemo::new("rows-v2", || VirtualNode::element("ul"));
+        assert!(!new.reuse_view_of(&old));
+
+        assert!(!new.is_reused());
+        assert_eq!(new.to_string(), "<ul></ul>");
+    }
+
+    #[test]
+    #[should_panic]
+    fn fragment_view() {
+        VMemo::new((), || VirtualNode::Fragment(vec![])).view();
+    }
+}
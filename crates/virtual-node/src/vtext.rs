@@ -1,6 +1,9 @@
 use std::fmt;
 
+#[cfg(target_arch = "wasm32")]
 use crate::create_element::set_virtual_node_marker;
+use crate::escape_html::EscapeHtml;
+#[cfg(target_arch = "wasm32")]
 use web_sys::Text;
 
 /// Represents a text node
@@ -20,6 +23,7 @@ impl VText {
 
     /// Return a `Text` element from a `VirtualNode`, typically right before adding it
     /// into the DOM.
+    #[cfg(target_arch = "wasm32")]
     pub(crate) fn create_text_node(&self) -> Text {
         let document = web_sys::window().unwrap().document().unwrap();
         let text = document.create_text_node(&self.text);
@@ -30,6 +34,14 @@ impl VText {
     }
 }
 
+/// The text of the comments that separate neighboring text nodes, so that the browser does not
+/// merge them into one text node.
+///
+/// `ptns` = Percy text node separator. It is namespaced so that it does not get mistaken for a
+/// comment from a `VirtualNode::Comment`.
+#[doc(hidden)]
+pub const TEXT_SEPARATOR: &str = "percy:ptns";
+
 impl From<&str> for VText {
     fn from(text: &str) -> Self {
         VText {
@@ -50,9 +62,25 @@ impl fmt::Debug for VText {
     }
 }
 
-// Turn a VText into an HTML string
+// Turn a VText into an HTML string, escaping any characters that have special meaning in HTML.
 impl fmt::Display for VText {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.text)
+        write!(f, "{}", EscapeHtml(&self.text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verify that text that looks like HTML gets escaped so that it cannot create elements.
+    #[test]
+    fn escapes_text() {
+        let text = VText::new("<script>alert('hi') && 1 > 0</script>");
+
+        assert_eq!(
+            text.to_string(),
+            "&lt;script&gt;alert(&#39;hi&#39;) &amp;&amp; 1 &gt; 0&lt;/script&gt;"
+        );
     }
 }
@@ -1,5 +1,7 @@
 //! Used to run the component-preview-app in a browser.
 
+#![cfg(target_arch = "wasm32")]
+
 #![deny(missing_docs)]
 
 #[macro_use]
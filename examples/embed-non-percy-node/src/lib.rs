@@ -1,3 +1,5 @@
+#![cfg(target_arch = "wasm32")]
+
 use percy_dom::{event::VirtualEvents, prelude::*, JsCast};
 use web_sys::{Element, Node};
 
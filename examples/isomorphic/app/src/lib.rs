@@ -6,15 +6,18 @@ pub use percy_dom::VirtualNode;
 use percy_router::prelude::*;
 use std::cell::RefCell;
 use std::rc::Rc;
-use wasm_bindgen;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsCast;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
 mod state;
 mod store;
 mod views;
 
+#[cfg(target_arch = "wasm32")]
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_name = "downloadJson")]
@@ -77,6 +80,7 @@ fn contributors_route(store: Provided<Rc<RefCell<Store>>>) -> VirtualNode {
     ContributorsView::new(Rc::clone(&store)).render()
 }
 
+#[cfg(target_arch = "wasm32")]
 fn download_contributors_json(store: Provided<Rc<RefCell<Store>>>) {
     // In order to check if the download has already been initiated, we must
     // wrap the possibility of a download attempt in a closure and pass it to
@@ -115,6 +119,10 @@ fn download_contributors_json(store: Provided<Rc<RefCell<Store>>>) {
 
 // @book end on-visit-example
 
+// The contributors are downloaded by the browser, so there is nothing to do on the server.
+#[cfg(not(target_arch = "wasm32"))]
+fn download_contributors_json(_store: Provided<Rc<RefCell<Store>>>) {}
+
 fn make_router(store: Rc<RefCell<Store>>) -> Rc<Router> {
     let router = Router::new(create_routes![home_route, contributors_route]);
     router.provide(store);
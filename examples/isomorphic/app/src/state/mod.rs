@@ -40,6 +40,7 @@ impl State {
         match msg {
             Msg::Click => self.increment_click(),
             Msg::SetPath(path) => self.set_path(path.to_string()),
+            #[cfg(target_arch = "wasm32")]
             Msg::SetContributorsJson(json) => {
                 self.contributors = Some(json.into_serde().unwrap());
             }
@@ -1,9 +1,11 @@
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::JsValue;
 
 pub enum Msg {
     Click,
     SetPath(String),
     /// Deserializes JSON array of Github contributors to `Option<Vec<PercyContributor>>`
+    #[cfg(target_arch = "wasm32")]
     SetContributorsJson(JsValue),
     /// Represents whether the client is already fetching the JSON array of Github contributors
     InitiatedContributorsDownload,
@@ -1,3 +1,5 @@
+#![cfg(target_arch = "wasm32")]
+
 use console_error_panic_hook;
 use isomorphic_app;
 use isomorphic_app::Msg;
@@ -69,10 +71,14 @@ impl Client {
         window().set_onpopstate(Some(on_popstate.as_ref().unchecked_ref()));
         on_popstate.forget();
 
+        // The server rendered our app inside of this element, so we hydrate the existing DOM
+        // instead of creating it all over again.
         let root_node = document()
             .get_element_by_id("isomorphic-rust-web-app")
+            .unwrap()
+            .first_element_child()
             .unwrap();
-        let pdom = PercyDom::new_replace_mount(app.render(), root_node);
+        let pdom = PercyDom::hydrate(app.render(), root_node);
 
         let store = Rc::clone(&app.store);
         intercept_relative_links(store);
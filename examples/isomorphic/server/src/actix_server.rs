@@ -3,13 +3,18 @@ use actix_rt::System;
 use actix_web::{
     web, App as ActixApp, FromRequest, HttpRequest, HttpResponse, HttpServer, Responder,
 };
+use percy_dom::prelude::*;
+use percy_dom::ssr::DocumentShell;
 use serde::Deserialize;
+use std::convert::Infallible;
 
 use isomorphic_app::App;
 
-const HTML_PLACEHOLDER: &str = "#HTML_INSERTED_HERE_BY_SERVER#";
 const STATE_PLACEHOLDER: &str = "#INITIAL_STATE_JSON#";
 
+// The size of each chunk of HTML that we stream to the client.
+const CHUNK_SIZE: usize = 8 * 1024;
+
 #[derive(Deserialize, Debug)]
 struct RequestQuery {
     init: Option<u32>,
@@ -35,10 +40,30 @@ fn respond(path: String, init: Option<u32>) -> impl Responder {
     let app = App::new(init.unwrap_or(1001), path);
     let state = app.store.borrow();
 
-    let html = format!("{}", include_str!("./index.html"));
-    let html = html.replacen(HTML_PLACEHOLDER, &app.render().to_string(), 1);
-    let html = html.replacen(STATE_PLACEHOLDER, &state.to_json(), 1);
-    HttpResponse::Ok().content_type("text/html").body(html)
+    let mut shell = DocumentShell::default();
+    shell.html_attrs.insert("lang".to_string(), "en".into());
+    shell.head = include_str!("./head.html").to_string();
+    shell.body_attrs.insert(
+        "style".to_string(),
+        "margin: 0; padding: 0; width: 100%; height: 100%;".into(),
+    );
+    shell.body_end =
+        include_str!("./body_end.html").replacen(STATE_PLACEHOLDER, &state.to_json(), 1);
+
+    let body = html! {
+      <div id="isomorphic-rust-web-app" style="width: 100%; height: 100%;">
+        { app.render() }
+      </div>
+    };
+
+    // Stream the HTML to the client as it gets rendered.
+    let chunks = shell
+        .render_to_chunks(body, CHUNK_SIZE)
+        .map(|chunk| Ok::<_, Infallible>(web::Bytes::from(chunk)));
+
+    HttpResponse::Ok()
+        .content_type("text/html")
+        .streaming(futures_util::stream::iter(chunks))
 }
 
 pub async fn serve(static_files: String) {
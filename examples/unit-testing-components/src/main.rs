@@ -1,4 +1,5 @@
 use percy_dom::prelude::*;
+use std::rc::Rc;
 
 fn main() {
     println!("To see this example in action:");
@@ -40,39 +41,54 @@ fn water_bottle_view(percent_full: f32) -> VirtualNode {
     }
 }
 
+#[allow(unused)]
+#[derive(Debug, PartialEq)]
+enum Msg {
+    Refill,
+}
+
+#[allow(unused)]
+fn refill_button(send: Rc<dyn Fn(Msg)>) -> VirtualNode {
+    html! {
+        <div>
+          <button class="refill" onclick=move |_event| send(Msg::Refill)>
+            Refill
+          </button>
+        </div>
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
 
     #[test]
     fn conditional_water_messaging() {
-        assert_eq!(
-            water_bottle_view(0.7)
-                .children_recursive()
-                .iter()
-                .filter(|v| {
-                    if let Some(elem) = v.as_velement_ref() {
-                        return elem.attrs.get("id") == Some(&"full-water".into());
-                    }
-
-                    false
-                })
-                .collect::<Vec<_>>()
-                .len(),
-            1
-        );
+        let full_view = water_bottle_view(0.7);
+        assert!(full_view.query_selector("#full-water").is_some());
 
         let water_view = water_bottle_view(0.2587);
-
+        assert!(water_view.query_selector("#full-water").is_none());
         assert_eq!(
-            water_view
-                .as_velement_ref()
-                .expect("Not an element node")
-                .children[0]
-                .as_vtext_ref()
-                .expect("Not a text node")
-                .text,
+            water_view.text_content().trim(),
             "Please fill me up :( I am only 0.2587 percent full :("
-        )
+        );
+    }
+
+    #[test]
+    fn full_water_bottle_snapshot() {
+        assert_vnode_snapshot!("full_water_bottle", water_bottle_view(0.7));
+    }
+
+    #[test]
+    fn refill_button_sends_refill_msg() {
+        let sent = Rc::new(RefCell::new(vec![]));
+        let sent_clone = sent.clone();
+        let view = refill_button(Rc::new(move |msg| sent_clone.borrow_mut().push(msg)));
+
+        view.click("button.refill");
+
+        assert_eq!(*sent.borrow(), vec![Msg::Refill]);
     }
 }
@@ -3,5 +3,7 @@
 cd $(git rev-parse --show-toplevel)
 
 cargo check --all && # Make sure examples compile
+cargo build -p isomorphic-server && # Make sure server side rendering builds for non wasm32 targets
+! cargo tree -p isomorphic-server -e normal | grep -E "web-sys|js-sys|wasm-bindgen" && # ..without any browser dependencies
 cargo test --all &&
 wasm-pack test --firefox --headless crates/percy-dom